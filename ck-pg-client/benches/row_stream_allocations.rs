@@ -0,0 +1,143 @@
+//! Compare the allocations made by [`PgClient::row_stream_raw`]
+//! and [`PgClient::query_raw`] when receiving many rows.
+//!
+//! This benchmark installs a counting global allocator,
+//! so it lives in its own target rather than in the test binary.
+//! Run it with `cargo bench --bench row_stream_allocations`.
+
+#![feature(exit_status_error)]
+
+use {
+    ck_pg_client::{
+        ConnectionOptions,
+        PgClient,
+        Sslmode,
+        capabilities::{Md5Unavailable, SslUnavailable},
+        protocol::FormatCode,
+    },
+    self::with_cluster::{WithCluster, with_cluster},
+    std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+    },
+};
+
+#[path = "../src/tests/with_cluster.rs"]
+mod with_cluster;
+
+const ROWS: usize = 100_000;
+
+fn main()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        let query = "SELECT x, x::text FROM generate_series(1, $1) AS x";
+        let rows = ROWS.to_string();
+        let parameter_values = [Some(rows.as_bytes())];
+
+        let (sum, owned_allocations) = count_allocations(|| {
+            let result = client.query_raw(query, &[23], &parameter_values,
+                                          &[], &[FormatCode::Binary])
+                .unwrap();
+            result.rows.iter()
+                .map(|row| row[0].as_deref().unwrap())
+                .map(|value| i32::from_be_bytes(value.try_into().unwrap()))
+                .map(i64::from)
+                .sum::<i64>()
+        });
+        assert_eq!(sum, (1 ..= ROWS as i64).sum::<i64>());
+
+        let (sum, stream_allocations) = count_allocations(|| {
+            let mut stream = client.row_stream_raw(
+                query, &[23], &parameter_values, &[], &[FormatCode::Binary],
+            ).unwrap();
+            let mut sum = 0;
+            while let Some(row) = stream.next_row().unwrap() {
+                let value = row.values().next().unwrap().unwrap();
+                sum += i64::from(i32::from_be_bytes(value.try_into().unwrap()));
+            }
+            sum
+        });
+        assert_eq!(sum, (1 ..= ROWS as i64).sum::<i64>());
+
+        // The owned API allocates a row and its values for every row,
+        // whereas the row stream allocates only for setting up the query.
+        assert!(owned_allocations >= 3 * ROWS,
+                "query_raw made {owned_allocations} allocations");
+        assert!(stream_allocations < 100,
+                "row_stream_raw made {stream_allocations} allocations");
+    });
+}
+
+/// Connect to a cluster spawned by [`with_cluster`] without SSL.
+fn connect(port: u16) -> PgClient
+{
+    let options = ConnectionOptions{
+        host: "localhost".into(),
+        port,
+        dbname: "postgres".into(),
+        user: "postgres".into(),
+        password: None,
+        sslmode: Sslmode::Disable,
+    };
+
+    PgClient::connect(&Md5Unavailable, &SslUnavailable, |_| (), &options)
+        .unwrap()
+}
+
+thread_local! {
+    /// Number of allocations made by the current thread.
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Global allocator that counts allocations.
+struct CountingAllocator;
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator
+{
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8
+    {
+        count_allocation();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8
+    {
+        count_allocation();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize)
+        -> *mut u8
+    {
+        count_allocation();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout)
+    {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn count_allocation()
+{
+    // The thread-local may already be destroyed during thread exit.
+    let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+}
+
+/// Call `f` and count the allocations it makes on the current thread.
+///
+/// Reallocations are counted as allocations.
+fn count_allocations<F, R>(f: F) -> (R, usize)
+    where F: FnOnce() -> R
+{
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let after = ALLOCATIONS.with(Cell::get);
+    (result, after - before)
+}
@@ -1,4 +1,8 @@
-use {std::{error, io}, thiserror::Error};
+use {
+    crate::{protocol::ErrorNoticeFieldArray, types::Type},
+    std::{error, ffi::{CStr, CString}, fmt, io, num::NonZeroU8},
+    thiserror::Error,
+};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -11,6 +15,9 @@ pub enum Error
     #[error("frontend message: string field contains nul")]
     Nul,
 
+    #[error("frontend message: too long")]
+    FrontendMessageTooLong,
+
     #[error("backend message: invalid syntax")]
     BackendMessageParse,
 
@@ -25,4 +32,106 @@ pub enum Error
 
     #[error("SSL: handshake: {0}")]
     SslHandshake(Box<dyn error::Error + Send + Sync>),
+
+    #[error("row: no such column: {0}")]
+    NoSuchColumn(String),
+
+    #[error("query: expected {expected} parameters, got {actual}")]
+    ParameterCount{
+        /// The number of parameters of the statement.
+        expected: usize,
+        /// The number of parameters given.
+        actual: usize,
+    },
+
+    #[error("types: cannot convert between Rust type {rust_type} \
+             and PostgreSQL type {pg_type}")]
+    WrongType{
+        /// The name of the Rust type.
+        rust_type: &'static str,
+        /// The PostgreSQL type.
+        pg_type: Type,
+    },
+
+    #[error("types: no such type: {0}")]
+    NoSuchType(String),
+
+    #[error("types: unexpected NULL")]
+    UnexpectedNull,
+
+    #[error("types: {0}")]
+    Conversion(Box<dyn error::Error + Send + Sync>),
+
+    #[error("portal: not in a transaction block")]
+    NotInTransactionBlock,
+
+    #[error("query: COPY from or to the client is not supported")]
+    CopyUnsupported,
+
+    #[error("ErrorResponse: {0}")]
+    ErrorResponse(Box<ErrorResponse>),
+}
+
+/// Owned copy of the fields of an `ErrorResponse` message.
+///
+/// The fields are kept in the order in which the server sent them.
+/// Accessors are provided for the fields that are always present.
+#[derive(Clone, Debug)]
+pub struct ErrorResponse
+{
+    /// The type codes and values of the fields.
+    pub fields: Vec<(NonZeroU8, CString)>,
+}
+
+impl ErrorResponse
+{
+    /// Copy the fields of an `ErrorResponse` message.
+    pub fn from_fields(fields: ErrorNoticeFieldArray) -> Self
+    {
+        let fields = fields
+            .map(|field| (field.type_code, field.value.to_owned()))
+            .collect();
+        Self{fields}
+    }
+
+    /// The value of the first field with the given type code, if any.
+    pub fn field(&self, type_code: u8) -> Option<&CStr>
+    {
+        self.fields.iter()
+            .find(|(c, _)| c.get() == type_code)
+            .map(|(_, value)| value.as_c_str())
+    }
+
+    /// The non-localized severity, falling back to the localized one.
+    pub fn severity(&self) -> Option<&CStr>
+    {
+        self.field(b'V').or_else(|| self.field(b'S'))
+    }
+
+    /// The SQLSTATE code for the error.
+    pub fn code(&self) -> Option<&CStr>
+    {
+        self.field(b'C')
+    }
+
+    /// The primary human-readable error message.
+    pub fn message(&self) -> Option<&CStr>
+    {
+        self.field(b'M')
+    }
+}
+
+impl fmt::Display for ErrorResponse
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let severity = self.severity().unwrap_or_default();
+        let message = self.message().unwrap_or_default();
+        write!(f, "{}: {}",
+            severity.to_string_lossy(), message.to_string_lossy())?;
+        if let Some(code) = self.code() {
+            write!(f, " (SQLSTATE {})", code.to_string_lossy())?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,255 @@
+use crate::{
+    Column,
+    Error,
+    ErrorResponse,
+    PgClient,
+    QueryResult,
+    Result,
+    protocol::{
+        BackendMessage,
+        FormatCode,
+        write_bind,
+        write_describe,
+        write_execute,
+        write_parse,
+        write_sync,
+    },
+};
+
+/// Description of a statement, as reported by the server.
+#[derive(Clone, Debug, Default)]
+pub struct StatementDescription
+{
+    /// The data type OIDs of the parameters,
+    /// as reported by `ParameterDescription`.
+    pub parameter_type_oids: Vec<u32>,
+
+    /// The columns of the result, as reported by `RowDescription`.
+    ///
+    /// This is empty if the statement does not return rows,
+    /// in which case the server responds with `NoData`.
+    /// As the result formats are not known until the statement
+    /// is bound, all format codes in this description are zero.
+    pub columns: Vec<Column>,
+}
+
+/// Responses to extended query messages, up to `ReadyForQuery`.
+#[derive(Default)]
+pub (crate) struct ExtendedQueryResponses
+{
+    /// Set by `ParseComplete`.
+    pub parse_complete: bool,
+
+    /// Set by `ParameterDescription`.
+    pub parameter_type_oids: Vec<u32>,
+
+    /// Set by `RowDescription`, `DataRow`, and `CommandComplete`.
+    pub result: QueryResult,
+
+    /// Whether `Execute` stopped because it reached its row limit.
+    pub portal_suspended: bool,
+
+    /// Set by `ErrorResponse`.
+    pub error: Option<ErrorResponse>,
+
+    /// Set by `CopyInResponse` and `CopyOutResponse`.
+    pub copy_unsupported: bool,
+}
+
+impl ExtendedQueryResponses
+{
+    /// Convert into [`Err`] if the server reported an error
+    /// or the statement started a COPY.
+    pub fn into_result(self) -> Result<Self>
+    {
+        if self.copy_unsupported {
+            return Err(Error::CopyUnsupported);
+        }
+        match self.error {
+            Some(error) => Err(Error::ErrorResponse(Box::new(error))),
+            None => Ok(self),
+        }
+    }
+}
+
+impl PgClient
+{
+    /// Parse and describe a statement without executing it.
+    ///
+    /// This uses the unnamed prepared statement,
+    /// replacing any unnamed prepared statement that existed before.
+    /// A parameter data type OID of zero leaves the type unspecified,
+    /// in which case the server infers it; the inferred types are
+    /// reported in [`StatementDescription::parameter_type_oids`].
+    /// There may be more parameters than type OIDs given.
+    ///
+    /// See [`query_raw`] for error handling.
+    ///
+    /// [`query_raw`]: `Self::query_raw`
+    pub fn describe(&mut self, query: &str, parameter_type_oids: &[u32])
+        -> Result<StatementDescription>
+    {
+        self.write_messages(|buf| {
+            write_parse(buf, b"", query.as_bytes(), parameter_type_oids)?;
+            write_describe(buf, b'S', b"")?;
+            write_sync(buf);
+            Ok(())
+        })?;
+        self.send()?;
+
+        let responses = self.receive_extended_query_responses()?;
+        let responses = responses.into_result()?;
+
+        Ok(StatementDescription{
+            parameter_type_oids: responses.parameter_type_oids,
+            columns: responses.result.columns,
+        })
+    }
+
+    /// Execute a statement using the [_Extended Query_][spec] flow.
+    ///
+    /// The statement is parsed, bound to the given parameters,
+    /// described, and executed, all in a single round trip.
+    /// The statement is stored in the statement cache, so that
+    /// executing the same query again skips parsing and describing;
+    /// see [`set_statement_cache_capacity`] for details.
+    /// If the cache is disabled, the unnamed prepared statement is used.
+    /// Parameter values are sent separately from the query string,
+    /// so they need not be escaped; `$1`, `$2`, etc. refer to them.
+    ///
+    /// A parameter data type OID of zero leaves the type unspecified,
+    /// in which case the server infers it from the query.
+    /// [`None`] as a parameter value represents SQL NULL.
+    ///
+    /// Both `parameter_format_codes` and `result_format_codes`
+    /// follow the conventions of the `Bind` message:
+    /// if empty, all values use text format;
+    /// if there is one format code, it applies to all values;
+    /// otherwise there must be one format code for each value.
+    /// The format codes of the columns in the result reflect this.
+    ///
+    /// If the server responds with `ErrorResponse`, the remaining
+    /// messages up to `ReadyForQuery` are consumed before returning
+    /// [`Error::ErrorResponse`], so the client remains usable.
+    /// The same goes for statements that copy data from or to the client,
+    /// for which [`Error::CopyUnsupported`] is returned.
+    ///
+    /// [`set_statement_cache_capacity`]: `Self::set_statement_cache_capacity`
+    ///
+    #[doc = crate::pgdoc::extended_query!("spec")]
+    pub fn query_raw(
+        &mut self,
+        query: &str,
+        parameter_type_oids: &[u32],
+        parameter_values: &[Option<&[u8]>],
+        parameter_format_codes: &[FormatCode],
+        result_format_codes: &[FormatCode],
+    ) -> Result<QueryResult>
+    {
+        if self.statement_cache_capacity() != 0 {
+            return self.query_cached(
+                query,
+                parameter_type_oids,
+                parameter_values,
+                parameter_format_codes,
+                result_format_codes,
+            );
+        }
+
+        self.write_messages(|buf| {
+            write_parse(buf, b"", query.as_bytes(), parameter_type_oids)?;
+            write_bind(
+                buf,
+                b"",
+                b"",
+                parameter_format_codes,
+                parameter_values.len(),
+                |i, buf| write_raw_parameter_value(buf, parameter_values[i]),
+                result_format_codes,
+            )?;
+            write_describe(buf, b'P', b"")?;
+            write_execute(buf, b"", 0)?;
+            write_sync(buf);
+            Ok(())
+        })?;
+        self.send()?;
+
+        let responses = self.receive_extended_query_responses()?;
+        Ok(responses.into_result()?.result)
+    }
+
+    /// Receive responses to extended query messages.
+    ///
+    /// Messages are received up to and including `ReadyForQuery`.
+    /// When the server reports an error, it discards all messages
+    /// up to the next `Sync`, so receiving up to `ReadyForQuery`
+    /// brings the connection back in a usable state.
+    /// A COPY started by the statement is abandoned;
+    /// see [`abandon_copy`][`Self::abandon_copy`].
+    pub (crate) fn receive_extended_query_responses(&mut self)
+        -> Result<ExtendedQueryResponses>
+    {
+        let mut responses = ExtendedQueryResponses::default();
+
+        loop {
+            match self.receive()? {
+                BackendMessage::ParseComplete =>
+                    responses.parse_complete = true,
+                BackendMessage::BindComplete
+                | BackendMessage::NoData
+                | BackendMessage::EmptyQueryResponse =>
+                    (),
+                BackendMessage::ParameterDescription{data_type_oids} =>
+                    responses.parameter_type_oids = data_type_oids.collect(),
+                BackendMessage::RowDescription{fields} =>
+                    responses.result.columns =
+                        fields.map(Column::from).collect(),
+                BackendMessage::DataRow{column_values} => {
+                    let row = column_values
+                        .map(|value| value.map(<[u8]>::to_vec))
+                        .collect();
+                    responses.result.rows.push(row);
+                },
+                BackendMessage::CommandComplete{command_tag} =>
+                    responses.result.command_tag =
+                        Some(command_tag.to_owned()),
+                BackendMessage::PortalSuspended =>
+                    responses.portal_suspended = true,
+                BackendMessage::ErrorResponse{fields} =>
+                    responses.error = Some(ErrorResponse::from_fields(fields)),
+                BackendMessage::CopyInResponse{..} => {
+                    self.abandon_copy(true, true, 0)?;
+                    responses.copy_unsupported = true;
+                    break;
+                },
+                BackendMessage::CopyOutResponse{..} => {
+                    self.abandon_copy(false, false, 1)?;
+                    responses.copy_unsupported = true;
+                    break;
+                },
+                BackendMessage::ReadyForQuery{..} =>
+                    break,
+                _ =>
+                    return Err(Error::BackendMessageUnexpected),
+            }
+        }
+
+        responses.result.session_parameters = self.session_parameters.clone();
+
+        Ok(responses)
+    }
+}
+
+/// Write a raw parameter value for [`write_bind`].
+pub (crate) fn write_raw_parameter_value(buf: &mut Vec<u8>, value: Option<&[u8]>)
+    -> Result<bool>
+{
+    match value {
+        Some(value) => {
+            buf.extend_from_slice(value);
+            Ok(false)
+        },
+        None =>
+            Ok(true),
+    }
+}
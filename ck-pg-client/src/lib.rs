@@ -4,21 +4,58 @@
 //!
 //! The following [Cargo features] can be specified:
 //!
+//! ## bigdecimal
+//!
+//! Convert `numeric` values to and from `BigDecimal`
+//! from the **bigdecimal** crate, in addition to [`Numeric`].
+//!
+//! ## chrono
+//!
+//! Convert date and time values to and from the types
+//! of the **chrono** crate, in addition to the types in [`types`].
+//!
 //! ## md5
 //!
 //! Use the **md5** crate for `md5` password authentication.
 //! If you wish to use another MD5 implementation,
 //! you can implement the [`Md5`] trait yourself.
 //!
+//! ## postgis
+//!
+//! Convert `geometry` and `geography` values from the **postgis**
+//! extension to and from `Geometry`.
+//!
 //! ## rustls
 //!
 //! Use the **rustls** crate for SSL session encryption.
 //! If you wish to use another SSL implementation,
 //! you can implement the [`Ssl`] trait yourself.
 //!
+//! ## rust_decimal
+//!
+//! Convert `numeric` values to and from `Decimal`
+//! from the **rust_decimal** crate, in addition to [`Numeric`].
+//!
+//! ## serde_json
+//!
+//! Convert `json` and `jsonb` values to and from `Value`
+//! from the **serde_json** crate, and to and from any type
+//! that implements `Serialize` or `Deserialize` using `Json`.
+//!
+//! ## time
+//!
+//! Convert date and time values to and from the types
+//! of the **time** crate, in addition to the types in [`types`].
+//!
+//! ## uuid
+//!
+//! Convert `uuid` values to and from `Uuid` from the **uuid** crate,
+//! in addition to [`Uuid`].
+//!
 //! # Unsupported protocol features
 //!
 //!  - Streaming replication protocol.
+//!  - `COPY FROM STDIN` and `COPY TO STDOUT`.
 //!  - Kerberos V5 authentication.
 //!  - GSSAPI session encryption.
 //!
@@ -30,7 +67,9 @@
 //! (This is why we refer to TLS as SSL, for example.)
 //!
 //! [`Md5`]: `capabilities::Md5`
+//! [`Numeric`]: `types::Numeric`
 //! [`Ssl`]: `capabilities::Ssl`
+//! [`Uuid`]: `types::Uuid`
 //! [Cargo features]: https://doc.rust-lang.org/cargo/reference/features.html
 
 // This crate implements a network protocol and deals with untrusted data.
@@ -47,22 +86,56 @@
 
 #[macro_use] mod pgdoc;
 
-pub use self::error::*;
+pub use self::{
+    error::*,
+    extended_query::*,
+    pipeline::*,
+    portal::*,
+    query_result::*,
+    row::*,
+    row_stream::*,
+    simple_query::*,
+    statement_cache::*,
+};
 
 use {
     crate::{
         connectivity::{Socket, unix_socket_path},
-        protocol::{ErrorNoticeFieldArray, Receiver},
+        protocol::{
+            BackendMessage,
+            ErrorNoticeFieldArray,
+            Receiver,
+            write_close,
+            write_copy_fail,
+            write_sync,
+        },
+        types::{SessionParameters, Type},
+    },
+    std::{
+        collections::HashMap,
+        ffi::{CStr, CString},
+        io::{Read, Write},
+        sync::Arc,
     },
-    std::io::{Read, Write},
 };
 
 pub mod capabilities;
 pub mod connectivity;
 pub mod protocol;
+pub mod types;
 
 mod error;
+mod extended_query;
+mod pipeline;
+mod portal;
+mod query_result;
+mod row;
+mod row_stream;
+mod simple_query;
+mod statement_cache;
 mod tests;
+mod type_lookup;
+mod typed_query;
 mod usize_conversions;
 
 /// Open connection to a database.
@@ -72,6 +145,45 @@ pub struct PgClient
     transport: Box<dyn Transport>,
 
     receiver: Receiver,
+
+    /// Buffer for serializing frontend messages into.
+    ///
+    /// Like the buffer in [`Receiver`], this is reused across requests.
+    send_buf: Vec<u8>,
+
+    /// Parameter statuses reported by the server.
+    ///
+    /// These are initially discovered during the start-up flow,
+    /// and updated whenever a `ParameterStatus` message is received.
+    parameter_statuses: HashMap<CString, CString>,
+
+    /// The parameter statuses that affect the representation of values.
+    ///
+    /// This is shared with query results, and replaced rather than
+    /// modified when a relevant `ParameterStatus` message is received.
+    session_parameters: Arc<SessionParameters>,
+
+    /// Transaction status indicator of the most recent `ReadyForQuery`.
+    transaction_status_indicator: u8,
+
+    /// Number of `Close` messages whose `CloseComplete` is outstanding.
+    ///
+    /// `Close` messages are not sent immediately, but are written
+    /// to the send buffer to be sent along with the next request.
+    /// The corresponding `CloseComplete` messages are skipped.
+    pending_close_completes: usize,
+
+    /// Counter used for generating prepared statement names.
+    next_statement_id: u64,
+
+    /// Counter used for generating portal names.
+    next_portal_id: u64,
+
+    statement_cache: StatementCache,
+
+    /// Types that are not built in, by OID,
+    /// as found by [`lookup_type`][`Self::lookup_type`].
+    types: HashMap<u32, Type>,
 }
 
 trait Transport: Read + Write + Send { }
@@ -101,7 +213,7 @@ impl PgClient
 
         let mut receiver = Receiver::new(on_notice);
 
-        protocol::startup(
+        let startup_info = protocol::startup(
             md5,
             &mut receiver,
             &mut transport,
@@ -109,7 +221,194 @@ impl PgClient
             &options.dbname,
         )?;
 
-        Ok(Self{transport, receiver})
+        let mut session_parameters = SessionParameters::default();
+        for (name, value) in &startup_info.parameter_statuses {
+            session_parameters.set(name.to_bytes(), value.to_bytes());
+        }
+
+        Ok(Self{
+            transport,
+            receiver,
+            send_buf: Vec::new(),
+            parameter_statuses: startup_info.parameter_statuses,
+            session_parameters: Arc::new(session_parameters),
+            transaction_status_indicator: b'I',
+            pending_close_completes: 0,
+            next_statement_id: 0,
+            next_portal_id: 0,
+            statement_cache: StatementCache::new(),
+            types: HashMap::new(),
+        })
+    }
+
+    /// The current value of a parameter reported by the server.
+    ///
+    /// The server reports certain parameters, such as `server_version`
+    /// and `DateStyle`, during start-up and whenever they change.
+    pub fn parameter_status(&self, name: &CStr) -> Option<&CStr>
+    {
+        self.parameter_statuses.get(name).map(CString::as_c_str)
+    }
+
+    /// The current values of the parameters that affect
+    /// the representation of values.
+    pub fn session_parameters(&self) -> &Arc<SessionParameters>
+    {
+        &self.session_parameters
+    }
+
+    /// The transaction status indicator of the most recent `ReadyForQuery`.
+    ///
+    /// This is `b'I'` if idle (not in a transaction block),
+    /// `b'T'` if in a transaction block, or `b'E'` if in
+    /// a failed transaction block (queries will be rejected
+    /// until the transaction block is ended).
+    pub fn transaction_status_indicator(&self) -> u8
+    {
+        self.transaction_status_indicator
+    }
+
+    /// Append frontend messages to the send buffer.
+    ///
+    /// If `f` fails, the send buffer is restored to its original length,
+    /// so that no partial request is sent along with a later request.
+    fn write_messages<F>(&mut self, f: F) -> Result<()>
+        where F: FnOnce(&mut Vec<u8>) -> Result<()>
+    {
+        let start = self.send_buf.len();
+        let result = f(&mut self.send_buf);
+        if result.is_err() {
+            self.send_buf.truncate(start);
+        }
+        result
+    }
+
+    /// Write a `Close` message to the send buffer.
+    ///
+    /// The message is sent along with the next request,
+    /// and the resulting `CloseComplete` is skipped by [`receive`].
+    ///
+    /// [`receive`]: `Self::receive`
+    fn close_later(&mut self, kind: u8, name: &[u8]) -> Result<()>
+    {
+        write_close(&mut self.send_buf, kind, name)?;
+        self.pending_close_completes += 1;
+        Ok(())
+    }
+
+    /// Write the contents of the send buffer to the transport.
+    ///
+    /// The send buffer is cleared afterwards, even if writing fails.
+    fn send(&mut self) -> Result<()>
+    {
+        let result = self.transport.write_all(&self.send_buf);
+        self.send_buf.clear();
+        result?;
+        self.transport.flush()?;
+        Ok(())
+    }
+
+    /// Abandon a COPY from or to the client, which is not supported.
+    ///
+    /// This is called upon receiving `CopyInResponse` (if `copy_in`)
+    /// or `CopyOutResponse`. A `COPY FROM STDIN` is failed with
+    /// `CopyFail`, and the data sent by a `COPY TO STDOUT` is discarded.
+    /// Messages are discarded up to and including the
+    /// `ready_for_query`th `ReadyForQuery`, so that the client
+    /// remains usable; the caller then returns [`Error::CopyUnsupported`].
+    ///
+    /// If `sync` is set, a `Sync` is sent as well, and `ReadyForQuery`
+    /// in response to it is also waited for. This is needed in the
+    /// extended query flow, as the server ignores `Sync` while it
+    /// waits for data, so a `Sync` sent directly after the `Execute`
+    /// that started the COPY produces no `ReadyForQuery`.
+    fn abandon_copy(&mut self, copy_in: bool, sync: bool,
+                    ready_for_query: usize) -> Result<()>
+    {
+        if copy_in {
+            write_copy_fail(&mut self.send_buf,
+                            b"COPY from or to the client is not supported")?;
+        }
+        if sync {
+            write_sync(&mut self.send_buf);
+        }
+        if !self.send_buf.is_empty() {
+            self.send()?;
+        }
+
+        for _ in 0 .. ready_for_query + usize::from(sync) {
+            while self.receive_unparsed()? != b'Z' {
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Receive a backend message, handling asynchronous messages.
+    ///
+    /// `ParameterStatus` messages may arrive at any time;
+    /// they are recorded and not returned to the caller.
+    /// `CloseComplete` messages in response to [`close_later`]
+    /// are likewise not returned to the caller.
+    /// The transaction status indicator of `ReadyForQuery`
+    /// is recorded before the message is returned.
+    ///
+    /// [`close_later`]: `Self::close_later`
+    fn receive(&mut self) -> Result<BackendMessage<'_>>
+    {
+        self.receive_unparsed()?;
+        self.receiver.parse()
+    }
+
+    /// Like [`receive`], but without parsing the message.
+    ///
+    /// Returns the identifier of the message, which can subsequently
+    /// be parsed using [`Receiver::parse`]. See [`Receiver::receive_unparsed`]
+    /// for why this is useful.
+    ///
+    /// [`receive`]: `Self::receive`
+    fn receive_unparsed(&mut self) -> Result<u8>
+    {
+        loop {
+            let identifier =
+                self.receiver.receive_unparsed(&mut self.transport)?;
+            match identifier {
+                b'S' => {
+                    let message = self.receiver.parse()?;
+                    if let BackendMessage::ParameterStatus{
+                        name,
+                        current_value,
+                    } = message {
+                        let mut session_parameters =
+                            (*self.session_parameters).clone();
+                        session_parameters.set(name.to_bytes(),
+                                               current_value.to_bytes());
+                        if session_parameters != *self.session_parameters {
+                            self.session_parameters =
+                                Arc::new(session_parameters);
+                        }
+
+                        let name = name.to_owned();
+                        let current_value = current_value.to_owned();
+                        self.parameter_statuses.insert(name, current_value);
+                    }
+                },
+                b'3' if self.pending_close_completes > 0 =>
+                    self.pending_close_completes -= 1,
+                b'Z' => {
+                    let message = self.receiver.parse()?;
+                    if let BackendMessage::ReadyForQuery{
+                        transaction_status_indicator,
+                    } = message {
+                        self.transaction_status_indicator =
+                            transaction_status_indicator;
+                    }
+                    return Ok(identifier);
+                },
+                _ =>
+                    return Ok(identifier),
+            }
+        }
     }
 }
 
@@ -20,10 +20,14 @@ macro_rules! pgdoc
 }
 
 pgdoc!(connection_strings, "/libpq-connect.html#LIBPQ-CONNSTRING");
+pgdoc!(extended_query, "/protocol-flow.html#PROTOCOL-FLOW-EXT-QUERY");
 pgdoc!(frontend_backend_protocol, "/protocol.html");
 pgdoc!(guc_unix_socket_directories, "/runtime-config-connection.html#GUC-UNIX-SOCKET-DIRECTORIES");
 pgdoc!(hostaddr, "/libpq-connect.html#LIBPQ-CONNECT-HOSTADDR");
+pgdoc!(multiple_statements, "/protocol-flow.html#PROTOCOL-FLOW-MULTI-STATEMENT");
 pgdoc!(password_authentication, "/auth-password.html");
+pgdoc!(pipelining, "/protocol-flow.html#PROTOCOL-FLOW-PIPELINING");
+pgdoc!(simple_query, "/protocol-flow.html#id-1.10.5.7.4");
 pgdoc!(ssl_session_encryption, "/protocol-flow.html#id-1.10.5.7.12");
 pgdoc!(sslmode, "/libpq-connect.html#LIBPQ-CONNECT-SSLMODE");
 pgdoc!(startup, "/protocol-flow.html#id-1.10.5.7.3");
@@ -0,0 +1,324 @@
+use {
+    crate::{
+        Column,
+        Error,
+        ErrorResponse,
+        PgClient,
+        QueryResult,
+        Result,
+        Statement,
+        extended_query::write_raw_parameter_value,
+        protocol::{
+            BackendMessage,
+            FormatCode,
+            write_bind,
+            write_describe,
+            write_execute,
+            write_flush,
+            write_parse,
+            write_sync,
+        },
+        statement_cache::result_columns,
+    },
+    std::collections::VecDeque,
+};
+
+/// Queue of extended query messages whose results are read later.
+///
+/// [Pipelining][spec] allows sending many queries without waiting
+/// for the result of each query before sending the next one.
+/// Queries are queued with [`query_raw`] and [`query_prepared`],
+/// and sync points are queued with [`sync`]. The queued messages are
+/// written to the server in one go by [`flush`], or when the first
+/// result is read with [`next_result`]. Results are read in order.
+///
+/// The semantics match those of pipeline mode in libpq.
+/// If a query fails, the server discards the messages up to the next
+/// sync point, so the results of the queries queued after the failed
+/// query and before the next sync point are [`PipelineResult::Aborted`].
+/// Each sync point produces [`PipelineResult::Sync`],
+/// after which execution resumes normally.
+/// Queries queued between two sync points run in the same
+/// implicit transaction, unless they contain transaction control
+/// commands; so an error rolls back the preceding queries as well.
+///
+/// Queries that copy data from or to the client are not supported.
+/// Reading the result of such a query returns [`Error::CopyUnsupported`]
+/// and discards the results of all queued items. The server closes
+/// the connection if a query is queued after a `COPY FROM STDIN`,
+/// as it only accepts data and sync points while it waits for data.
+///
+/// The pipeline holds a mutable borrow of the client,
+/// so no other requests can be interleaved with the pipeline.
+/// When the pipeline is dropped, a sync point is queued if necessary
+/// and any unread results are read and discarded, so that the client
+/// remains usable.
+///
+/// As this library uses synchronous I/O, the queued messages are written
+/// before any results are read. If a very large number of queries is
+/// queued, the server may block writing results that are not being read,
+/// while the client blocks writing messages that are not being read.
+/// To avoid this, read the results of a batch before queuing more.
+///
+/// [`flush`]: `Self::flush`
+/// [`next_result`]: `Self::next_result`
+/// [`query_prepared`]: `Self::query_prepared`
+/// [`query_raw`]: `Self::query_raw`
+/// [`sync`]: `Self::sync`
+///
+#[doc = crate::pgdoc::pipelining!("spec")]
+pub struct Pipeline<'a>
+{
+    client: &'a mut PgClient,
+
+    /// The responses that are expected, in order.
+    expected: VecDeque<Expected>,
+
+    /// Whether an error occurred since the most recent sync point.
+    aborted: bool,
+}
+
+enum Expected
+{
+    /// Responses to a query.
+    ///
+    /// If the columns are known in advance, no `Describe` was sent,
+    /// so no `RowDescription` or `NoData` is expected.
+    Query{columns: Option<Vec<Column>>},
+
+    /// `ReadyForQuery` in response to `Sync`.
+    Sync,
+}
+
+/// Result of an item queued in a [`Pipeline`].
+#[derive(Clone, Debug)]
+pub enum PipelineResult
+{
+    /// The query succeeded.
+    Query(QueryResult),
+
+    /// The query failed.
+    Error(ErrorResponse),
+
+    /// The query was not executed because an earlier query failed.
+    Aborted,
+
+    /// The sync point was reached.
+    Sync,
+}
+
+impl PgClient
+{
+    /// Start a pipeline.
+    ///
+    /// See [`Pipeline`] for details.
+    pub fn pipeline(&mut self) -> Pipeline<'_>
+    {
+        Pipeline{client: self, expected: VecDeque::new(), aborted: false}
+    }
+}
+
+impl<'a> Pipeline<'a>
+{
+    /// Queue a query.
+    ///
+    /// This uses the unnamed prepared statement;
+    /// the statement cache is not used in pipelines.
+    /// See [`PgClient::query_raw`] for the meaning of the arguments.
+    pub fn query_raw(
+        &mut self,
+        query: &str,
+        parameter_type_oids: &[u32],
+        parameter_values: &[Option<&[u8]>],
+        parameter_format_codes: &[FormatCode],
+        result_format_codes: &[FormatCode],
+    ) -> Result<()>
+    {
+        self.client.write_messages(|buf| {
+            write_parse(buf, b"", query.as_bytes(), parameter_type_oids)?;
+            write_bind(
+                buf,
+                b"",
+                b"",
+                parameter_format_codes,
+                parameter_values.len(),
+                |i, buf| write_raw_parameter_value(buf, parameter_values[i]),
+                result_format_codes,
+            )?;
+            write_describe(buf, b'P', b"")?;
+            write_execute(buf, b"", 0)?;
+            Ok(())
+        })?;
+        self.expected.push_back(Expected::Query{columns: None});
+        Ok(())
+    }
+
+    /// Queue the execution of a prepared statement.
+    ///
+    /// See [`PgClient::query_prepared`] for the meaning of the arguments.
+    pub fn query_prepared(
+        &mut self,
+        statement: &Statement,
+        parameter_values: &[Option<&[u8]>],
+        parameter_format_codes: &[FormatCode],
+        result_format_codes: &[FormatCode],
+    ) -> Result<()>
+    {
+        self.client.write_messages(|buf| {
+            write_bind(
+                buf,
+                b"",
+                statement.name().as_bytes(),
+                parameter_format_codes,
+                parameter_values.len(),
+                |i, buf| write_raw_parameter_value(buf, parameter_values[i]),
+                result_format_codes,
+            )?;
+            write_execute(buf, b"", 0)?;
+            Ok(())
+        })?;
+        let columns = result_columns(statement.columns(), result_format_codes);
+        self.expected.push_back(Expected::Query{columns: Some(columns)});
+        Ok(())
+    }
+
+    /// Queue a sync point.
+    ///
+    /// This ends the implicit transaction of the preceding queries,
+    /// unless a transaction block was started explicitly.
+    pub fn sync(&mut self)
+    {
+        write_sync(&mut self.client.send_buf);
+        self.expected.push_back(Expected::Sync);
+    }
+
+    /// Write all queued messages to the server.
+    ///
+    /// If the most recently queued item is not a sync point,
+    /// the server is asked to send the results produced so far,
+    /// which it would otherwise only do upon a sync point.
+    pub fn flush(&mut self) -> Result<()>
+    {
+        if self.client.send_buf.is_empty() {
+            return Ok(());
+        }
+        if !matches!(self.expected.back(), Some(Expected::Sync)) {
+            write_flush(&mut self.client.send_buf);
+        }
+        self.client.send()
+    }
+
+    /// Read the result of the oldest queued item that was not yet read.
+    ///
+    /// Any queued messages that were not yet written are written first.
+    /// Returns [`None`] if the results of all queued items were read.
+    pub fn next_result(&mut self) -> Result<Option<PipelineResult>>
+    {
+        self.flush()?;
+
+        let Some(expected) = self.expected.pop_front() else {
+            return Ok(None);
+        };
+
+        match expected {
+            Expected::Sync => {
+                match self.client.receive()? {
+                    BackendMessage::ReadyForQuery{..} => (),
+                    _ => return Err(Error::BackendMessageUnexpected),
+                }
+                self.aborted = false;
+                Ok(Some(PipelineResult::Sync))
+            },
+            Expected::Query{..} if self.aborted =>
+                Ok(Some(PipelineResult::Aborted)),
+            Expected::Query{columns} =>
+                self.receive_query_result(columns).map(Some),
+        }
+    }
+
+    fn receive_query_result(&mut self, columns: Option<Vec<Column>>)
+        -> Result<PipelineResult>
+    {
+        let mut result = QueryResult::default();
+        if let Some(columns) = columns {
+            result.columns = columns;
+        }
+
+        loop {
+            match self.client.receive()? {
+                BackendMessage::ParseComplete
+                | BackendMessage::BindComplete
+                | BackendMessage::NoData =>
+                    (),
+                BackendMessage::RowDescription{fields} =>
+                    result.columns = fields.map(Column::from).collect(),
+                BackendMessage::DataRow{column_values} => {
+                    let row = column_values
+                        .map(|value| value.map(<[u8]>::to_vec))
+                        .collect();
+                    result.rows.push(row);
+                },
+                BackendMessage::CommandComplete{command_tag} => {
+                    result.command_tag = Some(command_tag.to_owned());
+                    break;
+                },
+                BackendMessage::EmptyQueryResponse
+                | BackendMessage::PortalSuspended =>
+                    break,
+                BackendMessage::ErrorResponse{fields} => {
+                    self.aborted = true;
+                    let error = ErrorResponse::from_fields(fields);
+                    return Ok(PipelineResult::Error(error));
+                },
+                BackendMessage::CopyInResponse{..} => {
+                    self.abandon_copy(true)?;
+                    return Err(Error::CopyUnsupported);
+                },
+                BackendMessage::CopyOutResponse{..} => {
+                    self.abandon_copy(false)?;
+                    return Err(Error::CopyUnsupported);
+                },
+                _ =>
+                    return Err(Error::BackendMessageUnexpected),
+            }
+        }
+
+        result.session_parameters = self.client.session_parameters.clone();
+        Ok(PipelineResult::Query(result))
+    }
+
+    /// Abandon a COPY started by a query, discarding all unread results.
+    ///
+    /// A `Sync` is sent, so that the queries queued after
+    /// the last sync point do not leave any results behind.
+    /// While it waits for data, the server ignores sync points,
+    /// so these produce no `ReadyForQuery`.
+    fn abandon_copy(&mut self, copy_in: bool) -> Result<()>
+    {
+        let ready_for_query = if copy_in {
+            0
+        } else {
+            self.expected.iter()
+                .filter(|expected| matches!(expected, Expected::Sync))
+                .count()
+        };
+        self.expected.clear();
+        self.aborted = false;
+        self.client.abandon_copy(copy_in, true, ready_for_query)
+    }
+}
+
+impl<'a> Drop for Pipeline<'a>
+{
+    fn drop(&mut self)
+    {
+        if self.expected.is_empty() {
+            return;
+        }
+        if !matches!(self.expected.back(), Some(Expected::Sync)) {
+            self.sync();
+        }
+        while let Ok(Some(_)) = self.next_result() {
+        }
+    }
+}
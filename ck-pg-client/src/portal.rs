@@ -0,0 +1,191 @@
+use {
+    crate::{
+        Column,
+        Error,
+        PgClient,
+        RawRow,
+        Result,
+        Statement,
+        extended_query::write_raw_parameter_value,
+        protocol::{
+            FormatCode,
+            write_bind,
+            write_describe,
+            write_execute,
+            write_parse,
+            write_sync,
+        },
+    },
+    std::ffi::{CStr, CString},
+};
+
+/// Named portal from which rows are fetched incrementally.
+///
+/// A portal is created with [`PgClient::portal_raw`]
+/// or [`PgClient::portal_prepared`]. Each call to [`fetch`] executes
+/// the portal with a row limit, so that only a bounded number of rows
+/// is held in memory at any time, even for queries with huge results.
+///
+/// Portals only exist until the end of the transaction in which they
+/// were created, so they can only be created within a transaction block.
+/// The portal holds a mutable borrow of the client,
+/// so no other requests can be interleaved with fetching.
+/// When the portal is dropped, it is closed;
+/// the `Close` message is sent along with the next request.
+///
+/// [`fetch`]: `Self::fetch`
+pub struct Portal<'a>
+{
+    client: &'a mut PgClient,
+    name: String,
+    columns: Vec<Column>,
+
+    /// Set once `CommandComplete` is received.
+    command_tag: Option<CString>,
+}
+
+impl PgClient
+{
+    /// Create a portal for a query.
+    ///
+    /// This uses the unnamed prepared statement.
+    /// See [`query_raw`] for the meaning of the arguments
+    /// and [`Portal`] for details on portals.
+    /// Returns [`Error::NotInTransactionBlock`] if
+    /// the client is not in a transaction block.
+    ///
+    /// [`query_raw`]: `Self::query_raw`
+    pub fn portal_raw(
+        &mut self,
+        query: &str,
+        parameter_type_oids: &[u32],
+        parameter_values: &[Option<&[u8]>],
+        parameter_format_codes: &[FormatCode],
+        result_format_codes: &[FormatCode],
+    ) -> Result<Portal<'_>>
+    {
+        self.create_portal(|buf, portal| {
+            write_parse(buf, b"", query.as_bytes(), parameter_type_oids)?;
+            write_bind(
+                buf,
+                portal,
+                b"",
+                parameter_format_codes,
+                parameter_values.len(),
+                |i, buf| write_raw_parameter_value(buf, parameter_values[i]),
+                result_format_codes,
+            )
+        })
+    }
+
+    /// Create a portal for a prepared statement.
+    ///
+    /// See [`query_prepared`] for the meaning of the arguments
+    /// and [`portal_raw`] for details on creating portals.
+    ///
+    /// [`portal_raw`]: `Self::portal_raw`
+    /// [`query_prepared`]: `Self::query_prepared`
+    pub fn portal_prepared(
+        &mut self,
+        statement: &Statement,
+        parameter_values: &[Option<&[u8]>],
+        parameter_format_codes: &[FormatCode],
+        result_format_codes: &[FormatCode],
+    ) -> Result<Portal<'_>>
+    {
+        self.create_portal(|buf, portal| {
+            write_bind(
+                buf,
+                portal,
+                statement.name().as_bytes(),
+                parameter_format_codes,
+                parameter_values.len(),
+                |i, buf| write_raw_parameter_value(buf, parameter_values[i]),
+                result_format_codes,
+            )
+        })
+    }
+
+    /// Create a portal, given a function that writes the `Bind` message.
+    fn create_portal<F>(&mut self, write_bind_message: F)
+        -> Result<Portal<'_>>
+        where F: FnOnce(&mut Vec<u8>, &[u8]) -> Result<()>
+    {
+        if self.transaction_status_indicator != b'T' {
+            return Err(Error::NotInTransactionBlock);
+        }
+
+        let id = self.next_portal_id;
+        self.next_portal_id += 1;
+        let name = format!("ck_pg_client_{id}");
+
+        self.write_messages(|buf| {
+            write_bind_message(buf, name.as_bytes())?;
+            write_describe(buf, b'P', name.as_bytes())?;
+            write_sync(buf);
+            Ok(())
+        })?;
+        self.send()?;
+
+        let responses = self.receive_extended_query_responses()?;
+        let columns = responses.into_result()?.result.columns;
+
+        Ok(Portal{client: self, name, columns, command_tag: None})
+    }
+}
+
+impl<'a> Portal<'a>
+{
+    /// The columns of the rows returned by the portal.
+    pub fn columns(&self) -> &[Column]
+    {
+        &self.columns
+    }
+
+    /// The command tag, once all rows have been fetched.
+    ///
+    /// The row count in the command tag is that of the final fetch,
+    /// not that of the portal as a whole.
+    pub fn command_tag(&self) -> Option<&CStr>
+    {
+        self.command_tag.as_deref()
+    }
+
+    /// Fetch the next batch of at most `max_rows` rows.
+    ///
+    /// Returns [`None`] once all rows have been fetched.
+    /// A `max_rows` of zero fetches all remaining rows.
+    pub fn fetch(&mut self, max_rows: u32) -> Result<Option<Vec<RawRow>>>
+    {
+        if self.command_tag.is_some() {
+            return Ok(None);
+        }
+
+        let name = self.name.as_bytes();
+        self.client.write_messages(|buf| {
+            write_execute(buf, name, max_rows)?;
+            write_sync(buf);
+            Ok(())
+        })?;
+        self.client.send()?;
+
+        let responses = self.client.receive_extended_query_responses()?;
+        let result = responses.into_result()?.result;
+        self.command_tag = result.command_tag;
+
+        if result.rows.is_empty() && self.command_tag.is_some() {
+            Ok(None)
+        } else {
+            Ok(Some(result.rows))
+        }
+    }
+}
+
+impl<'a> Drop for Portal<'a>
+{
+    fn drop(&mut self)
+    {
+        // This can only fail if the name contains nul, which it doesn't.
+        let _ = self.client.close_later(b'P', self.name.as_bytes());
+    }
+}
@@ -0,0 +1,27 @@
+/// Format of a parameter or column value.
+///
+/// The format codes are used in `Bind`, `RowDescription`,
+/// and the various `Copy*Response` messages.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(u16)]
+pub enum FormatCode
+{
+    /// The value is represented as text.
+    Text = 0,
+
+    /// The value is represented in the binary format of its data type.
+    Binary = 1,
+}
+
+impl FormatCode
+{
+    /// Interpret a format code received from the server.
+    pub fn from_u16(format_code: u16) -> Option<Self>
+    {
+        match format_code {
+            0 => Some(Self::Text),
+            1 => Some(Self::Binary),
+            _ => None,
+        }
+    }
+}
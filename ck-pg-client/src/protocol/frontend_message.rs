@@ -1,4 +1,9 @@
-use crate::{Error, Result};
+use {crate::{Error, Result}, super::FormatCode};
+
+pub fn write_int16_u16(buf: &mut Vec<u8>, value: u16)
+{
+    buf.extend_from_slice(&value.to_be_bytes());
+}
 
 pub fn write_int32_u32(buf: &mut Vec<u8>, value: u32)
 {
@@ -15,3 +20,174 @@ pub fn write_string_slice(buf: &mut Vec<u8>, value: &[u8]) -> Result<()>
         Ok(())
     }
 }
+
+/// Append a frontend message with the given identifier to a buffer.
+///
+/// The message body is written by `f`, after which
+/// the length field of the message is filled in.
+/// If `f` fails, the buffer is restored to its original length,
+/// so that no partial message is left behind.
+pub fn write_message<F>(buf: &mut Vec<u8>, identifier: u8, f: F)
+    -> Result<()>
+    where F: FnOnce(&mut Vec<u8>) -> Result<()>
+{
+    let start = buf.len();
+    buf.push(identifier);
+    write_int32_u32(buf, 0);
+
+    if let Err(err) = f(buf) {
+        buf.truncate(start);
+        return Err(err);
+    }
+
+    let Ok(length) = u32::try_from(buf.len() - start - 1) else {
+        buf.truncate(start);
+        return Err(Error::FrontendMessageTooLong);
+    };
+    buf[start + 1 .. start + 5].copy_from_slice(&length.to_be_bytes());
+
+    Ok(())
+}
+
+/// Append a `Query` message to a buffer.
+pub fn write_query(buf: &mut Vec<u8>, query: &[u8]) -> Result<()>
+{
+    write_message(buf, b'Q', |buf| write_string_slice(buf, query))
+}
+
+/// Append a `CopyFail` message to a buffer.
+pub fn write_copy_fail(buf: &mut Vec<u8>, message: &[u8]) -> Result<()>
+{
+    write_message(buf, b'f', |buf| write_string_slice(buf, message))
+}
+
+/// Append the length of an array as an `Int16` to a buffer.
+fn write_array_length(buf: &mut Vec<u8>, length: usize) -> Result<()>
+{
+    let length = u16::try_from(length)
+        .map_err(|_| Error::FrontendMessageTooLong)?;
+    write_int16_u16(buf, length);
+    Ok(())
+}
+
+/// Append an array of format codes, preceded by its length, to a buffer.
+fn write_format_codes(buf: &mut Vec<u8>, format_codes: &[FormatCode])
+    -> Result<()>
+{
+    write_array_length(buf, format_codes.len())?;
+    for &format_code in format_codes {
+        write_int16_u16(buf, format_code as u16);
+    }
+    Ok(())
+}
+
+/// Append a `Parse` message to a buffer.
+///
+/// A parameter data type OID of zero leaves the type unspecified.
+pub fn write_parse(
+    buf: &mut Vec<u8>,
+    statement: &[u8],
+    query: &[u8],
+    parameter_type_oids: &[u32],
+) -> Result<()>
+{
+    write_message(buf, b'P', |buf| {
+        write_string_slice(buf, statement)?;
+        write_string_slice(buf, query)?;
+        write_array_length(buf, parameter_type_oids.len())?;
+        for &oid in parameter_type_oids {
+            write_int32_u32(buf, oid);
+        }
+        Ok(())
+    })
+}
+
+/// Append a `Bind` message to a buffer.
+///
+/// The parameter values are written by `write_parameter_value`,
+/// which is called once for each parameter with its index.
+/// It returns `true` if the value is NULL,
+/// in which case anything it wrote is discarded.
+pub fn write_bind<F>(
+    buf: &mut Vec<u8>,
+    portal: &[u8],
+    statement: &[u8],
+    parameter_format_codes: &[FormatCode],
+    parameter_count: usize,
+    mut write_parameter_value: F,
+    result_format_codes: &[FormatCode],
+) -> Result<()>
+    where F: FnMut(usize, &mut Vec<u8>) -> Result<bool>
+{
+    write_message(buf, b'B', |buf| {
+        write_string_slice(buf, portal)?;
+        write_string_slice(buf, statement)?;
+        write_format_codes(buf, parameter_format_codes)?;
+        write_array_length(buf, parameter_count)?;
+        for i in 0 .. parameter_count {
+            let start = buf.len();
+            write_int32_u32(buf, 0);
+            let is_null = write_parameter_value(i, buf)?;
+            if is_null {
+                buf.truncate(start);
+                write_int32_u32(buf, u32::MAX);
+            } else {
+                let length = u32::try_from(buf.len() - start - 4)
+                    .ok()
+                    .filter(|&length| length != u32::MAX)
+                    .ok_or(Error::FrontendMessageTooLong)?;
+                buf[start .. start + 4].copy_from_slice(&length.to_be_bytes());
+            }
+        }
+        write_format_codes(buf, result_format_codes)
+    })
+}
+
+/// Append a `Describe` message to a buffer.
+///
+/// The `kind` is `b'S'` to describe a prepared statement
+/// or `b'P'` to describe a portal.
+pub fn write_describe(buf: &mut Vec<u8>, kind: u8, name: &[u8]) -> Result<()>
+{
+    write_message(buf, b'D', |buf| {
+        buf.push(kind);
+        write_string_slice(buf, name)
+    })
+}
+
+/// Append an `Execute` message to a buffer.
+///
+/// A `max_rows` of zero denotes “no limit”.
+pub fn write_execute(buf: &mut Vec<u8>, portal: &[u8], max_rows: u32)
+    -> Result<()>
+{
+    write_message(buf, b'E', |buf| {
+        write_string_slice(buf, portal)?;
+        write_int32_u32(buf, max_rows);
+        Ok(())
+    })
+}
+
+/// Append a `Close` message to a buffer.
+///
+/// The `kind` is `b'S'` to close a prepared statement
+/// or `b'P'` to close a portal.
+pub fn write_close(buf: &mut Vec<u8>, kind: u8, name: &[u8]) -> Result<()>
+{
+    write_message(buf, b'C', |buf| {
+        buf.push(kind);
+        write_string_slice(buf, name)
+    })
+}
+
+/// Append a `Sync` message to a buffer.
+pub fn write_sync(buf: &mut Vec<u8>)
+{
+    buf.extend_from_slice(&[b'S', 0, 0, 0, 4]);
+}
+
+/// Append a `Flush` message to a buffer.
+pub fn write_flush(buf: &mut Vec<u8>)
+{
+    buf.extend_from_slice(&[b'H', 0, 0, 0, 4]);
+}
@@ -12,14 +12,16 @@
 
 pub use self::{
     backend_message::*,
+    format_code::*,
     receiver::*,
     ssl_session_encryption::*,
     startup::*,
 };
 
-use self::frontend_message::*;
+pub (crate) use self::frontend_message::*;
 
 mod backend_message;
+mod format_code;
 mod frontend_message;
 mod receiver;
 mod ssl_session_encryption;
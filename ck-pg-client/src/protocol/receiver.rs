@@ -56,6 +56,44 @@ impl Receiver
             }
         }
     }
+
+    /// Synchronously read a backend message from a stream without parsing it.
+    ///
+    /// Notice responses are handled the same way as by [`receive`].
+    /// Returns the identifier of the message that was read.
+    /// The message can subsequently be parsed using [`parse`].
+    ///
+    /// This is useful when the caller needs to skip certain messages
+    /// and return others; doing so with [`receive`] in a loop
+    /// runs into the same borrow checker limitation described there.
+    ///
+    /// [`receive`]: `Self::receive`
+    /// [`parse`]: `Self::parse`
+    pub fn receive_unparsed<R>(&mut self, r: &mut R) -> Result<u8>
+        where R: Read
+    {
+        loop {
+            read_backend_message(r, &mut self.buf)?;
+            if self.buf.starts_with(b"N") {
+                let message = BackendMessage::parse(&self.buf)
+                    .ok_or(Error::BackendMessageParse)?;
+                if let BackendMessage::NoticeResponse{fields} = message {
+                    (self.on_notice)(fields);
+                }
+            } else {
+                break Ok(self.buf[0]);
+            }
+        }
+    }
+
+    /// Parse the message most recently read by [`receive_unparsed`].
+    ///
+    /// [`receive_unparsed`]: `Self::receive_unparsed`
+    pub fn parse(&self) -> Result<BackendMessage<'_>>
+    {
+        BackendMessage::parse(&self.buf)
+            .ok_or(Error::BackendMessageParse)
+    }
 }
 
 /// Synchronously read a backend message from a stream.
@@ -0,0 +1,106 @@
+use {
+    crate::{
+        Result,
+        Row,
+        RowDescription,
+        protocol::RowDescriptionField,
+        types::{SessionParameters, Type},
+    },
+    std::{
+        collections::HashMap,
+        ffi::CString,
+        num::{NonZeroU16, NonZeroU32},
+        sync::Arc,
+    },
+};
+
+/// Owned copy of a `RowDescription` field.
+#[allow(missing_docs)]
+#[derive(Clone, Debug)]
+pub struct Column
+{
+    pub name: CString,
+    pub table_oid: Option<NonZeroU32>,
+    pub attribute_number: Option<NonZeroU16>,
+    pub data_type_oid: u32,
+    pub data_type_size: i16,
+    pub data_type_modifier: u32,
+    pub format_code: u16,
+}
+
+impl<'a> From<RowDescriptionField<'a>> for Column
+{
+    fn from(field: RowDescriptionField<'a>) -> Self
+    {
+        Self{
+            name: field.name.to_owned(),
+            table_oid: field.table_oid,
+            attribute_number: field.attribute_number,
+            data_type_oid: field.data_type_oid,
+            data_type_size: field.data_type_size,
+            data_type_modifier: field.data_type_modifier,
+            format_code: field.format_code,
+        }
+    }
+}
+
+/// Values of a row received as a `DataRow` message.
+///
+/// There is one value per column; [`None`] represents SQL NULL.
+pub type RawRow = Vec<Option<Vec<u8>>>;
+
+/// Result of executing a statement.
+#[derive(Clone, Debug, Default)]
+pub struct QueryResult
+{
+    /// The columns described by `RowDescription`.
+    ///
+    /// This is empty for statements that don't return rows.
+    pub columns: Vec<Column>,
+
+    /// The rows received as `DataRow` messages.
+    pub rows: Vec<RawRow>,
+
+    /// The command tag received as `CommandComplete`.
+    ///
+    /// This is [`None`] if the query string was empty,
+    /// in which case the server responds with `EmptyQueryResponse`.
+    pub command_tag: Option<CString>,
+
+    /// The session parameters in effect when the result was received.
+    ///
+    /// These are used when converting values in text format.
+    /// See [`PgClient::simple_query_multi`] for how changes made by
+    /// earlier statements in the same query string are accounted for.
+    ///
+    /// [`PgClient::simple_query_multi`]: `crate::PgClient::simple_query_multi`
+    pub session_parameters: Arc<SessionParameters>,
+}
+
+impl QueryResult
+{
+    /// Convert the rows into [`Row`]s that share the columns.
+    ///
+    /// This fails if a row does not have one value for each column.
+    pub fn into_rows(self) -> Result<Vec<Row>>
+    {
+        self.into_rows_with_types(&HashMap::new())
+    }
+
+    /// Like [`into_rows`][`Self::into_rows`], but with the given
+    /// types for columns whose types are not built in.
+    pub (crate) fn into_rows_with_types(self, types: &HashMap<u32, Type>)
+        -> Result<Vec<Row>>
+    {
+        let description = RowDescription::new(self.columns)
+            .with_types(types)
+            .with_session_parameters(self.session_parameters);
+        let description = Arc::new(description);
+        self.rows.into_iter()
+            .map(|row| {
+                let values = row.iter().map(Option::as_deref);
+                Row::new(description.clone(), values)
+            })
+            .collect()
+    }
+}
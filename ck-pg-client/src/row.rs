@@ -0,0 +1,329 @@
+use {
+    crate::{
+        Column,
+        Error,
+        RawRow,
+        Result,
+        protocol::FormatCode,
+        types::{Context, FromSql, SessionParameters, Type, from_sql_checked},
+    },
+    std::{collections::HashMap, fmt, ops::Range, sync::Arc},
+};
+
+/// Columns of a query result, indexed by name.
+///
+/// The description is created once per query result
+/// and shared by all of its rows.
+#[derive(Clone, Debug)]
+pub struct RowDescription
+{
+    columns: Vec<Column>,
+
+    /// The type of each column.
+    types: Vec<Type>,
+
+    /// The indices of the columns with each name, in ascending order.
+    indices_by_name: HashMap<Box<[u8]>, Vec<usize>>,
+
+    session_parameters: Arc<SessionParameters>,
+}
+
+impl RowDescription
+{
+    /// Create a row description from the columns of a query result.
+    ///
+    /// The type of each column is looked up by its data type OID.
+    /// The session parameters are [`SessionParameters::DEFAULT`].
+    pub fn new(columns: Vec<Column>) -> Self
+    {
+        let types = columns.iter()
+            .map(|column| Type::from_oid_or_unknown(column.data_type_oid))
+            .collect();
+
+        let mut indices_by_name = HashMap::<_, Vec<_>>::new();
+        for (index, column) in columns.iter().enumerate() {
+            let name = column.name.to_bytes().into();
+            indices_by_name.entry(name).or_default().push(index);
+        }
+        let session_parameters = Arc::default();
+        Self{columns, types, indices_by_name, session_parameters}
+    }
+
+    /// Replace the types of the columns whose types are not built in
+    /// with the types with the same OIDs, if any.
+    pub (crate) fn with_types(mut self, types: &HashMap<u32, Type>) -> Self
+    {
+        for ty in &mut self.types {
+            if let Some(known) = types.get(&ty.oid()) {
+                *ty = known.clone();
+            }
+        }
+        self
+    }
+
+    /// Replace the session parameters used for converting values.
+    pub fn with_session_parameters(
+        self,
+        session_parameters: Arc<SessionParameters>,
+    ) -> Self
+    {
+        Self{session_parameters, ..self}
+    }
+
+    /// The session parameters used for converting values.
+    pub fn session_parameters(&self) -> &Arc<SessionParameters>
+    {
+        &self.session_parameters
+    }
+
+    /// The columns, in the order in which they appear in rows.
+    pub fn columns(&self) -> &[Column]
+    {
+        &self.columns
+    }
+
+    /// The types of the columns.
+    pub fn types(&self) -> &[Type]
+    {
+        &self.types
+    }
+
+    /// The context for converting values of the column with the given index.
+    pub (crate) fn context(&self, index: usize) -> Result<Context<'_>>
+    {
+        let format_code = FormatCode::from_u16(self.columns[index].format_code)
+            .ok_or(Error::BackendMessageParse)?;
+        Ok(Context::new(&self.types[index], format_code)
+            .with_session_parameters(&self.session_parameters))
+    }
+
+    /// The index of the first column with the given name.
+    ///
+    /// Column names are compared exactly. Unlike in SQL,
+    /// they are not folded to lower case when unquoted.
+    pub fn index_of(&self, name: &str) -> Option<usize>
+    {
+        self.indices_of(name).first().copied()
+    }
+
+    /// The indices of all columns with the given name.
+    ///
+    /// A query result can have multiple columns with the same name,
+    /// for instance `SELECT 1 AS a, 2 AS a` or a join
+    /// of two tables that both have an `id` column.
+    pub fn indices_of(&self, name: &str) -> &[usize]
+    {
+        self.indices_by_name.get(name.as_bytes())
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Type that can be used to look up a column in a row.
+///
+/// This is implemented for [`usize`], which is the index of the column,
+/// and for [`str`], which is the name of the column.
+/// If multiple columns have the given name, the first one is used.
+pub trait RowIndex
+{
+    /// Find the index of the column, if it exists.
+    fn index(&self, description: &RowDescription) -> Option<usize>;
+}
+
+impl RowIndex for usize
+{
+    fn index(&self, description: &RowDescription) -> Option<usize>
+    {
+        (*self < description.columns.len()).then_some(*self)
+    }
+}
+
+impl RowIndex for str
+{
+    fn index(&self, description: &RowDescription) -> Option<usize>
+    {
+        description.index_of(self)
+    }
+}
+
+impl<T> RowIndex for &T
+    where T: ?Sized + RowIndex
+{
+    fn index(&self, description: &RowDescription) -> Option<usize>
+    {
+        T::index(self, description)
+    }
+}
+
+/// Row of a query result.
+///
+/// The values of the row are stored in a single buffer,
+/// so that any value can be accessed in constant time.
+/// The [`RowDescription`] is shared with the other rows of the result.
+#[derive(Clone, Debug)]
+pub struct Row
+{
+    description: Arc<RowDescription>,
+    values: Vec<u8>,
+
+    /// The range of each value in `values`; [`None`] for SQL NULL.
+    ranges: Vec<Option<Range<usize>>>,
+}
+
+impl Row
+{
+    /// Create a row from its values.
+    ///
+    /// There must be one value for each column in the description.
+    pub (crate) fn new<'v, I>(description: Arc<RowDescription>, values: I)
+        -> Result<Self>
+        where I: IntoIterator<Item = Option<&'v [u8]>>
+    {
+        let mut buf = Vec::new();
+        let mut ranges = Vec::with_capacity(description.columns.len());
+        for value in values {
+            let range = value.map(|value| {
+                let start = buf.len();
+                buf.extend_from_slice(value);
+                start .. buf.len()
+            });
+            ranges.push(range);
+        }
+
+        if ranges.len() != description.columns.len() {
+            return Err(Error::BackendMessageParse);
+        }
+
+        Ok(Self{description, values: buf, ranges})
+    }
+
+    /// The description of the columns of the row.
+    pub fn description(&self) -> &Arc<RowDescription>
+    {
+        &self.description
+    }
+
+    /// The columns of the row.
+    pub fn columns(&self) -> &[Column]
+    {
+        &self.description.columns
+    }
+
+    /// The number of values in the row.
+    pub fn len(&self) -> usize
+    {
+        self.ranges.len()
+    }
+
+    /// Whether the row has no values.
+    pub fn is_empty(&self) -> bool
+    {
+        self.ranges.is_empty()
+    }
+
+    /// The column with the given index or name.
+    pub fn column<I>(&self, index: I) -> Result<&Column>
+        where I: RowIndex + fmt::Debug
+    {
+        let index = self.index(index)?;
+        Ok(&self.description.columns[index])
+    }
+
+    /// Decode the value of the column with the given index or name.
+    ///
+    /// Returns [`Error::WrongType`] if `T` does not accept the type
+    /// of the column, and [`Error::UnexpectedNull`] if the value is
+    /// SQL NULL and `T` is not an [`Option`].
+    pub fn get<'r, T, I>(&'r self, index: I) -> Result<T>
+        where T: FromSql<'r>, I: RowIndex + fmt::Debug
+    {
+        let index = self.index(index)?;
+        let cx = self.description.context(index)?;
+        from_sql_checked(&cx, self.value(index))
+    }
+
+    /// The value of the column with the given index or name.
+    ///
+    /// [`None`] represents SQL NULL.
+    /// The value is in the format given by the format code of the column.
+    pub fn get_raw<I>(&self, index: I) -> Result<Option<&[u8]>>
+        where I: RowIndex + fmt::Debug
+    {
+        let index = self.index(index)?;
+        Ok(self.value(index))
+    }
+
+    /// The values of the row, in column order.
+    pub fn values(&self) -> impl Iterator<Item = Option<&[u8]>>
+    {
+        (0 .. self.len()).map(|index| self.value(index))
+    }
+
+    /// Copy the values of the row.
+    pub fn to_raw_row(&self) -> RawRow
+    {
+        self.values().map(|value| value.map(<[u8]>::to_vec)).collect()
+    }
+
+    fn index<I>(&self, index: I) -> Result<usize>
+        where I: RowIndex + fmt::Debug
+    {
+        index.index(&self.description)
+            .ok_or_else(|| Error::NoSuchColumn(format!("{index:?}")))
+    }
+
+    fn value(&self, index: usize) -> Option<&[u8]>
+    {
+        self.ranges[index].clone().map(|range| &self.values[range])
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {super::*, std::{assert_matches::assert_matches, ffi::CString}};
+
+    fn column(name: &str) -> Column
+    {
+        Column{
+            name: CString::new(name).unwrap(),
+            table_oid: None,
+            attribute_number: None,
+            data_type_oid: 25,
+            data_type_size: -1,
+            data_type_modifier: u32::MAX,
+            format_code: 0,
+        }
+    }
+
+    #[test]
+    fn lookup()
+    {
+        let columns = vec![column("id"), column("name"), column("id")];
+        let description = Arc::new(RowDescription::new(columns));
+        assert_eq!(description.index_of("id"), Some(0));
+        assert_eq!(description.indices_of("id"), [0, 2]);
+        assert_eq!(description.indices_of("ID"), [0usize; 0]);
+
+        let values = [Some(&b"1"[..]), None, Some(b"2")];
+        let row = Row::new(description.clone(), values).unwrap();
+        assert_eq!(row.len(), 3);
+        assert_eq!(row.get_raw(0).unwrap(), Some(&b"1"[..]));
+        assert_eq!(row.get_raw("id").unwrap(), Some(&b"1"[..]));
+        assert_eq!(row.get_raw(2).unwrap(), Some(&b"2"[..]));
+        assert_eq!(row.get_raw("name").unwrap(), None);
+        assert_eq!(row.column("name").unwrap().name.to_bytes(), b"name");
+        assert_eq!(row.values().collect::<Vec<_>>(), values);
+
+        assert_matches!(
+            row.get_raw(3).unwrap_err(),
+            Error::NoSuchColumn(name) if name == "3",
+        );
+        assert_matches!(
+            row.get_raw("nope").unwrap_err(),
+            Error::NoSuchColumn(name) if name == "\"nope\"",
+        );
+
+        let result = Row::new(description, [None, None]);
+        assert_matches!(result.unwrap_err(), Error::BackendMessageParse);
+    }
+}
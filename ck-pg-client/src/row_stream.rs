@@ -0,0 +1,359 @@
+use {
+    crate::{
+        Column,
+        Error,
+        ErrorResponse,
+        PgClient,
+        RawRow,
+        Result,
+        Row,
+        RowDescription,
+        RowIndex,
+        Statement,
+        extended_query::write_raw_parameter_value,
+        protocol::{
+            BackendMessage,
+            DataRowColumnValueArray,
+            FormatCode,
+            write_bind,
+            write_describe,
+            write_execute,
+            write_parse,
+            write_sync,
+        },
+        types::{FromSql, from_sql_checked},
+    },
+    std::{ffi::{CStr, CString}, fmt, sync::Arc},
+};
+
+/// Rows of a query result, received one at a time.
+///
+/// A row stream is created with [`PgClient::row_stream_raw`]
+/// or [`PgClient::row_stream_prepared`]. Unlike the methods that
+/// return a [`QueryResult`], a row stream does not collect the rows.
+/// Instead, [`next_row`] returns a view of each `DataRow` message
+/// that borrows from the receive buffer of the client, which is reused
+/// for every message. Hence no allocations are made per row,
+/// but each row must be processed before the next one is received.
+///
+/// The row stream holds a mutable borrow of the client,
+/// so no other requests can be interleaved with receiving rows.
+/// When the row stream is dropped, any rows not yet received
+/// are received and discarded, so that the client remains usable.
+/// To stop a query early without receiving all of its rows,
+/// use a [`Portal`] instead.
+///
+/// [`Portal`]: `crate::Portal`
+/// [`QueryResult`]: `crate::QueryResult`
+/// [`next_row`]: `Self::next_row`
+pub struct RowStream<'a>
+{
+    client: &'a mut PgClient,
+    description: Arc<RowDescription>,
+
+    /// Set once `CommandComplete` is received.
+    command_tag: Option<CString>,
+
+    /// Set by `ErrorResponse`, and reported once `ReadyForQuery` arrives.
+    error: Option<ErrorResponse>,
+
+    /// Set once `ReadyForQuery` is received.
+    done: bool,
+}
+
+/// View of a `DataRow` message received by a [`RowStream`].
+#[derive(Clone, Debug)]
+pub struct RowRef<'r>
+{
+    description: &'r Arc<RowDescription>,
+    values: DataRowColumnValueArray<'r>,
+}
+
+impl PgClient
+{
+    /// Execute a query, receiving the rows one at a time.
+    ///
+    /// This uses the unnamed prepared statement.
+    /// See [`query_raw`] for the meaning of the arguments
+    /// and [`RowStream`] for details on row streams.
+    ///
+    /// [`query_raw`]: `Self::query_raw`
+    pub fn row_stream_raw(
+        &mut self,
+        query: &str,
+        parameter_type_oids: &[u32],
+        parameter_values: &[Option<&[u8]>],
+        parameter_format_codes: &[FormatCode],
+        result_format_codes: &[FormatCode],
+    ) -> Result<RowStream<'_>>
+    {
+        self.create_row_stream(|buf| {
+            write_parse(buf, b"", query.as_bytes(), parameter_type_oids)?;
+            write_bind(
+                buf,
+                b"",
+                b"",
+                parameter_format_codes,
+                parameter_values.len(),
+                |i, buf| write_raw_parameter_value(buf, parameter_values[i]),
+                result_format_codes,
+            )
+        })
+    }
+
+    /// Execute a prepared statement, receiving the rows one at a time.
+    ///
+    /// See [`query_prepared`] for the meaning of the arguments
+    /// and [`RowStream`] for details on row streams.
+    /// Types of columns that are not built in are looked up first;
+    /// see [`lookup_type`][`Self::lookup_type`].
+    ///
+    /// [`query_prepared`]: `Self::query_prepared`
+    pub fn row_stream_prepared(
+        &mut self,
+        statement: &Statement,
+        parameter_values: &[Option<&[u8]>],
+        parameter_format_codes: &[FormatCode],
+        result_format_codes: &[FormatCode],
+    ) -> Result<RowStream<'_>>
+    {
+        self.lookup_statement_types(statement)?;
+        self.create_row_stream(|buf| {
+            write_bind(
+                buf,
+                b"",
+                statement.name().as_bytes(),
+                parameter_format_codes,
+                parameter_values.len(),
+                |i, buf| write_raw_parameter_value(buf, parameter_values[i]),
+                result_format_codes,
+            )
+        })
+    }
+
+    /// Create a row stream, given a function that writes the `Bind`
+    /// message for the unnamed portal.
+    ///
+    /// The columns are received before returning,
+    /// so that errors in parsing or binding are reported here.
+    fn create_row_stream<F>(&mut self, write_bind_message: F)
+        -> Result<RowStream<'_>>
+        where F: FnOnce(&mut Vec<u8>) -> Result<()>
+    {
+        self.write_messages(|buf| {
+            write_bind_message(buf)?;
+            write_describe(buf, b'P', b"")?;
+            write_execute(buf, b"", 0)?;
+            write_sync(buf);
+            Ok(())
+        })?;
+        self.send()?;
+
+        let mut stream = RowStream{
+            client: self,
+            description: Arc::new(RowDescription::new(Vec::new())),
+            command_tag: None,
+            error: None,
+            done: false,
+        };
+
+        loop {
+            match stream.client.receive_unparsed()? {
+                b'1' | b'2' =>
+                    (),
+                b'T' => {
+                    let message = stream.client.receiver.parse()?;
+                    if let BackendMessage::RowDescription{fields} = message {
+                        let columns = fields.map(Column::from).collect();
+                        let session_parameters =
+                            stream.client.session_parameters.clone();
+                        let description = RowDescription::new(columns)
+                            .with_types(&stream.client.types)
+                            .with_session_parameters(session_parameters);
+                        stream.description = Arc::new(description);
+                    }
+                    break;
+                },
+                b'n' =>
+                    break,
+                _ => {
+                    stream.receive_other()?;
+                    if stream.done {
+                        break;
+                    }
+                },
+            }
+        }
+
+        Ok(stream)
+    }
+}
+
+impl<'a> RowStream<'a>
+{
+    /// The description of the columns of the rows.
+    pub fn description(&self) -> &Arc<RowDescription>
+    {
+        &self.description
+    }
+
+    /// The columns of the rows.
+    pub fn columns(&self) -> &[Column]
+    {
+        self.description.columns()
+    }
+
+    /// The command tag, once all rows have been received.
+    pub fn command_tag(&self) -> Option<&CStr>
+    {
+        self.command_tag.as_deref()
+    }
+
+    /// Receive the next row.
+    ///
+    /// Returns [`None`] once all rows have been received.
+    /// If the server reports an error, the remaining messages
+    /// up to `ReadyForQuery` are consumed before returning
+    /// [`Error::ErrorResponse`], so the client remains usable.
+    pub fn next_row(&mut self) -> Result<Option<RowRef<'_>>>
+    {
+        while !self.done {
+            match self.client.receive_unparsed()? {
+                b'D' => {
+                    let message = self.client.receiver.parse()?;
+                    let BackendMessage::DataRow{column_values} = message
+                        else { unreachable!() };
+                    let description = &self.description;
+                    return Ok(Some(RowRef{description, values: column_values}));
+                },
+                _ =>
+                    self.receive_other()?,
+            }
+        }
+        Ok(None)
+    }
+
+    /// Handle a message other than `DataRow` or `RowDescription`.
+    ///
+    /// When `ReadyForQuery` is received, the error reported
+    /// by `ErrorResponse` (if any) is returned.
+    fn receive_other(&mut self) -> Result<()>
+    {
+        match self.client.receiver.parse()? {
+            BackendMessage::CommandComplete{command_tag} =>
+                self.command_tag = Some(command_tag.to_owned()),
+            BackendMessage::EmptyQueryResponse =>
+                (),
+            BackendMessage::ErrorResponse{fields} =>
+                self.error = Some(ErrorResponse::from_fields(fields)),
+            BackendMessage::ReadyForQuery{..} => {
+                self.done = true;
+                if let Some(error) = self.error.take() {
+                    return Err(Error::ErrorResponse(Box::new(error)));
+                }
+            },
+            BackendMessage::CopyInResponse{..} => {
+                self.done = true;
+                self.client.abandon_copy(true, true, 0)?;
+                return Err(Error::CopyUnsupported);
+            },
+            BackendMessage::CopyOutResponse{..} => {
+                self.done = true;
+                self.client.abandon_copy(false, false, 1)?;
+                return Err(Error::CopyUnsupported);
+            },
+            _ =>
+                return Err(Error::BackendMessageUnexpected),
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Drop for RowStream<'a>
+{
+    fn drop(&mut self)
+    {
+        while !self.done {
+            match self.client.receive_unparsed() {
+                Ok(b'Z') | Err(_) => self.done = true,
+                Ok(b'G') => {
+                    let _ = self.client.abandon_copy(true, true, 0);
+                    self.done = true;
+                },
+                Ok(b'H') => {
+                    let _ = self.client.abandon_copy(false, false, 1);
+                    self.done = true;
+                },
+                Ok(_) => (),
+            }
+        }
+    }
+}
+
+impl<'r> RowRef<'r>
+{
+    /// The description of the columns of the row.
+    pub fn description(&self) -> &'r Arc<RowDescription>
+    {
+        self.description
+    }
+
+    /// The columns of the row.
+    pub fn columns(&self) -> &'r [Column]
+    {
+        self.description.columns()
+    }
+
+    /// Decode the value of the column with the given index or name.
+    ///
+    /// See [`Row::get`] for details
+    /// and [`get_raw`] for the time complexity.
+    ///
+    /// [`get_raw`]: `Self::get_raw`
+    pub fn get<T, I>(&self, index: I) -> Result<T>
+        where T: FromSql<'r>, I: RowIndex + fmt::Debug
+    {
+        let (index, value) = self.find(index)?;
+        let cx = self.description.context(index)?;
+        from_sql_checked(&cx, value)
+    }
+
+    /// The value of the column with the given index or name.
+    ///
+    /// Unlike with [`Row::get_raw`], finding the value takes time
+    /// linear in the index of the column, as the lengths of
+    /// the preceding values in the `DataRow` message are skipped.
+    pub fn get_raw<I>(&self, index: I) -> Result<Option<&'r [u8]>>
+        where I: RowIndex + fmt::Debug
+    {
+        self.find(index).map(|(_, value)| value)
+    }
+
+    fn find<I>(&self, index: I) -> Result<(usize, Option<&'r [u8]>)>
+        where I: RowIndex + fmt::Debug
+    {
+        index.index(self.description)
+            .and_then(|i| Some((i, self.values().nth(i)?)))
+            .ok_or_else(|| Error::NoSuchColumn(format!("{index:?}")))
+    }
+
+    /// The values of the row, in column order.
+    ///
+    /// [`None`] represents SQL NULL.
+    pub fn values(&self) -> DataRowColumnValueArray<'r>
+    {
+        self.values.clone()
+    }
+
+    /// Copy the values of the row.
+    pub fn to_raw_row(&self) -> RawRow
+    {
+        self.values().map(|value| value.map(<[u8]>::to_vec)).collect()
+    }
+
+    /// Copy the row into a [`Row`], sharing the description.
+    pub fn to_row(&self) -> Result<Row>
+    {
+        Row::new(self.description.clone(), self.values())
+    }
+}
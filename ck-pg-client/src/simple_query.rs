@@ -0,0 +1,202 @@
+use {
+    crate::{
+        Column,
+        Error,
+        ErrorResponse,
+        PgClient,
+        QueryResult,
+        Result,
+        protocol::{BackendMessage, write_query},
+    },
+    std::sync::Arc,
+};
+
+/// Results of a query string that may contain multiple statements.
+#[derive(Clone, Debug, Default)]
+pub struct MultiStatementResults
+{
+    /// The results of the statements that completed successfully,
+    /// in the order in which they appear in the query string.
+    pub results: Vec<QueryResult>,
+
+    /// The error that caused execution to stop, if any.
+    pub error: Option<StatementError>,
+}
+
+/// Error that occurred while executing one of multiple statements.
+#[derive(Clone, Debug)]
+pub struct StatementError
+{
+    /// Zero-based index of the statement that failed.
+    ///
+    /// This is equal to the number of statements that succeeded,
+    /// since the server stops executing statements at the first error.
+    pub statement_index: usize,
+
+    /// The error reported by the server.
+    pub error_response: ErrorResponse,
+}
+
+impl MultiStatementResults
+{
+    /// Convert into [`Err`] if any statement failed.
+    ///
+    /// The results of the statements that succeeded are discarded.
+    pub fn into_result(self) -> Result<Vec<QueryResult>>
+    {
+        match self.error {
+            Some(error) => {
+                let error_response = Box::new(error.error_response);
+                Err(Error::ErrorResponse(error_response))
+            },
+            None =>
+                Ok(self.results),
+        }
+    }
+}
+
+impl PgClient
+{
+    /// Execute a query string using the [_Simple Query_][spec] flow.
+    ///
+    /// All values in the result are in text format,
+    /// as the simple query flow does not support binary format.
+    /// If the server responds with `ErrorResponse`, the remaining
+    /// messages up to `ReadyForQuery` are consumed before returning
+    /// [`Error::ErrorResponse`], so the client remains usable.
+    /// The same goes for statements that copy data from or to the client,
+    /// for which [`Error::CopyUnsupported`] is returned.
+    ///
+    /// If the query string contains multiple statements, the rows
+    /// of all statements are collected into a single result, and the
+    /// columns and command tag are those of the last statement.
+    /// Use [`simple_query_multi`] to obtain the result of each statement.
+    ///
+    /// [`simple_query_multi`]: `Self::simple_query_multi`
+    ///
+    #[doc = crate::pgdoc::simple_query!("spec")]
+    pub fn simple_query(&mut self, query: &str) -> Result<QueryResult>
+    {
+        let results = self.simple_query_multi(query)?.into_result()?;
+
+        let mut flattened = QueryResult::default();
+        for result in results {
+            flattened.columns = result.columns;
+            flattened.rows.extend(result.rows);
+            flattened.command_tag = result.command_tag;
+            flattened.session_parameters = result.session_parameters;
+        }
+
+        Ok(flattened)
+    }
+
+    /// Execute a query string that may contain [multiple statements][spec].
+    ///
+    /// Each statement produces its own result.
+    /// The server stops executing statements at the first error,
+    /// in which case [`MultiStatementResults::error`] is set and
+    /// [`MultiStatementResults::results`] contains the results of the
+    /// statements that completed before it. Rows sent by the failed
+    /// statement before the error occurred are discarded.
+    /// Note that unless the query string contains explicit transaction
+    /// control commands, the statements run in a single implicit
+    /// transaction, so the effects of the succeeded statements
+    /// are rolled back when a later statement fails.
+    ///
+    /// Each result has the session parameters in effect when its
+    /// statement completed, which are used to convert values in text
+    /// format. Since PostgreSQL 14, the server reports only their final
+    /// values, after the last statement, so these are given to the
+    /// results that follow the first `SET`, `RESET`, or `DISCARD`
+    /// statement. Changes made otherwise, such as by `set_config`,
+    /// apply to the results of subsequent queries only.
+    ///
+    /// [`Err`] is only returned if communication with the server fails,
+    /// or if a statement copies data from or to the client, which is
+    /// not supported. In the latter case, a `COPY FROM STDIN` is failed
+    /// and the data sent by a `COPY TO STDOUT` is discarded,
+    /// before returning [`Error::CopyUnsupported`],
+    /// so the client remains usable.
+    ///
+    #[doc = crate::pgdoc::multiple_statements!("spec")]
+    pub fn simple_query_multi(&mut self, query: &str)
+        -> Result<MultiStatementResults>
+    {
+        write_query(&mut self.send_buf, query.as_bytes())?;
+        self.send()?;
+
+        let mut results = MultiStatementResults::default();
+        let mut current = QueryResult::default();
+        let mut first_set = None;
+
+        loop {
+            match self.receive()? {
+                BackendMessage::RowDescription{fields} =>
+                    current.columns = fields.map(Column::from).collect(),
+                BackendMessage::DataRow{column_values} => {
+                    let row = column_values
+                        .map(|value| value.map(<[u8]>::to_vec))
+                        .collect();
+                    current.rows.push(row);
+                },
+                BackendMessage::CommandComplete{command_tag} => {
+                    if first_set.is_none()
+                        && is_set_command_tag(command_tag.to_bytes()) {
+                        first_set = Some(results.results.len());
+                    }
+                    current.command_tag = Some(command_tag.to_owned());
+                    current.session_parameters =
+                        self.session_parameters.clone();
+                    results.results.push(current);
+                    current = QueryResult::default();
+                },
+                BackendMessage::EmptyQueryResponse => {
+                    current.session_parameters =
+                        self.session_parameters.clone();
+                    results.results.push(current);
+                    current = QueryResult::default();
+                },
+                BackendMessage::ErrorResponse{fields} => {
+                    let statement_index = results.results.len();
+                    let error_response = ErrorResponse::from_fields(fields);
+                    let error = StatementError{statement_index, error_response};
+                    results.error = Some(error);
+                },
+                BackendMessage::CopyInResponse{..} => {
+                    self.abandon_copy(true, false, 1)?;
+                    return Err(Error::CopyUnsupported);
+                },
+                BackendMessage::CopyOutResponse{..} => {
+                    self.abandon_copy(false, false, 1)?;
+                    return Err(Error::CopyUnsupported);
+                },
+                BackendMessage::ReadyForQuery{..} =>
+                    break,
+                _ =>
+                    return Err(Error::BackendMessageUnexpected),
+            }
+        }
+
+        // Since PostgreSQL 14, changes made by the statements are
+        // reported just before `ReadyForQuery`, rather than when they
+        // are made, so they are applied to the results that follow
+        // the first statement that changes session parameters.
+        let deferred = results.results.last().is_some_and(|result| {
+            !Arc::ptr_eq(&result.session_parameters, &self.session_parameters)
+        });
+        if let Some(first_set) = first_set.filter(|_| deferred) {
+            for result in &mut results.results[first_set ..] {
+                result.session_parameters = self.session_parameters.clone();
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+/// Whether a command tag is that of a statement that changes
+/// session parameters, which are `SET`, `RESET`, and `DISCARD`.
+fn is_set_command_tag(command_tag: &[u8]) -> bool
+{
+    matches!(command_tag, b"SET" | b"RESET" | b"DISCARD ALL")
+}
@@ -0,0 +1,494 @@
+use {
+    crate::{
+        Column,
+        Error,
+        ErrorResponse,
+        PgClient,
+        QueryResult,
+        Result,
+        StatementDescription,
+        extended_query::write_raw_parameter_value,
+        protocol::{
+            FormatCode,
+            write_bind,
+            write_describe,
+            write_execute,
+            write_parse,
+            write_sync,
+        },
+    },
+    std::{collections::HashMap, mem::take, sync::Arc},
+};
+
+/// The default capacity of the statement cache.
+pub const DEFAULT_STATEMENT_CACHE_CAPACITY: usize = 100;
+
+/// Named prepared statement.
+///
+/// A prepared statement exists on the connection on which it was prepared.
+/// It must only be used with the [`PgClient`] that created it.
+/// Cloning a statement is cheap and does not prepare another statement.
+#[derive(Clone, Debug)]
+pub struct Statement
+{
+    inner: Arc<StatementInner>,
+}
+
+#[derive(Debug)]
+struct StatementInner
+{
+    name: String,
+    description: StatementDescription,
+}
+
+impl Statement
+{
+    fn new(name: String, description: StatementDescription) -> Self
+    {
+        Self{inner: Arc::new(StatementInner{name, description})}
+    }
+
+    /// The name of the prepared statement.
+    pub fn name(&self) -> &str
+    {
+        &self.inner.name
+    }
+
+    /// The data type OIDs of the parameters.
+    pub fn parameter_type_oids(&self) -> &[u32]
+    {
+        &self.inner.description.parameter_type_oids
+    }
+
+    /// The columns of the result.
+    ///
+    /// As the result formats are not known until the statement
+    /// is bound, all format codes in this description are zero.
+    pub fn columns(&self) -> &[Column]
+    {
+        &self.inner.description.columns
+    }
+}
+
+/// Least recently used cache of prepared statements, keyed by query string.
+pub (crate) struct StatementCache
+{
+    capacity: usize,
+
+    /// Incremented on every access; used to find the least recently used.
+    clock: u64,
+
+    entries: HashMap<String, StatementCacheEntry>,
+}
+
+struct StatementCacheEntry
+{
+    parameter_type_oids: Vec<u32>,
+    statement: Statement,
+    last_used: u64,
+}
+
+impl StatementCache
+{
+    pub fn new() -> Self
+    {
+        Self{
+            capacity: DEFAULT_STATEMENT_CACHE_CAPACITY,
+            clock: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize
+    {
+        self.capacity
+    }
+
+    /// Find the statement for a query, marking it as recently used.
+    ///
+    /// A statement prepared with different parameter types is not returned.
+    pub fn get(&mut self, query: &str, parameter_type_oids: &[u32])
+        -> Option<Statement>
+    {
+        self.clock += 1;
+        let entry = self.entries.get_mut(query)?;
+        if entry.parameter_type_oids != parameter_type_oids {
+            return None;
+        }
+        entry.last_used = self.clock;
+        Some(entry.statement.clone())
+    }
+
+    /// Insert a statement into the cache.
+    ///
+    /// Returns the statements that no longer fit in the cache,
+    /// including any statement previously cached for the same query.
+    pub fn insert(
+        &mut self,
+        query: String,
+        parameter_type_oids: Vec<u32>,
+        statement: Statement,
+    ) -> Vec<Statement>
+    {
+        self.clock += 1;
+        let entry = StatementCacheEntry{
+            parameter_type_oids,
+            statement,
+            last_used: self.clock,
+        };
+        let replaced = self.entries.insert(query, entry);
+        let mut evicted = self.evict();
+        evicted.extend(replaced.map(|entry| entry.statement));
+        evicted
+    }
+
+    /// Remove the statement for a query from the cache.
+    pub fn remove(&mut self, query: &str) -> Option<Statement>
+    {
+        self.entries.remove(query).map(|entry| entry.statement)
+    }
+
+    /// Remove all statements from the cache.
+    pub fn clear(&mut self) -> Vec<Statement>
+    {
+        self.entries.drain().map(|(_, entry)| entry.statement).collect()
+    }
+
+    /// Change the capacity of the cache.
+    ///
+    /// Returns the statements that no longer fit in the cache.
+    pub fn set_capacity(&mut self, capacity: usize) -> Vec<Statement>
+    {
+        self.capacity = capacity;
+        self.evict()
+    }
+
+    /// Remove least recently used entries until the cache fits.
+    fn evict(&mut self) -> Vec<Statement>
+    {
+        let mut evicted = Vec::new();
+        while self.entries.len() > self.capacity {
+            let query = self.entries.iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(query, _)| query.clone())
+                .unwrap();
+            evicted.extend(self.remove(&query));
+        }
+        evicted
+    }
+}
+
+impl PgClient
+{
+    /// Create a named prepared statement.
+    ///
+    /// Unlike the statements in the statement cache,
+    /// this statement exists until it is closed with [`close_statement`]
+    /// or the connection is closed; it is never closed automatically.
+    /// See [`describe`] for the meaning of `parameter_type_oids`.
+    ///
+    /// [`close_statement`]: `Self::close_statement`
+    /// [`describe`]: `Self::describe`
+    pub fn prepare(&mut self, query: &str, parameter_type_oids: &[u32])
+        -> Result<Statement>
+    {
+        let name = self.new_statement_name();
+
+        self.write_messages(|buf| {
+            write_parse(buf, name.as_bytes(), query.as_bytes(),
+                        parameter_type_oids)?;
+            write_describe(buf, b'S', name.as_bytes())?;
+            write_sync(buf);
+            Ok(())
+        })?;
+        self.send()?;
+
+        let responses = self.receive_extended_query_responses()?;
+        let responses = responses.into_result()?;
+
+        let description = StatementDescription{
+            parameter_type_oids: responses.parameter_type_oids,
+            columns: responses.result.columns,
+        };
+        Ok(Statement::new(name, description))
+    }
+
+    /// Execute a prepared statement.
+    ///
+    /// See [`query_raw`] for the meaning of the arguments.
+    ///
+    /// [`query_raw`]: `Self::query_raw`
+    pub fn query_prepared(
+        &mut self,
+        statement: &Statement,
+        parameter_values: &[Option<&[u8]>],
+        parameter_format_codes: &[FormatCode],
+        result_format_codes: &[FormatCode],
+    ) -> Result<QueryResult>
+    {
+        self.write_messages(|buf| {
+            write_bind(
+                buf,
+                b"",
+                statement.name().as_bytes(),
+                parameter_format_codes,
+                parameter_values.len(),
+                |i, buf| write_raw_parameter_value(buf, parameter_values[i]),
+                result_format_codes,
+            )?;
+            write_execute(buf, b"", 0)?;
+            write_sync(buf);
+            Ok(())
+        })?;
+        self.send()?;
+
+        let responses = self.receive_extended_query_responses()?;
+        let mut result = responses.into_result()?.result;
+        result.columns =
+            result_columns(statement.columns(), result_format_codes);
+        Ok(result)
+    }
+
+    /// Close a prepared statement created with [`prepare`].
+    ///
+    /// The `Close` message is sent along with the next request.
+    ///
+    /// [`prepare`]: `Self::prepare`
+    pub fn close_statement(&mut self, statement: Statement) -> Result<()>
+    {
+        self.close_later(b'S', statement.name().as_bytes())
+    }
+
+    /// The maximum number of statements in the statement cache.
+    pub fn statement_cache_capacity(&self) -> usize
+    {
+        self.statement_cache.capacity()
+    }
+
+    /// Change the maximum number of statements in the statement cache.
+    ///
+    /// [`query_raw`] prepares named statements and caches them by query
+    /// string, so that executing the same query again skips parsing.
+    /// When a statement is evicted, it is closed on the server.
+    /// The default capacity is [`DEFAULT_STATEMENT_CACHE_CAPACITY`].
+    ///
+    /// A capacity of zero disables the cache, in which case [`query_raw`]
+    /// only uses the unnamed prepared statement. This is necessary
+    /// when connecting through a connection pooler that may
+    /// use a different server connection for each transaction,
+    /// such as PgBouncer in transaction pooling mode.
+    ///
+    /// [`query_raw`]: `Self::query_raw`
+    pub fn set_statement_cache_capacity(&mut self, capacity: usize)
+        -> Result<()>
+    {
+        for statement in self.statement_cache.set_capacity(capacity) {
+            self.close_statement(statement)?;
+        }
+        Ok(())
+    }
+
+    /// Implementation of [`query_raw`] for when the cache is enabled.
+    ///
+    /// If the statement is not yet cached, it is prepared in the same
+    /// round trip as it is executed. If the server reports that the
+    /// result type of a cached statement changed, which happens when
+    /// a table used by the statement is altered, the statement is
+    /// prepared again. This is not possible within a transaction block,
+    /// as the error aborts the transaction; in that case the error is
+    /// returned and the statement is prepared again on the next use.
+    ///
+    /// [`query_raw`]: `Self::query_raw`
+    pub (crate) fn query_cached(
+        &mut self,
+        query: &str,
+        parameter_type_oids: &[u32],
+        parameter_values: &[Option<&[u8]>],
+        parameter_format_codes: &[FormatCode],
+        result_format_codes: &[FormatCode],
+    ) -> Result<QueryResult>
+    {
+        let mut retried = false;
+
+        loop {
+            let cached =
+                self.statement_cache.get(query, parameter_type_oids);
+            let name = match &cached {
+                Some(statement) => statement.name().to_owned(),
+                None => self.new_statement_name(),
+            };
+
+            self.write_messages(|buf| {
+                if cached.is_none() {
+                    write_parse(buf, name.as_bytes(), query.as_bytes(),
+                                parameter_type_oids)?;
+                    write_describe(buf, b'S', name.as_bytes())?;
+                }
+                write_bind(
+                    buf,
+                    b"",
+                    name.as_bytes(),
+                    parameter_format_codes,
+                    parameter_values.len(),
+                    |i, buf| {
+                        write_raw_parameter_value(buf, parameter_values[i])
+                    },
+                    result_format_codes,
+                )?;
+                write_execute(buf, b"", 0)?;
+                write_sync(buf);
+                Ok(())
+            })?;
+            self.send()?;
+
+            let mut responses = self.receive_extended_query_responses()?;
+
+            let statement = match cached {
+                Some(ref statement) =>
+                    statement.clone(),
+
+                // The statement exists once it is parsed,
+                // even if binding or executing it failed.
+                None if responses.parse_complete => {
+                    let description = StatementDescription{
+                        parameter_type_oids:
+                            take(&mut responses.parameter_type_oids),
+                        columns: take(&mut responses.result.columns),
+                    };
+                    let statement = Statement::new(name, description);
+                    self.cache_statement(query, parameter_type_oids,
+                                         statement.clone())?;
+                    statement
+                },
+
+                None =>
+                    return responses.into_result().map(|r| r.result),
+            };
+
+            match responses.into_result() {
+                Ok(responses) => {
+                    let mut result = responses.result;
+                    result.columns = result_columns(
+                        statement.columns(),
+                        result_format_codes,
+                    );
+                    return Ok(result);
+                },
+                Err(Error::ErrorResponse(error))
+                    if cached.is_some()
+                    && is_cached_plan_result_type_change(&error) => {
+                    self.uncache_statement(query)?;
+                    let idle = self.transaction_status_indicator == b'I';
+                    if idle && !retried {
+                        retried = true;
+                        continue;
+                    }
+                    return Err(Error::ErrorResponse(error));
+                },
+                Err(error) =>
+                    return Err(error),
+            }
+        }
+    }
+
+    /// Find or prepare the statement for a query without parameter types.
+    ///
+    /// If the statement is not yet cached, it is prepared and cached.
+    /// If the cache is disabled, the query is described using
+    /// the unnamed prepared statement, and a statement with
+    /// an empty name is returned, which refers to the unnamed
+    /// prepared statement until another query replaces it.
+    pub (crate) fn cached_statement(&mut self, query: &str)
+        -> Result<Statement>
+    {
+        if self.statement_cache_capacity() == 0 {
+            let description = self.describe(query, &[])?;
+            return Ok(Statement::new(String::new(), description));
+        }
+
+        if let Some(statement) = self.statement_cache.get(query, &[]) {
+            return Ok(statement);
+        }
+
+        let statement = self.prepare(query, &[])?;
+        self.cache_statement(query, &[], statement.clone())?;
+        Ok(statement)
+    }
+
+    /// Insert a statement into the cache, closing evicted statements.
+    fn cache_statement(
+        &mut self,
+        query: &str,
+        parameter_type_oids: &[u32],
+        statement: Statement,
+    ) -> Result<()>
+    {
+        let evicted = self.statement_cache.insert(
+            query.to_owned(),
+            parameter_type_oids.to_owned(),
+            statement,
+        );
+        for statement in evicted {
+            self.close_statement(statement)?;
+        }
+        Ok(())
+    }
+
+    /// Remove the statement for a query from the cache and close it.
+    pub (crate) fn uncache_statement(&mut self, query: &str) -> Result<()>
+    {
+        if let Some(statement) = self.statement_cache.remove(query) {
+            self.close_statement(statement)?;
+        }
+        Ok(())
+    }
+
+    /// Generate a name for a new prepared statement.
+    ///
+    /// The names share a namespace with statements prepared using
+    /// the SQL `PREPARE` command, hence the distinctive prefix.
+    fn new_statement_name(&mut self) -> String
+    {
+        let id = self.next_statement_id;
+        self.next_statement_id += 1;
+        format!("ck_pg_client_{id}")
+    }
+}
+
+/// Whether an error indicates that a cached plan was invalidated.
+///
+/// The server reports this when the result type of a prepared statement
+/// changes, for instance because a column was added to a table
+/// that the statement selects all columns from.
+/// The message may be localized, so the routine name is checked as well.
+pub (crate) fn is_cached_plan_result_type_change(error: &ErrorResponse)
+    -> bool
+{
+    let field = |type_code| error.field(type_code).map(|v| v.to_bytes());
+    field(b'C') == Some(b"0A000") && (
+        field(b'R') == Some(b"RevalidateCachedQuery") ||
+        field(b'M') == Some(b"cached plan must not change result type")
+    )
+}
+
+/// The columns of a result given the statement's columns and format codes.
+///
+/// See [`PgClient::query_raw`] for the meaning of the format codes.
+pub (crate) fn result_columns(
+    columns: &[Column],
+    result_format_codes: &[FormatCode],
+) -> Vec<Column>
+{
+    columns.iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let format_code = match result_format_codes {
+                [] => FormatCode::Text,
+                [format_code] => *format_code,
+                _ => result_format_codes.get(i).copied()
+                        .unwrap_or(FormatCode::Text),
+            };
+            Column{format_code: format_code as u16, ..column.clone()}
+        })
+        .collect()
+}
@@ -0,0 +1,173 @@
+use {
+    crate::{
+        Error,
+        protocol::FormatCode,
+        types::{
+            Array,
+            Context,
+            Dimension,
+            Timestamp,
+            ToSql,
+            Type,
+            parse_array_literal,
+            parse_composite_literal,
+            write_composite_literal,
+        },
+    },
+    super::{WithCluster, connect, with_cluster},
+    std::{assert_matches::assert_matches, str},
+};
+
+#[test]
+fn array()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        // Arrays can be passed to = ANY.
+        let ids = vec![1i64, 3, i64::MAX];
+        let query = "SELECT x FROM unnest('{1,2,3}'::int8[]) AS x \
+                     WHERE x = ANY($1) ORDER BY x";
+        let rows = client.query(query, &[&ids]).unwrap();
+        let found: Vec<i64> = rows.iter().map(|r| r.get(0).unwrap()).collect();
+        assert_eq!(found, [1, 3]);
+        let rows = client.query(query, &[&&ids[.. 0]]).unwrap();
+        assert!(rows.is_empty());
+
+        // The server agrees with how we encode arrays.
+        let strings = vec!["a".to_owned(), "b c".to_owned(), String::new()];
+        let rows = client.query("SELECT $1::text[]::text, \
+                                        array_length($1::text[], 1)",
+                                &[&strings]).unwrap();
+        assert_eq!(rows[0].get::<&str, _>(0).unwrap(), r#"{a,"b c",""}"#);
+        assert_eq!(rows[0].get::<i32, _>(1).unwrap(), 3);
+
+        let nullable = vec![Some(1i32), None, Some(-1)];
+        let rows = client.query("SELECT $1::int4[]::text, $1::int4[]",
+                                &[&nullable]).unwrap();
+        assert_eq!(rows[0].get::<&str, _>(0).unwrap(), "{1,NULL,-1}");
+        assert_eq!(rows[0].get::<Vec<Option<i32>>, _>(1).unwrap(), nullable);
+        assert_matches!(rows[0].get::<Vec<i32>, _>(1),
+                        Err(Error::UnexpectedNull));
+
+        let rows = client.query("SELECT $1::int4[]::text, $1::int4[]",
+                                &[&Vec::<i32>::new()]).unwrap();
+        assert_eq!(rows[0].get::<&str, _>(0).unwrap(), "{}");
+        assert!(rows[0].get::<Vec<i32>, _>(1).unwrap().is_empty());
+
+        // Multi-dimensional arrays and lower bounds are preserved.
+        let dimensions = vec![
+            Dimension{length: 2, lower_bound: 0},
+            Dimension{length: 3, lower_bound: -1},
+        ];
+        let elements = vec![1i16, 2, 3, 4, 5, 6];
+        let array = Array::new(dimensions, elements).unwrap();
+        let rows = client.query("SELECT $1::int2[]::text, \
+                                        '[0:1][-1:1]={{1,2,3},{4,5,6}}'\
+                                        ::int2[]",
+                                &[&array]).unwrap();
+        assert_eq!(rows[0].get::<&str, _>(0).unwrap(),
+                   "[0:1][-1:1]={{1,2,3},{4,5,6}}");
+        assert_eq!(rows[0].get::<Array<i16>, _>(1).unwrap(), array);
+        assert_matches!(rows[0].get::<Vec<i16>, _>(1),
+                        Err(Error::Conversion(..)));
+
+        let rows = client.query("SELECT '[0:1]={a,b}'::varchar[], \
+                                        '{}'::int4[]",
+                                &[]).unwrap();
+        assert_eq!(rows[0].get::<Vec<String>, _>(0).unwrap(), ["a", "b"]);
+        let array = rows[0].get::<Array<String>, _>(0).unwrap();
+        assert_eq!(array.dimensions(),
+                   [Dimension{length: 2, lower_bound: 0}]);
+        assert_eq!(array.get(&[1]).unwrap(), "b");
+        let array = rows[0].get::<Array<i32>, _>(1).unwrap();
+        assert_eq!(array, Array::default());
+
+        // The element type must match the array type.
+        assert_matches!(client.query("SELECT $1::int4[]", &[&ids]),
+                        Err(Error::WrongType{..}));
+        let rows = client.query("SELECT '{1}'::int8[]", &[]).unwrap();
+        assert_matches!(rows[0].get::<Vec<i32>, _>(0),
+                        Err(Error::WrongType{..}));
+    });
+}
+
+#[test]
+fn array_text()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        // Arrays in text format decode to the same values
+        // as arrays in binary format.
+        let query = r#"SELECT '{1,NULL,3}'::int4[],
+                              ARRAY['a b', 'c"d\e', NULL, '', 'NULL', '{}'],
+                              '[0:1][1:2]={{1,2},{3,4}}'::int8[],
+                              ARRAY['\x00ff'::bytea],
+                              ARRAY['2024-02-09 07:08:09'::timestamp]"#;
+        let text = &client.simple_query(query).unwrap().into_rows().unwrap()[0];
+        let binary = &client.query(query, &[]).unwrap()[0];
+        macro_rules! check
+        {
+            ($index:literal, $ty:ty) => {
+                assert_eq!(text.get::<$ty, _>($index).unwrap(),
+                           binary.get::<$ty, _>($index).unwrap());
+            };
+        }
+        check!(0, Vec<Option<i32>>);
+        check!(1, Vec<Option<String>>);
+        check!(2, Array<i64>);
+        check!(3, Vec<Vec<u8>>);
+        check!(4, Vec<Timestamp>);
+
+        // Arrays of box use a different delimiter.
+        let rows = client.simple_query("SELECT ARRAY[box '(1,1),(0,0)', \
+                                                     box '(3,3),(2,2)']")
+            .unwrap().into_rows().unwrap();
+        let literal = str::from_utf8(rows[0].get_raw(0).unwrap().unwrap())
+            .unwrap();
+        let array = parse_array_literal(literal, b';').unwrap();
+        assert_eq!(array.elements(),
+                   [Some("(1,1),(0,0)".into()), Some("(3,3),(2,2)".into())]);
+
+        // The server parses arrays that we encode in text format.
+        let value = vec![Some("a b".to_owned()), None, Some(r#"c"d\e"#.into()),
+                         Some("NULL".to_owned()), Some(String::new())];
+        let cx = Context::new(&Type::TEXT_ARRAY, FormatCode::Text);
+        let mut buf = Vec::new();
+        value.to_sql(&cx, &mut buf).unwrap();
+        let result = client.query_raw(
+            r#"SELECT $1::text[] = ARRAY['a b', NULL, 'c"d\e', 'NULL', '']
+                                   IS NOT FALSE,
+                      array_length($1::text[], 1)"#,
+            &[],
+            &[Some(&buf)],
+            &[FormatCode::Text],
+            &[FormatCode::Text],
+        ).unwrap();
+        assert_eq!(result.rows, [[Some(b"t".to_vec()), Some(b"5".to_vec())]]);
+
+        // Composite literals are parsed and written like the server does.
+        let query = r#"SELECT ROW(1, 'x,y', NULL, '', 'a"b\c', ' d ')"#;
+        let rows = client.simple_query(query).unwrap().into_rows().unwrap();
+        let literal = str::from_utf8(rows[0].get_raw(0).unwrap().unwrap())
+            .unwrap();
+        let fields = parse_composite_literal(literal).unwrap();
+        let expected = [Some("1"), Some("x,y"), None, Some(""),
+                        Some(r#"a"b\c"#), Some(" d ")];
+        assert_eq!(fields, expected.map(|field| field.map(Into::into)));
+
+        let mut literal = String::new();
+        write_composite_literal(expected, &mut literal);
+        client.simple_query("CREATE TEMPORARY TABLE fields \
+                             (a int4, b text, c text, d text, \
+                              e text, f text)").unwrap();
+        let query = format!("SELECT $1::text::fields \
+                             IS NOT DISTINCT FROM {}::fields",
+                            &query["SELECT ".len() ..]);
+        let rows = client.query(&query, &[&literal]).unwrap();
+        assert!(rows[0].get::<bool, _>(0).unwrap());
+    });
+}
@@ -0,0 +1,45 @@
+use {
+    crate::{Error, types::BitString},
+    super::{WithCluster, connect, with_cluster},
+    std::assert_matches::assert_matches,
+};
+
+#[test]
+fn bit_string()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let bits: BitString = "1011001110".parse().unwrap();
+        let rows = client.query("SELECT $1::varbit, \
+                                        $1::bit(10), \
+                                        format('%s', $1::varbit), \
+                                        length($1::varbit), \
+                                        B'1100' # B'1010', \
+                                        ''::varbit, \
+                                        ARRAY[$1::varbit, $1]",
+                                &[&bits]).unwrap();
+        let row = &rows[0];
+        assert_eq!(row.get::<BitString, _>(0).unwrap(), bits);
+        assert_eq!(row.get::<BitString, _>(1).unwrap(), bits);
+        assert_eq!(row.get::<&str, _>(2).unwrap(), "1011001110");
+        assert_eq!(row.get::<i32, _>(3).unwrap(), 10);
+        assert_eq!(row.get::<BitString, _>(4).unwrap().to_string(), "0110");
+        assert!(row.get::<BitString, _>(5).unwrap().is_empty());
+        assert_eq!(row.get::<Vec<BitString>, _>(6).unwrap(),
+                   [bits.clone(), bits.clone()]);
+
+        // The length of bit(n) is checked by the server on assignment.
+        client.simple_query("CREATE TEMPORARY TABLE t (x bit(4))").unwrap();
+        assert_matches!(client.query("INSERT INTO t VALUES ($1)", &[&bits]),
+                        Err(Error::ErrorResponse(..)));
+
+        // The cast to bit(6) truncates the eight bits of X'F0'.
+        let rows = client.simple_query("SELECT B'1011001110', X'F0'::bit(6)")
+            .unwrap().into_rows().unwrap();
+        assert_eq!(rows[0].get::<BitString, _>(0).unwrap(), bits);
+        assert_eq!(rows[0].get::<BitString, _>(1).unwrap().to_string(),
+                   "111100");
+    });
+}
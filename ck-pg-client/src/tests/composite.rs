@@ -0,0 +1,127 @@
+use {
+    crate::{
+        Error,
+        types::{Kind, Record, Type},
+    },
+    super::{WithCluster, connect, with_cluster},
+    std::assert_matches::assert_matches,
+};
+
+#[test]
+fn record()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let rows = client.query("SELECT ROW(1, 'a'::text, NULL::text), \
+                                        ROW(2, ROW(3, 'b'), ARRAY[4, 5]), \
+                                        ARRAY[ROW(6, 'c'), ROW(7, 'd')]",
+                                &[]).unwrap();
+        assert_eq!(rows[0].columns()[0].data_type_oid, Type::RECORD.oid());
+
+        let value: (i32, String, Option<String>) = rows[0].get(0).unwrap();
+        assert_eq!(value, (1, "a".to_owned(), None));
+        let record: Record = rows[0].get(0).unwrap();
+        assert_eq!(record.len(), 3);
+        assert_eq!(record.field_type(1), Some(&Type::TEXT));
+        assert_eq!(record.get::<&str>(1).unwrap(), "a");
+
+        let value: (i32, (i32, &str), Vec<i32>) = rows[0].get(1).unwrap();
+        assert_eq!(value, (2, (3, "b"), vec![4, 5]));
+
+        let value: Vec<(i32, String)> = rows[0].get(2).unwrap();
+        assert_eq!(value, [(6, "c".to_owned()), (7, "d".to_owned())]);
+
+        // The number and types of the fields are checked.
+        assert_matches!(rows[0].get::<(i32, String), _>(0),
+                        Err(Error::Conversion(..)));
+        assert_matches!(rows[0].get::<(i64, String, Option<String>), _>(0),
+                        Err(Error::WrongType{..}));
+    });
+}
+
+#[test]
+fn composite()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        client.simple_query("CREATE TYPE item AS (id int8, name text, \
+                                                  tags text[]); \
+                             CREATE TYPE bag AS (item item, items item[]); \
+                             ALTER TYPE item ADD ATTRIBUTE gone int4; \
+                             ALTER TYPE item DROP ATTRIBUTE gone; \
+                             CREATE FUNCTION make_item(int8) RETURNS item \
+                             LANGUAGE sql AS $$ \
+                                 SELECT $1, 'item ' || $1, '{x,y}'::text[] \
+                             $$")
+            .unwrap();
+
+        // The type of a column is looked up when the query is prepared.
+        let rows = client.query("SELECT make_item(1)", &[]).unwrap();
+        assert_matches!(rows[0].get::<Record, _>(0), Ok(..));
+
+        let item = client.lookup_type("item").unwrap();
+        assert_eq!(item.name(), "item");
+        let Kind::Composite(fields) = item.kind()
+            else { panic!("{item:?}") };
+        let names: Vec<_> = fields.iter().map(|f| f.name()).collect();
+        assert_eq!(names, ["id", "name", "tags"]);
+        assert_eq!(*fields[2].ty(), Type::TEXT_ARRAY);
+
+        let rows = client.query("SELECT make_item(1)", &[]).unwrap();
+        let record: Record = rows[0].get(0).unwrap();
+        assert_eq!(record.field_name(1), Some("name"));
+        assert_eq!(record.get_by_name::<&str>("name").unwrap(), "item 1");
+        assert_eq!(record.get_by_name::<Vec<&str>>("tags").unwrap(),
+                   ["x", "y"]);
+
+        // Composite values are encoded as parameters, and arrays
+        // of the composite type are looked up along with it.
+        let value = (2i64, "two".to_owned(), vec!["z".to_owned()]);
+        let rows = client.query("SELECT $1::item, ($1::item).name, \
+                                        ARRAY[$1::item, make_item(3)]",
+                                &[&value]).unwrap();
+        assert_eq!(rows[0].get::<(i64, String, Vec<String>), _>(0).unwrap(),
+                   value);
+        assert_eq!(rows[0].get::<&str, _>(1).unwrap(), "two");
+        let items: Vec<(i64, &str, Option<Vec<&str>>)> =
+            rows[0].get(2).unwrap();
+        assert_eq!(items, [(2, "two", Some(vec!["z"])),
+                           (3, "item 3", Some(vec!["x", "y"]))]);
+
+        // Nested composite types are looked up recursively.
+        let bag = client.lookup_type("public.bag").unwrap();
+        let Kind::Composite(fields) = bag.kind() else { panic!("{bag:?}") };
+        assert_eq!(*fields[0].ty(), item);
+        let value = (value.clone(), vec![value.clone(), value]);
+        let rows = client.query("SELECT $1::bag", &[&value]).unwrap();
+        assert_eq!(rows[0].get::<(_, Vec<_>), _>(0).unwrap(), value);
+
+        assert_matches!(client.lookup_type("no_such_type"),
+                        Err(Error::ErrorResponse(..)));
+
+        // Fields of type `record` are only resolved to built-in types.
+        let rows = client.query("SELECT ROW(make_item(5))", &[]).unwrap();
+        let record: Record = rows[0].get(0).unwrap();
+        let ty = record.field_type(0).unwrap();
+        assert_eq!((ty.oid(), ty.name()), (item.oid(), ""));
+        assert_matches!(ty.kind(), Kind::Simple);
+        assert_matches!(rows[0].get::<((i64, &str, Vec<&str>),), _>(0),
+                        Err(Error::WrongType{..}));
+
+        // Changes to types go unnoticed until the types are cleared.
+        client.simple_query("ALTER TYPE item ADD ATTRIBUTE size int4")
+            .unwrap();
+        let rows = client.query("SELECT '(4,four,,)'::item", &[]).unwrap();
+        assert_matches!(rows[0].get::<Record, _>(0),
+                        Err(Error::Conversion(..)));
+        client.clear_types().unwrap();
+        let rows = client.query("SELECT '(4,four,,)'::item", &[]).unwrap();
+        let record: Record = rows[0].get(0).unwrap();
+        assert_eq!(record.field_name(3), Some("size"));
+        assert_eq!(record.get_by_name::<Option<i32>>("size").unwrap(), None);
+    });
+}
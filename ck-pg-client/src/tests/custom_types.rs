@@ -0,0 +1,140 @@
+use {
+    crate::{
+        Error,
+        Result,
+        types::{Context, FromSql, IsNull, Kind, Range, ToSql, Type},
+    },
+    super::{WithCluster, connect, with_cluster},
+    std::{assert_matches::assert_matches, collections::HashMap},
+};
+
+/// Rust enum for the enum type `mood`, which is accepted by name.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Mood
+{
+    Sad,
+    Ok,
+    Happy,
+}
+
+impl ToSql for Mood
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        ty.name() == "mood"
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        let label = match self {
+            Self::Sad => "sad",
+            Self::Ok => "ok",
+            Self::Happy => "happy",
+        };
+        label.to_sql(cx, buf)
+    }
+}
+
+impl FromSql<'_> for Mood
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        ty.name() == "mood"
+    }
+
+    fn from_sql(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        match <&str>::from_sql(cx, raw)? {
+            "sad" => Ok(Self::Sad),
+            "ok" => Ok(Self::Ok),
+            "happy" => Ok(Self::Happy),
+            label => Err(Error::Conversion(format!("mood {label}").into())),
+        }
+    }
+}
+
+#[test]
+fn custom_types()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        client.simple_query("CREATE EXTENSION hstore; \
+                             CREATE EXTENSION citext; \
+                             CREATE TYPE mood AS ENUM ('sad', 'ok', 'happy'); \
+                             CREATE DOMAIN posint AS int4 CHECK (VALUE > 0); \
+                             CREATE DOMAIN email AS citext; \
+                             CREATE TABLE person (email email, mood mood, \
+                                                  age posint, tags hstore, \
+                                                  moods mood[])")
+            .unwrap();
+
+        // The types are looked up without calling lookup_type.
+        let tags: HashMap<String, Option<String>> = [
+            ("a".to_owned(), Some("1".to_owned())),
+            ("b".to_owned(), None),
+        ].into();
+        client.query("INSERT INTO person VALUES ($1, $2, $3, $4, $5)",
+                     &[&"Me@Example.com", &Mood::Happy, &30, &tags,
+                       &vec!["sad", "ok"]])
+            .unwrap();
+
+        let rows = client.query("SELECT email, mood, age, tags, moods, \
+                                        email = 'me@example.COM', mood \
+                                 FROM person",
+                                &[]).unwrap();
+        let row = &rows[0];
+        assert_eq!(row.get::<&str, _>(0).unwrap(), "Me@Example.com");
+        assert_eq!(row.get::<Mood, _>(1).unwrap(), Mood::Happy);
+        assert_eq!(row.get::<String, _>(1).unwrap(), "happy");
+        assert_eq!(row.get::<i32, _>(2).unwrap(), 30);
+        assert_eq!(row.get::<HashMap<String, Option<String>>, _>(3).unwrap(),
+                   tags);
+        assert_eq!(row.get::<Vec<Mood>, _>(4).unwrap(), [Mood::Sad, Mood::Ok]);
+        assert!(row.get::<bool, _>(5).unwrap());
+        assert_matches!(row.get::<i32, _>(6), Err(Error::WrongType{..}));
+
+        let mood = client.lookup_type("mood").unwrap();
+        let Kind::Enum(labels) = mood.kind() else { panic!("{mood:?}") };
+        assert_eq!(labels, &["sad", "ok", "happy"]);
+        let posint = client.lookup_type("posint").unwrap();
+        let Kind::Domain(base_type) = posint.kind()
+            else { panic!("{posint:?}") };
+        assert_eq!(*base_type, Type::INT4);
+
+        // Constraints of domains are checked by the server.
+        assert_matches!(client.query("SELECT $1::posint", &[&0]),
+                        Err(Error::ErrorResponse(..)));
+        assert_matches!(client.query("SELECT $1::mood", &[&"meh"]),
+                        Err(Error::ErrorResponse(..)));
+        assert_matches!(client.query("SELECT $1::text", &[&Mood::Sad]),
+                        Err(Error::WrongType{..}));
+
+        // Arrays and ranges of domains are converted
+        // with the codec of the base type of the domain.
+        client.simple_query("CREATE TYPE posintrange \
+                             AS RANGE (subtype = posint)")
+            .unwrap();
+        let rows = client.query("SELECT $1::posint[], $2::posintrange",
+                                &[&vec![1, 2], &Range::from(1 .. 5)])
+            .unwrap();
+        assert_eq!(rows[0].get::<Vec<i32>, _>(0).unwrap(), [1, 2]);
+        assert_eq!(rows[0].get::<Range<i32>, _>(1).unwrap(),
+                   Range::from(1 .. 5));
+        assert_matches!(client.query("SELECT $1::posint[]", &[&vec![0]]),
+                        Err(Error::ErrorResponse(..)));
+
+        // Types are also looked up when the statement cache is disabled,
+        // in which case looking up types replaces the unnamed statement.
+        client.set_statement_cache_capacity(0).unwrap();
+        client.simple_query("CREATE TYPE size AS ENUM ('s', 'm', 'l'); \
+                             CREATE DOMAIN sizes AS size[]")
+            .unwrap();
+        let rows = client.query("SELECT $1::size, $2::sizes, 1",
+                                &[&"m", &vec!["s", "l"]]).unwrap();
+        assert_eq!(rows[0].get::<&str, _>(0).unwrap(), "m");
+        assert_eq!(rows[0].get::<Vec<&str>, _>(1).unwrap(), ["s", "l"]);
+        assert_eq!(rows[0].get::<i32, _>(2).unwrap(), 1);
+    });
+}
@@ -0,0 +1,209 @@
+use {
+    crate::{
+        Error,
+        Row,
+        types::{Date, Interval, Time, TimeTz, Timestamp, TimestampTz},
+    },
+    super::{WithCluster, connect, with_cluster},
+    std::assert_matches::assert_matches,
+};
+
+#[test]
+fn datetime()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        client.simple_query("SET TimeZone = 'UTC'").unwrap();
+        assert!(client.session_parameters().integer_datetimes());
+
+        let date = |y, m, d| Date::from_ymd(y, m, d).unwrap();
+        let time = |h, m, s, us| Time::from_hms_micro(h, m, s, us).unwrap();
+
+        // The server agrees with how we encode and format each value.
+        macro_rules! check
+        {
+            ($pg:literal, $value:expr) => {
+                let value = $value;
+                let query =
+                    concat!("SELECT $1::", $pg, ", $1::", $pg, "::text");
+                let rows = client.query(query, &[&value]).unwrap();
+                assert_eq!(rows[0].get::<_, _>(0).ok(), Some(value));
+                assert_eq!(rows[0].get::<&str, _>(1).unwrap(),
+                           value.to_string());
+            };
+        }
+
+        for value in [
+            date(2000, 1, 1), date(1, 1, 1), date(0, 12, 31),
+            date(-4712, 1, 1), date(5874897, 12, 31),
+            Date::INFINITY, Date::NEG_INFINITY,
+        ] {
+            check!("date", value);
+        }
+
+        for value in [
+            time(0, 0, 0, 0), time(12, 34, 56, 789_012), time(24, 0, 0, 0),
+        ] {
+            check!("time", value);
+            for utc_offset in [0, 3600, -(9 * 3600 + 30 * 60), 57599,
+                               -57599] {
+                check!("timetz", TimeTz::new(value, utc_offset).unwrap());
+            }
+        }
+
+        // The server rejects offsets of 16 hours, as does TimeTz::new.
+        for offset in ["+16", "-16"] {
+            let query = format!("SELECT '12:00{offset}'::timetz");
+            assert_matches!(client.query(&query, &[]),
+                            Err(Error::ErrorResponse(..)));
+        }
+
+        for value in [
+            Timestamp::new(date(2000, 1, 1), time(0, 0, 0, 0)).unwrap(),
+            Timestamp::new(date(-4712, 1, 1), time(0, 0, 0, 1)).unwrap(),
+            Timestamp::new(date(294276, 12, 31), time(23, 59, 59, 999_999))
+                .unwrap(),
+            Timestamp::INFINITY,
+            Timestamp::NEG_INFINITY,
+        ] {
+            check!("timestamp", value);
+            check!("timestamptz", TimestampTz::from_utc(value));
+        }
+
+        // The server parses the ISO 8601 format we use for intervals.
+        for value in [
+            Interval::default(),
+            Interval{months: 14, days: -3, microseconds: -3_723_000_004},
+            Interval{months: i32::MIN, days: i32::MAX, microseconds: i64::MAX},
+        ] {
+            let text = value.to_string();
+            let rows = client.query("SELECT $1::interval, $2::text::interval",
+                                    &[&value, &text]).unwrap();
+            assert_eq!(rows[0].get::<Interval, _>(0).unwrap(), value);
+            assert_eq!(rows[0].get::<Interval, _>(1).unwrap(), value);
+        }
+    });
+}
+
+#[test]
+fn datetime_text_styles()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let query = |bc_timestamp: &str| format!("
+            SELECT d::date, ts::timestamp, ts::timestamptz,
+                   i::interval, t::time, t::timetz
+            FROM (VALUES
+                ('2024-02-09', '2024-02-09 07:08:09.5',
+                 '1 year 2 mons 3 days 04:05:06.789', '07:08:09.5+05:30'),
+                ('0044-03-15 BC', '{bc_timestamp}',
+                 '-1 years -2 mons +3 days -04:05:06', '24:00:00-15:59'),
+                ('infinity', '2024-07-09 07:08:09.123456',
+                 '1 day -00:00:01', '00:00:00+00'),
+                ('5874897-12-31', '294276-12-30 23:59:59.999999',
+                 '-00:00:00.5', '12:34:56.000001-03'),
+                ('4714-11-24 BC', '-infinity',
+                 '-178956970 years -8 mons -2147483648 days', '00:00:00'),
+                ('2000-01-01', '2000-01-01 00:00:00', '0', '00:00:00')
+            ) v(d, ts, i, t)
+        ");
+
+        let styles = ["ISO", "SQL", "Postgres", "German"].into_iter()
+            .flat_map(|style| ["MDY", "DMY", "YMD"].map(|o| (style, o)));
+        let interval_styles =
+            ["postgres", "postgres_verbose", "sql_standard", "iso_8601"];
+        let time_zones = ["UTC", "America/Los_Angeles", "Europe/Amsterdam",
+                          "America/Sao_Paulo", "Asia/Kathmandu"];
+
+        for (date_style, date_order) in styles {
+            for interval_style in interval_styles {
+                for time_zone in time_zones {
+                    // Before time zones were adopted, the server formats
+                    // local mean time as LMT, which cannot be interpreted.
+                    let query = match (date_style, time_zone) {
+                        ("ISO", _) | (_, "UTC") =>
+                            query("0044-03-15 07:08:09 BC"),
+                        _ =>
+                            query("2024-02-29 00:00:00"),
+                    };
+
+                    // The settings take effect for the query
+                    // even though they are changed in the same request.
+                    let settings = format!("
+                        SET DateStyle = '{date_style}, {date_order}';
+                        SET IntervalStyle = '{interval_style}';
+                        SET TimeZone = '{time_zone}';
+                    ");
+                    let text_rows =
+                        client.simple_query(&(settings + &query)).unwrap()
+                            .into_rows().unwrap();
+                    let binary_rows = client.query(&query, &[]).unwrap();
+
+                    let context = format!("{date_style}, {date_order}, \
+                                           {interval_style}, {time_zone}");
+                    assert_eq!(text_rows.len(), binary_rows.len());
+                    for (text, binary) in text_rows.iter().zip(&binary_rows) {
+                        check_text_row(&context, text, binary);
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[test]
+fn datetime_text_styles_per_statement()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        // Each result is converted with the settings in effect
+        // when its statement was executed.
+        let query = "SELECT '2024-02-09'::date, '2024-02-09 07:08'::timestamp";
+        let results = client.simple_query_multi(&format!(
+            "{query}; SET DateStyle = 'SQL, DMY'; {query}",
+        )).unwrap().into_result().unwrap();
+        let binary_rows = client.query(query, &[]).unwrap();
+        assert_eq!(results.len(), 3);
+        for (index, result) in results.into_iter().enumerate().step_by(2) {
+            let text_rows = result.into_rows().unwrap();
+            let context = format!("statement {index}");
+            for (column, value) in ["2024-02-09", "2024-02-09 07:08:00"]
+                .into_iter().enumerate() {
+                let raw = text_rows[0].get_raw(column).unwrap().unwrap();
+                assert_eq!(raw == value.as_bytes(), index == 0, "{context}");
+            }
+            assert_eq!(text_rows[0].get::<Date, _>(0).ok(),
+                       Some(binary_rows[0].get::<Date, _>(0).unwrap()),
+                       "{context}");
+            assert_eq!(text_rows[0].get::<Timestamp, _>(1).ok(),
+                       Some(binary_rows[0].get::<Timestamp, _>(1).unwrap()),
+                       "{context}");
+        }
+    });
+}
+
+/// Check that values in text format decode to the same values
+/// as the corresponding values in binary format.
+fn check_text_row(context: &str, text: &Row, binary: &Row)
+{
+    macro_rules! check
+    {
+        ($index:literal, $ty:ty) => {
+            let raw = text.get_raw($index).unwrap().unwrap();
+            assert_eq!(text.get::<$ty, _>($index).ok(),
+                       Some(binary.get::<$ty, _>($index).unwrap()),
+                       "{context}: {}", String::from_utf8_lossy(raw));
+        };
+    }
+    check!(0, Date);
+    check!(1, Timestamp);
+    check!(2, TimestampTz);
+    check!(3, Interval);
+    check!(4, Time);
+    check!(5, TimeTz);
+}
@@ -0,0 +1,184 @@
+use {
+    crate::{Error, PipelineResult, protocol::FormatCode},
+    super::{WithCluster, connect, with_cluster},
+    std::assert_matches::assert_matches,
+};
+
+#[test]
+fn query_raw_format_codes()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let result = client.query_raw(
+            "SELECT $1::int4 + 1 AS a, $2::text AS b, $3::bytea AS c",
+            &[],
+            &[Some(&41i32.to_be_bytes()), Some(b"x"), None],
+            &[FormatCode::Binary, FormatCode::Text, FormatCode::Text],
+            &[FormatCode::Binary, FormatCode::Text, FormatCode::Text],
+        ).unwrap();
+
+        let format_codes: Vec<_> = result.columns.iter()
+            .map(|column| column.format_code)
+            .collect();
+        assert_eq!(format_codes, [1, 0, 0]);
+        assert_eq!(result.rows, [[
+            Some(42i32.to_be_bytes().to_vec()),
+            Some(b"x".to_vec()),
+            None,
+        ]]);
+        assert_eq!(result.command_tag.unwrap().to_bytes(), b"SELECT 1");
+
+        let result = client.query_raw("", &[], &[], &[], &[]).unwrap();
+        assert!(result.columns.is_empty());
+        assert!(result.command_tag.is_none());
+    });
+}
+
+#[test]
+fn query_raw_error_resynchronizes()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        // Error during Parse.
+        let error = client.query_raw("SELEC 1", &[], &[], &[], &[])
+            .unwrap_err();
+        assert_matches!(
+            error,
+            Error::ErrorResponse(response)
+                if response.code().unwrap().to_bytes() == b"42601",
+        );
+
+        // Error during Bind.
+        let error = client.query_raw("SELECT $1::int4", &[], &[Some(b"x")],
+                                     &[], &[])
+            .unwrap_err();
+        assert_matches!(
+            error,
+            Error::ErrorResponse(response)
+                if response.code().unwrap().to_bytes() == b"22P02",
+        );
+
+        // Error during Execute.
+        let error = client.query_raw("SELECT 1 / 0", &[], &[], &[], &[])
+            .unwrap_err();
+        assert_matches!(
+            error,
+            Error::ErrorResponse(response)
+                if response.code().unwrap().to_bytes() == b"22012",
+        );
+
+        let result = client.query_raw("SELECT 1", &[], &[], &[], &[])
+            .unwrap();
+        assert_eq!(result.rows, [[Some(b"1".to_vec())]]);
+    });
+}
+
+#[test]
+fn copy_resynchronizes()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        client.simple_query("CREATE TABLE t (x int)").unwrap();
+
+        let copy_in = "COPY t FROM STDIN";
+        let copy_out = "COPY (SELECT generate_series(1, 10000)) TO STDOUT";
+        let count = "SELECT count(*) FROM t";
+
+        // Both with and without the statement cache,
+        // and again once the statements are cached.
+        for capacity in [0, 16, 16] {
+            client.set_statement_cache_capacity(capacity).unwrap();
+            for query in [copy_in, copy_out] {
+                let error = client.query_raw(query, &[], &[], &[], &[])
+                    .unwrap_err();
+                assert_matches!(error, Error::CopyUnsupported);
+                let result = client.query_raw(count, &[], &[], &[], &[])
+                    .unwrap();
+                assert_eq!(result.rows, [[Some(b"0".to_vec())]]);
+            }
+        }
+
+        for query in [copy_in, copy_out] {
+            // The COPY starts once the row stream is created.
+            let mut stream = client.row_stream_raw(query, &[], &[], &[], &[])
+                .unwrap();
+            let error = stream.next_row().map(|_| ()).unwrap_err();
+            assert_matches!(error, Error::CopyUnsupported);
+            drop(stream);
+            client.row_stream_raw(query, &[], &[], &[], &[]).unwrap();
+
+            client.simple_query("BEGIN").unwrap();
+            let mut portal = client.portal_raw(query, &[], &[], &[], &[])
+                .unwrap();
+            assert_matches!(portal.fetch(0), Err(Error::CopyUnsupported));
+            drop(portal);
+            client.simple_query("ROLLBACK").unwrap();
+        }
+
+        // The server ignores the sync points after COPY FROM STDIN.
+        let mut pipeline = client.pipeline();
+        pipeline.query_raw("SELECT 1", &[], &[], &[], &[]).unwrap();
+        pipeline.sync();
+        pipeline.query_raw(copy_in, &[], &[], &[], &[]).unwrap();
+        pipeline.sync();
+        pipeline.sync();
+        assert_matches!(pipeline.next_result(),
+                        Ok(Some(PipelineResult::Query(_))));
+        assert_matches!(pipeline.next_result(),
+                        Ok(Some(PipelineResult::Sync)));
+        assert_matches!(pipeline.next_result(), Err(Error::CopyUnsupported));
+        assert_matches!(pipeline.next_result(), Ok(None));
+        drop(pipeline);
+
+        let result = client.query_raw(count, &[], &[], &[], &[]).unwrap();
+        assert_eq!(result.rows, [[Some(b"0".to_vec())]]);
+
+        // Queries after COPY TO STDOUT are executed,
+        // including those queued after the last sync point.
+        let mut pipeline = client.pipeline();
+        pipeline.query_raw(copy_out, &[], &[], &[], &[]).unwrap();
+        pipeline.sync();
+        pipeline.query_raw("INSERT INTO t VALUES (1)", &[], &[], &[], &[])
+            .unwrap();
+        pipeline.sync();
+        pipeline.query_raw("INSERT INTO t VALUES (2)", &[], &[], &[], &[])
+            .unwrap();
+        pipeline.flush().unwrap();
+        assert_matches!(pipeline.next_result(), Err(Error::CopyUnsupported));
+        assert_matches!(pipeline.next_result(), Ok(None));
+        drop(pipeline);
+
+        let result = client.simple_query("SELECT array_agg(x) FROM t")
+            .unwrap();
+        assert_eq!(result.rows, [[Some(b"{1,2}".to_vec())]]);
+    });
+}
+
+#[test]
+fn describe()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let description = client.describe(
+            "SELECT $1::int8 AS a, $2 || 'x' AS b",
+            &[0, 25],
+        ).unwrap();
+        assert_eq!(description.parameter_type_oids, [20, 25]);
+        let names: Vec<_> = description.columns.iter()
+            .map(|column| column.name.to_bytes())
+            .collect();
+        assert_eq!(names, [b"a", b"b"]);
+
+        let description = client.describe("SET search_path = public", &[])
+            .unwrap();
+        assert!(description.parameter_type_oids.is_empty());
+        assert!(description.columns.is_empty());
+    });
+}
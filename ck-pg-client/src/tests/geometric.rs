@@ -0,0 +1,103 @@
+use {
+    crate::types::{
+        Circle,
+        Line,
+        LineSegment,
+        Path,
+        Point,
+        Polygon,
+        Rect,
+    },
+    super::{WithCluster, connect, with_cluster},
+};
+
+#[test]
+fn geometric()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        // The server agrees with how we encode, format, and parse
+        // each value, and the text format decodes to the same value.
+        macro_rules! check
+        {
+            ($pg:literal, $ty:ty, $text:expr) => {
+                let value: $ty = $text.parse().unwrap();
+                let query = concat!("SELECT $1::", $pg, ", format('%s', $1::",
+                                    $pg, "), $2::text::", $pg);
+                let rows = client.query(query, &[&value, &$text]).unwrap();
+                assert_eq!(rows[0].get::<$ty, _>(0).unwrap(), value);
+                assert_eq!(rows[0].get::<&str, _>(1).unwrap(),
+                           value.to_string());
+                assert_eq!(rows[0].get::<$ty, _>(2).unwrap(), value);
+
+                let query = format!("SELECT '{}'::{}", $text, $pg);
+                let rows = client.simple_query(&query).unwrap()
+                    .into_rows().unwrap();
+                assert_eq!(rows[0].get::<$ty, _>(0).unwrap(), value);
+            };
+        }
+
+        for text in ["(1,2)", "-1.5, 2.25", "(Infinity,-Infinity)",
+                     "(0.001,12345.678)"] {
+            check!("point", Point, text);
+        }
+        check!("line", Line, "{1,-1,0}");
+        check!("line", Line, "{0,2.5,-Infinity}");
+        check!("lseg", LineSegment, "[(0,0),(1,1)]");
+        check!("lseg", LineSegment, "1,2,3,4");
+        check!("box", Rect, "(1,1),(0,0)");
+        check!("box", Rect, "(0,2),(3,-1)");
+        check!("path", Path, "[(0,0),(1,1),(2,0)]");
+        check!("path", Path, "((0,0),(1,1),(2,0))");
+        check!("path", Path, "[(5,5)]");
+        check!("polygon", Polygon, "((0,0),(1,1),(2,0))");
+        check!("polygon", Polygon, "0,0,4,0,4,4");
+        check!("circle", Circle, "<(0,0),1>");
+        check!("circle", Circle, "((1.5,-2),0.5)");
+
+        // Open and closed paths are distinguished by the server.
+        let open = Path{closed: false, points: vec![Point::new(0.0, 0.0),
+                                                    Point::new(1.0, 1.0)]};
+        let closed = Path{closed: true, ..open.clone()};
+        let rows = client.query("SELECT isopen($1), isclosed($2), \
+                                        popen($2), pclose($1)",
+                                &[&open, &closed]).unwrap();
+        assert!(rows[0].get::<bool, _>(0).unwrap());
+        assert!(rows[0].get::<bool, _>(1).unwrap());
+        assert_eq!(rows[0].get::<Path, _>(2).unwrap(), open);
+        assert_eq!(rows[0].get::<Path, _>(3).unwrap(), closed);
+
+        // Boxes are normalized, and are delimited by semicolons in arrays.
+        let a = Point::new(3.0, 0.0);
+        let b = Point::new(1.0, 2.0);
+        let rect = Rect::new(a, b);
+        let rows = client.query("SELECT box($1, $2), ARRAY[$3::box, $3], \
+                                        format('%s', ARRAY[$3::box, $3]), \
+                                        area($3), center($3), \
+                                        lseg($1, $2)",
+                                &[&a, &b, &rect]).unwrap();
+        assert_eq!(rows[0].get::<Rect, _>(0).unwrap(), rect);
+        assert_eq!(rows[0].get::<Vec<Rect>, _>(1).unwrap(), [rect, rect]);
+        assert_eq!(rows[0].get::<&str, _>(2).unwrap(),
+                   "{(3,2),(1,0);(3,2),(1,0)}");
+        assert_eq!(rows[0].get::<f64, _>(3).unwrap(), 4.0);
+        assert_eq!(rows[0].get::<Point, _>(4).unwrap(), Point::new(2.0, 1.0));
+        assert_eq!(rows[0].get::<LineSegment, _>(5).unwrap(),
+                   LineSegment{start: a, end: b});
+
+        let rows = client.simple_query("SELECT ARRAY['(0,0),(1,1)'::box, \
+                                                      '(2,2),(3,3)'::box], \
+                                               polygon(path '((0,0),(1,0))'), \
+                                               circle(point(1,1), 2)")
+            .unwrap().into_rows().unwrap();
+        assert_eq!(rows[0].get::<Vec<Rect>, _>(0).unwrap(),
+                   [Rect::new(Point::new(1.0, 1.0), Point::new(0.0, 0.0)),
+                    Rect::new(Point::new(3.0, 3.0), Point::new(2.0, 2.0))]);
+        assert_eq!(rows[0].get::<Polygon, _>(1).unwrap(),
+                   Polygon(vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)]));
+        assert_eq!(rows[0].get::<Circle, _>(2).unwrap(),
+                   Circle{center: Point::new(1.0, 1.0), radius: 2.0});
+    });
+}
@@ -0,0 +1,65 @@
+use {
+    crate::protocol::FormatCode,
+    super::{WithCluster, connect, with_cluster},
+};
+
+#[test]
+fn json()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        // The server normalizes jsonb but not json.
+        let text = r#"{"b": [1, 2.50], "a": "é"}"#;
+        let rows = client.query("SELECT $1::json, $1::jsonb",
+                                &[&text]).unwrap();
+        assert_eq!(rows[0].get::<&str, _>(0).unwrap(), text);
+        assert_eq!(rows[0].get::<&str, _>(1).unwrap(),
+                   r#"{"a": "é", "b": [1, 2.50]}"#);
+
+        // The version number is removed in binary format only.
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            let result = client.query_raw("SELECT '[]'::jsonb", &[], &[],
+                                          &[], &[format_code]).unwrap();
+            let rows = result.into_rows().unwrap();
+            assert_eq!(rows[0].get::<&str, _>(0).unwrap(), "[]");
+        }
+
+        let rows = client.query("SELECT $1::jsonb = '[1]'",
+                                &[&"[1]"]).unwrap();
+        assert!(rows[0].get::<bool, _>(0).unwrap());
+    });
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_serde_json()
+{
+    use {crate::types::Json, std::collections::BTreeMap};
+
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let value = serde_json::json!({"a": [1, 2.5, null, "é"], "b": {}});
+        let rows = client.query("SELECT $1::json, $1::jsonb, $1::jsonb::text",
+                                &[&value]).unwrap();
+        assert_eq!(rows[0].get::<serde_json::Value, _>(0).unwrap(), value);
+        assert_eq!(rows[0].get::<serde_json::Value, _>(1).unwrap(), value);
+        let text = rows[0].get::<&str, _>(2).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(text).unwrap(),
+                   value);
+
+        let map = BTreeMap::from([("x", vec![1, 2]), ("y", vec![])]);
+        let rows = client.query("SELECT $1::jsonb -> 'x', $1::jsonb",
+                                &[&Json(&map)]).unwrap();
+        assert_eq!(rows[0].get::<Json<Vec<i32>>, _>(0).unwrap().0, [1, 2]);
+        let Json(decoded) =
+            rows[0].get::<Json<BTreeMap<&str, Vec<i32>>>, _>(1).unwrap();
+        assert_eq!(decoded, map);
+
+        let rows = client.query("SELECT '\"x\"'::jsonb", &[]).unwrap();
+        assert!(rows[0].get::<Json<i32>, _>(0).is_err());
+    });
+}
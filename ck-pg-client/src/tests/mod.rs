@@ -19,8 +19,70 @@ use crate::capabilities::SslRustls;
 #[cfg(feature = "rustls")]
 mod rustls_util;
 
+mod array;
+mod bit_string;
+mod composite;
+mod custom_types;
+mod datetime;
+mod extended_query;
+mod geometric;
+mod json;
+mod money;
+mod network;
+mod numeric;
+mod pipeline;
+mod portal;
+#[cfg(feature = "postgis")]
+mod postgis;
+mod range;
+mod row;
+mod row_stream;
+mod scalar_types;
+mod simple_query;
+mod statement_cache;
+mod system;
+mod text_search;
+mod typed_query;
+mod vector;
 mod with_cluster;
 
+/// Connect to a cluster spawned by [`with_cluster`] without SSL.
+fn connect(port: u16) -> PgClient
+{
+    let options = ConnectionOptions{
+        host: "localhost".into(),
+        port,
+        dbname: "postgres".into(),
+        user: "postgres".into(),
+        password: None,
+        sslmode: Sslmode::Disable,
+    };
+
+    PgClient::connect(
+        &Md5Unavailable,
+        &SslUnavailable,
+        |notice| println!("{notice:?}"),
+        &options,
+    ).unwrap()
+}
+
+/// Create the extension if it is installed, and return whether it is.
+///
+/// Tests of codecs for extensions that are not bundled with PostgreSQL
+/// are skipped if the extension is not installed.
+fn create_extension(client: &mut PgClient, name: &str) -> bool
+{
+    let rows = client.query("SELECT FROM pg_available_extensions \
+                             WHERE name = $1",
+                            &[&name]).unwrap();
+    if rows.is_empty() {
+        println!("skipping test: extension {name} is not installed");
+        return false;
+    }
+    client.simple_query(&format!("CREATE EXTENSION {name}")).unwrap();
+    true
+}
+
 #[cfg(feature = "rustls")]
 #[test]
 fn connect_ssl_required_success()
@@ -0,0 +1,34 @@
+use {
+    crate::types::Money,
+    super::{WithCluster, connect, with_cluster},
+};
+
+#[test]
+fn money()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        client.simple_query("SET lc_monetary = 'C'").unwrap();
+
+        let rows = client.query("SELECT $1::money, \
+                                        format('%s', $1::money), \
+                                        $1::money * 2, \
+                                        '-12.34'::money",
+                                &[&Money(123456)]).unwrap();
+        let row = &rows[0];
+        assert_eq!(row.get::<Money, _>(0).unwrap(), Money(123456));
+        assert_eq!(row.get::<&str, _>(1).unwrap(), "$1,234.56");
+        assert_eq!(row.get::<Money, _>(2).unwrap(), Money(246912));
+        assert_eq!(row.get::<Money, _>(3).unwrap(), Money(-1234));
+
+        // The text output has a currency symbol and group separators.
+        let rows = client.simple_query("SELECT '1234.56'::money, \
+                                               '-12.34'::money, \
+                                               '-92233720368547758.08'::money")
+            .unwrap().into_rows().unwrap();
+        assert_eq!(rows[0].get::<Money, _>(0).unwrap(), Money(123456));
+        assert_eq!(rows[0].get::<Money, _>(1).unwrap(), Money(-1234));
+        assert_eq!(rows[0].get::<Money, _>(2).unwrap(), Money(i64::MIN));
+    });
+}
@@ -0,0 +1,79 @@
+use {
+    crate::types::{Cidr, Inet, MacAddr, MacAddr8, Uuid},
+    super::{WithCluster, connect, with_cluster},
+    std::net::IpAddr,
+};
+
+#[test]
+fn network()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        // The server agrees with how we encode, format, and parse
+        // each value, and the text format decodes to the same value.
+        macro_rules! check
+        {
+            ($pg:literal, $ty:ty, $text:expr) => {
+                let value: $ty = $text.parse().unwrap();
+                // Casting inet to text always includes the netmask,
+                // unlike the output function, which format uses.
+                let query = concat!("SELECT $1::", $pg, ", format('%s', $1::",
+                                    $pg, "), $2::text::", $pg);
+                let rows = client.query(query, &[&value, &$text]).unwrap();
+                assert_eq!(rows[0].get::<$ty, _>(0).unwrap(), value);
+                assert_eq!(rows[0].get::<&str, _>(1).unwrap(),
+                           value.to_string());
+                assert_eq!(rows[0].get::<$ty, _>(2).unwrap(), value);
+
+                let query = format!("SELECT '{}'::{}", $text, $pg);
+                let rows = client.simple_query(&query).unwrap()
+                    .into_rows().unwrap();
+                assert_eq!(rows[0].get::<$ty, _>(0).unwrap(), value);
+            };
+        }
+
+        for text in [
+            "192.168.0.1", "192.168.0.1/24", "10.0.0.0/8", "0.0.0.0/0",
+            "::1", "2001:db8::1/64", "::ffff:192.168.0.1", "::/0",
+            "fe80::1:2:3:4/128",
+        ] {
+            check!("inet", Inet, text);
+        }
+
+        for text in [
+            "192.168.0.0/24", "10.0.0.0/8", "192.168.0.1/32", "0.0.0.0/0",
+            "2001:db8::/32", "::1/128", "::/0",
+        ] {
+            check!("cidr", Cidr, text);
+        }
+
+        for text in ["::1", "192.168.0.1"] {
+            check!("inet", IpAddr, text);
+            let value: IpAddr = text.parse().unwrap();
+            let rows = client.query("SELECT $1::cidr", &[&value]).unwrap();
+            assert_eq!(rows[0].get::<IpAddr, _>(0).unwrap(), value);
+        }
+
+        check!("macaddr", MacAddr, "08:00:2b:01:02:03");
+        check!("macaddr", MacAddr, "ff:ff:ff:ff:ff:ff");
+        check!("macaddr8", MacAddr8, "08:00:2b:ff:fe:01:02:03");
+
+        check!("uuid", Uuid, "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11");
+        check!("uuid", Uuid, "00000000-0000-0000-0000-000000000000");
+
+        #[cfg(feature = "uuid")]
+        check!("uuid", ::uuid::Uuid, "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11");
+
+        // Values with a netmask cannot be decoded as host addresses.
+        let rows = client.query("SELECT '10.0.0.0/8'::inet", &[]).unwrap();
+        assert!(rows[0].get::<IpAddr, _>(0).is_err());
+
+        // Converting to cidr clears the bits right of the netmask.
+        let inet: Inet = "10.1.2.3/8".parse().unwrap();
+        let rows = client.query("SELECT $1::inet::cidr", &[&inet]).unwrap();
+        assert_eq!(rows[0].get::<Cidr, _>(0).unwrap().to_string(),
+                   "10.0.0.0/8");
+    });
+}
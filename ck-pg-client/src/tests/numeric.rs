@@ -0,0 +1,38 @@
+use {
+    crate::types::Numeric,
+    super::{WithCluster, connect, with_cluster},
+};
+
+#[test]
+fn numeric()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let huge = format!("-{}.{}", "9".repeat(131072), "9".repeat(16383));
+        let values = [
+            "0", "0.000", "-1", "12345.678", "0.00010", "10000", "1e100",
+            "-1e-100", "NaN", "Infinity", "-Infinity", &huge,
+        ];
+        for value in values {
+            let value: Numeric = value.parse().unwrap();
+
+            // The server agrees with how we encode and format the value.
+            let rows = client.query("SELECT $1::numeric, $1::numeric::text",
+                                    &[&value]).unwrap();
+            assert_eq!(rows[0].get::<Numeric, _>(0).unwrap(), value);
+            assert_eq!(rows[0].get::<&str, _>(1).unwrap(), value.to_string());
+
+            // The server agrees with how we parse the value.
+            let text = value.to_string();
+            let rows = client.query("SELECT $1::text::numeric",
+                                    &[&text]).unwrap();
+            assert_eq!(rows[0].get::<Numeric, _>(0).unwrap(), value);
+        }
+
+        let rows = client.query("SELECT 1.5e-3::numeric(10, 6)", &[]).unwrap();
+        assert_eq!(rows[0].get::<Numeric, _>(0).unwrap().to_string(),
+                   "0.001500");
+    });
+}
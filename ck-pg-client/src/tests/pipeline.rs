@@ -0,0 +1,84 @@
+use {
+    crate::PipelineResult,
+    super::{WithCluster, connect, with_cluster},
+    std::assert_matches::assert_matches,
+};
+
+#[test]
+fn pipeline_aborted_after_error()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        client.simple_query("CREATE TABLE t (x int)").unwrap();
+        let statement = client.prepare("SELECT count(*) FROM t", &[])
+            .unwrap();
+
+        let mut pipeline = client.pipeline();
+        pipeline.query_raw("INSERT INTO t VALUES ($1)", &[],
+                           &[Some(b"1")], &[], &[]).unwrap();
+        pipeline.query_raw("SELECT 1 / 0", &[], &[], &[], &[]).unwrap();
+        pipeline.query_raw("INSERT INTO t VALUES (2)", &[], &[], &[], &[])
+            .unwrap();
+        pipeline.sync();
+        pipeline.query_prepared(&statement, &[], &[], &[]).unwrap();
+        pipeline.sync();
+
+        assert_matches!(
+            pipeline.next_result().unwrap(),
+            Some(PipelineResult::Query(result))
+                if result.command_tag.as_deref().unwrap().to_bytes()
+                    == b"INSERT 0 1",
+        );
+        assert_matches!(
+            pipeline.next_result().unwrap(),
+            Some(PipelineResult::Error(error))
+                if error.code().unwrap().to_bytes() == b"22012",
+        );
+        assert_matches!(
+            pipeline.next_result().unwrap(),
+            Some(PipelineResult::Aborted),
+        );
+        assert_matches!(
+            pipeline.next_result().unwrap(),
+            Some(PipelineResult::Sync),
+        );
+
+        // The implicit transaction was rolled back, including the insert.
+        let Some(PipelineResult::Query(result)) = pipeline.next_result()
+            .unwrap() else { panic!() };
+        assert_eq!(result.columns[0].name.to_bytes(), b"count");
+        assert_eq!(result.rows, [[Some(b"0".to_vec())]]);
+
+        assert_matches!(
+            pipeline.next_result().unwrap(),
+            Some(PipelineResult::Sync),
+        );
+        assert_matches!(pipeline.next_result().unwrap(), None);
+    });
+}
+
+#[test]
+fn pipeline_drop_discards_results()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let mut pipeline = client.pipeline();
+        for i in 0 .. 100 {
+            let query = format!("SELECT {i}");
+            pipeline.query_raw(&query, &[], &[], &[], &[]).unwrap();
+        }
+        pipeline.flush().unwrap();
+        assert_matches!(
+            pipeline.next_result().unwrap(),
+            Some(PipelineResult::Query(result))
+                if result.rows == [[Some(b"0".to_vec())]],
+        );
+        drop(pipeline);
+
+        let result = client.simple_query("SELECT 'ok'").unwrap();
+        assert_eq!(result.rows, [[Some(b"ok".to_vec())]]);
+    });
+}
@@ -0,0 +1,58 @@
+use {
+    crate::Error,
+    super::{WithCluster, connect, with_cluster},
+    std::assert_matches::assert_matches,
+};
+
+#[test]
+fn portal_fetch_batches()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        let query = "SELECT x FROM generate_series(1, $1::int4) AS x";
+
+        let error = client.portal_raw(query, &[], &[Some(b"10")], &[], &[])
+            .map(|_| ())
+            .unwrap_err();
+        assert_matches!(error, Error::NotInTransactionBlock);
+
+        client.simple_query("BEGIN").unwrap();
+
+        let mut portal = client.portal_raw(query, &[], &[Some(b"10")],
+                                           &[], &[]).unwrap();
+        assert_eq!(portal.columns()[0].name.to_bytes(), b"x");
+
+        let mut batch_sizes = Vec::new();
+        let mut values = Vec::new();
+        while let Some(rows) = portal.fetch(4).unwrap() {
+            batch_sizes.push(rows.len());
+            values.extend(rows.into_iter().map(|row| row[0].clone()));
+        }
+        assert_eq!(batch_sizes, [4, 4, 2]);
+        assert_eq!(values.len(), 10);
+        assert_eq!(values[9], Some(b"10".to_vec()));
+        assert_eq!(portal.command_tag().unwrap().to_bytes(), b"SELECT 2");
+        assert!(portal.fetch(4).unwrap().is_none());
+        drop(portal);
+
+        // Exactly divisible: the last fetch returns no rows.
+        let statement = client.prepare(query, &[]).unwrap();
+        let mut portal = client.portal_prepared(&statement, &[Some(b"4")],
+                                                &[], &[]).unwrap();
+        assert_eq!(portal.fetch(2).unwrap().unwrap().len(), 2);
+        assert_eq!(portal.fetch(2).unwrap().unwrap().len(), 2);
+        assert!(portal.fetch(2).unwrap().is_none());
+        drop(portal);
+
+        // Dropped portals are closed.
+        let portal = client.portal_prepared(&statement, &[Some(b"4")],
+                                            &[], &[]).unwrap();
+        drop(portal);
+        let result = client.simple_query("SELECT count(*) FROM pg_cursors")
+            .unwrap();
+        assert_eq!(result.rows, [[Some(b"0".to_vec())]]);
+
+        client.simple_query("COMMIT").unwrap();
+    });
+}
@@ -0,0 +1,74 @@
+use {
+    crate::types::{Coordinate, Geometry, Shape},
+    super::{WithCluster, connect, create_extension, with_cluster},
+};
+
+#[test]
+fn postgis()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        if !create_extension(&mut client, "postgis") {
+            return;
+        }
+
+        let xy = Coordinate::xy;
+        let point = Geometry{srid: Some(4326), has_z: false, has_m: false,
+                             shape: Shape::Point(Some(xy(1.0, 2.0)))};
+        let triangle = vec![xy(0.0, 0.0), xy(1.0, 0.0), xy(0.0, 1.0),
+                            xy(0.0, 0.0)];
+        let collection = Geometry{
+            srid: None, has_z: false, has_m: false,
+            shape: Shape::GeometryCollection(vec![
+                Shape::Point(Some(xy(1.0, 2.0))),
+                Shape::LineString(vec![xy(0.0, 0.0), xy(1.0, 1.0)]),
+            ]),
+        };
+
+        // SRIDs, polygons and nested collections survive the round trip.
+        let rows = client.query("SELECT $1::geometry, \
+                                        ST_AsEWKT($1::geometry), \
+                                        ST_Area($2::geometry), \
+                                        ST_AsText($3::geometry), \
+                                        $1::geography, \
+                                        ARRAY[$1::geometry, $3]",
+                                &[&point,
+                                  &Geometry{
+                                      srid: None, has_z: false, has_m: false,
+                                      shape: Shape::Polygon(vec![triangle]),
+                                  },
+                                  &collection]).unwrap();
+        assert_eq!(rows[0].get::<Geometry, _>(0).unwrap(), point);
+        assert_eq!(rows[0].get::<&str, _>(1).unwrap(), "SRID=4326;POINT(1 2)");
+        assert_eq!(rows[0].get::<f64, _>(2).unwrap(), 0.5);
+        assert_eq!(rows[0].get::<&str, _>(3).unwrap(),
+                   "GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(0 0,1 1))");
+        assert_eq!(rows[0].get::<Geometry, _>(4).unwrap(), point);
+        assert_eq!(rows[0].get::<Vec<Geometry>, _>(5).unwrap(),
+                   [point.clone(), collection]);
+
+        // Z coordinates and empty points are written by the server only.
+        let rows = client.query("SELECT 'SRID=3857;LINESTRING Z \
+                                             (1 2 3, 4 5 6)'::geometry, \
+                                        'POINT EMPTY'::geometry, \
+                                        'POINT(1 2)'::geography",
+                                &[]).unwrap();
+        assert_eq!(rows[0].get::<Geometry, _>(0).unwrap(),
+                   Geometry{srid: Some(3857), has_z: true, has_m: false,
+                            shape: Shape::LineString(vec![
+                                Coordinate::xyz(1.0, 2.0, 3.0),
+                                Coordinate::xyz(4.0, 5.0, 6.0),
+                            ])});
+        assert_eq!(rows[0].get::<Geometry, _>(1).unwrap(),
+                   Geometry{srid: None, has_z: false, has_m: false,
+                            shape: Shape::Point(None)});
+        assert_eq!(rows[0].get::<Geometry, _>(2).unwrap(), point);
+
+        // In text format, the server sends EWKB as hexadecimal digits.
+        let rows = client.simple_query("SELECT 'SRID=4326;POINT(1 2)'\
+                                               ::geometry")
+            .unwrap().into_rows().unwrap();
+        assert_eq!(rows[0].get::<Geometry, _>(0).unwrap(), point);
+    });
+}
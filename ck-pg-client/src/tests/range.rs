@@ -0,0 +1,166 @@
+use {
+    crate::{
+        Error,
+        types::{
+            Date,
+            Kind,
+            Multirange,
+            Numeric,
+            Range,
+            Timestamp,
+            TimestampTz,
+            Type,
+        },
+    },
+    super::{WithCluster, connect, with_cluster},
+    std::{assert_matches::assert_matches, ops::Bound},
+};
+
+#[test]
+fn range()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        // Ranges of discrete types are normalized by the server.
+        let value = Range::NonEmpty{lower: Bound::Excluded(1),
+                                    upper: Bound::Included(5)};
+        let rows = client.query("SELECT $1::int4range, $2::int8range, \
+                                        '[3,3)'::int4range, \
+                                        '(,)'::int8range, \
+                                        format('%s', $1::int4range)",
+                                &[&value, &Range::from(-7i64 ..)]).unwrap();
+        assert_eq!(rows[0].get::<Range<i32>, _>(0).unwrap(),
+                   Range::from(2 .. 6));
+        assert_eq!(rows[0].get::<Range<i64>, _>(1).unwrap(),
+                   Range::from(-7 ..));
+        assert_eq!(rows[0].get::<Range<i32>, _>(2).unwrap(), Range::Empty);
+        assert_eq!(rows[0].get::<Range<i64>, _>(3).unwrap(),
+                   Range::NonEmpty{lower: Bound::Unbounded,
+                                   upper: Bound::Unbounded});
+        assert_eq!(rows[0].get::<&str, _>(4).unwrap(), "[2,6)");
+
+        // Continuous ranges keep their bounds.
+        let numerics = Range::NonEmpty{
+            lower: Bound::Excluded("1.5".parse::<Numeric>().unwrap()),
+            upper: Bound::Included("2.25".parse().unwrap()),
+        };
+        let hour = TimestampTz::from_microseconds(3_600_000_000);
+        let window = Range::from(TimestampTz::from_microseconds(0) .. hour);
+        let rows = client.query("SELECT $1::numrange, \
+                                        format('%s', $1::numrange), \
+                                        $2::tstzrange, \
+                                        $2 = '[2000-01-01 00:00+00, \
+                                               2000-01-01 01:00+00)' \
+                                             ::tstzrange, \
+                                        '[2000-01-01,)'::daterange, \
+                                        'empty'::tsrange",
+                                &[&numerics, &window]).unwrap();
+        assert_eq!(rows[0].get::<Range<Numeric>, _>(0).unwrap(), numerics);
+        assert_eq!(rows[0].get::<&str, _>(1).unwrap(), "(1.5,2.25]");
+        assert_eq!(rows[0].get::<Range<TimestampTz>, _>(2).unwrap(), window);
+        assert!(rows[0].get::<bool, _>(3).unwrap());
+        assert_eq!(rows[0].get::<Range<Date>, _>(4).unwrap(),
+                   Range::from(Date::from_days(0) ..));
+        assert!(rows[0].get::<Range<Timestamp>, _>(5).unwrap().is_empty());
+
+        // Multiranges are normalized by the server.
+        let value = Multirange(vec![Range::from(5 .. 7), Range::Empty,
+                                    Range::from(1 .. 3), Range::from(2 .. 4)]);
+        let rows = client.query("SELECT $1::int4multirange, \
+                                        format('%s', $1::int4multirange), \
+                                        '{}'::int8multirange, \
+                                        '{[2000-01-01,2000-01-03)}' \
+                                            ::datemultirange",
+                                &[&value]).unwrap();
+        assert_eq!(rows[0].get::<Multirange<i32>, _>(0).unwrap(),
+                   Multirange(vec![Range::from(1 .. 4), Range::from(5 .. 7)]));
+        assert_eq!(rows[0].get::<&str, _>(1).unwrap(), "{[1,4),[5,7)}");
+        assert_eq!(rows[0].get::<Multirange<i64>, _>(2).unwrap(),
+                   Multirange::default());
+        assert_eq!(rows[0].get::<Multirange<Date>, _>(3).unwrap(),
+                   Multirange(vec![Range::from(Date::from_days(0)
+                                               .. Date::from_days(2))]));
+
+        // The text format decodes to the same values.
+        let rows = client.simple_query("SELECT '(1,5]'::int4range, \
+                                          '(,1.5]'::numrange, \
+                                          '{[1,3), (5,)}'::int8multirange, \
+                                          'empty'::daterange")
+            .unwrap().into_rows().unwrap();
+        assert_eq!(rows[0].get::<Range<i32>, _>(0).unwrap(),
+                   Range::from(2 .. 6));
+        assert_eq!(rows[0].get::<Range<Numeric>, _>(1).unwrap(),
+                   Range::NonEmpty{lower: Bound::Unbounded,
+                                   upper: Bound::Included("1.5".parse()
+                                                          .unwrap())});
+        assert_eq!(rows[0].get::<Multirange<i64>, _>(2).unwrap(),
+                   Multirange(vec![Range::from(1 .. 3), Range::from(6 ..)]));
+        assert_eq!(rows[0].get::<Range<Date>, _>(3).unwrap(), Range::Empty);
+
+        // Bounds cannot be NULL.
+        let value = Range::from(Some(1) .. None);
+        assert_matches!(client.query("SELECT $1::int4range", &[&value]),
+                        Err(Error::Conversion(..)));
+
+        assert_matches!(client.query("SELECT $1::int4range",
+                                     &[&Range::from(1i64 .. 2)]),
+                        Err(Error::WrongType{..}));
+    });
+}
+
+#[test]
+fn custom_range()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        client.simple_query("CREATE TYPE floatrange AS RANGE \
+                                 (subtype = float8, \
+                                  multirange_type_name = floatmultirange)")
+            .unwrap();
+
+        let value = Range::from(0.5 ..= 1.5);
+
+        // A failed lookup in a transaction block
+        // does not abort the transaction.
+        client.simple_query("CREATE ROLE lookup_user; \
+                             REVOKE SELECT ON pg_catalog.pg_range \
+                                 FROM PUBLIC; \
+                             BEGIN; \
+                             SET LOCAL ROLE lookup_user")
+            .unwrap();
+        assert_matches!(client.query("SELECT $1::floatrange", &[&value]),
+                        Err(Error::ErrorResponse(..)));
+        assert_eq!(client.transaction_status_indicator(), b'T');
+        assert_eq!(client.query("SELECT 1", &[]).unwrap().len(), 1);
+        client.simple_query("ROLLBACK; \
+                             GRANT SELECT ON pg_catalog.pg_range TO PUBLIC")
+            .unwrap();
+
+        // The range type is looked up automatically.
+        let rows = client.query("SELECT $1::floatrange", &[&value]).unwrap();
+        assert_eq!(rows[0].get::<Range<f64>, _>(0).unwrap(), value);
+
+        let floatrange = client.lookup_type("floatrange").unwrap();
+        let Kind::Range(subtype) = floatrange.kind()
+            else { panic!("{floatrange:?}") };
+        assert_eq!(*subtype, Type::FLOAT8);
+        let floatmultirange = client.lookup_type("floatmultirange").unwrap();
+        let Kind::Multirange(range_type) = floatmultirange.kind()
+            else { panic!("{floatmultirange:?}") };
+        assert_eq!(*range_type, floatrange);
+
+        let multirange = Multirange(vec![Range::from(.. -1.0), value]);
+        let rows = client.query("SELECT $1::floatrange, \
+                                        $2::floatmultirange, \
+                                        ARRAY[$1::floatrange]",
+                                &[&value, &multirange]).unwrap();
+        assert_eq!(rows[0].get::<Range<f64>, _>(0).unwrap(), value);
+        assert_eq!(rows[0].get::<Multirange<f64>, _>(1).unwrap(),
+                   multirange);
+        assert_eq!(rows[0].get::<Vec<Range<f64>>, _>(2).unwrap(), [value]);
+    });
+}
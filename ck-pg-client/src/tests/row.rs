@@ -0,0 +1,46 @@
+use {super::{WithCluster, connect, with_cluster}, std::sync::Arc};
+
+#[test]
+fn rows_by_name()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        client.simple_query("
+            CREATE TABLE a (id int4, name text);
+            CREATE TABLE b (id int4, a_id int4);
+            INSERT INTO a VALUES (1, 'one'), (2, NULL);
+            INSERT INTO b VALUES (10, 1), (20, 2);
+        ").unwrap();
+
+        let query = "SELECT * FROM a JOIN b ON b.a_id = a.id ORDER BY a.id";
+        let rows = client.query_raw(query, &[], &[], &[], &[])
+            .unwrap()
+            .into_rows()
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(Arc::ptr_eq(rows[0].description(), rows[1].description()));
+
+        let row = &rows[1];
+        assert_eq!(row.description().indices_of("id"), [0, 2]);
+        assert_eq!(row.get_raw("id").unwrap(), Some(&b"2"[..]));
+        assert_eq!(row.get_raw(2).unwrap(), Some(&b"20"[..]));
+        assert_eq!(row.get_raw("name").unwrap(), None);
+        assert_eq!(row.get_raw("a_id").unwrap(), Some(&b"2"[..]));
+
+        // The duplicate columns come from different tables.
+        let first = row.column(0).unwrap();
+        let second = row.column(2).unwrap();
+        assert_ne!(first.table_oid, second.table_oid);
+        assert_eq!(first.attribute_number.unwrap().get(), 1);
+        assert_eq!(first.data_type_oid, 23);
+        assert_eq!(first.format_code, 0);
+
+        // Rows received by a row stream can be looked up by name too.
+        let mut stream = client.row_stream_raw(query, &[], &[], &[], &[])
+            .unwrap();
+        let row = stream.next_row().unwrap().unwrap();
+        assert_eq!(row.get_raw("name").unwrap(), Some(&b"one"[..]));
+        assert_eq!(row.to_row().unwrap().get_raw(3).unwrap(), Some(&b"1"[..]));
+    });
+}
@@ -0,0 +1,64 @@
+use {
+    crate::{Error, protocol::FormatCode},
+    super::{WithCluster, connect, with_cluster},
+    std::assert_matches::assert_matches,
+};
+
+#[test]
+fn row_stream_rows()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let query = "SELECT x, NULLIF(x, 2) FROM generate_series(1, $1) AS x";
+        let mut stream = client.row_stream_raw(query, &[23], &[Some(b"3")],
+                                               &[], &[]).unwrap();
+        assert_eq!(stream.columns().len(), 2);
+        let mut rows = Vec::new();
+        while let Some(row) = stream.next_row().unwrap() {
+            assert_eq!(row.columns()[0].name.to_bytes(), b"x");
+            rows.push(row.to_raw_row());
+        }
+        assert_eq!(rows, [
+            [Some(b"1".to_vec()), Some(b"1".to_vec())],
+            [Some(b"2".to_vec()), None],
+            [Some(b"3".to_vec()), Some(b"3".to_vec())],
+        ]);
+        assert_eq!(stream.command_tag().unwrap().to_bytes(), b"SELECT 3");
+        assert!(stream.next_row().unwrap().is_none());
+        drop(stream);
+
+        // Errors in the middle of the stream.
+        let query = "SELECT 1 / (3 - x) FROM generate_series(1, 5) AS x";
+        let mut stream = client.row_stream_raw(query, &[], &[], &[], &[])
+            .unwrap();
+        assert!(stream.next_row().unwrap().is_some());
+        assert!(stream.next_row().unwrap().is_some());
+        let error = stream.next_row().map(|_| ()).unwrap_err();
+        assert_matches!(
+            error,
+            Error::ErrorResponse(e) if e.code().unwrap().to_bytes() == b"22012",
+        );
+        drop(stream);
+
+        // Errors before the first row.
+        let error = client.row_stream_raw("SELEC", &[], &[], &[], &[])
+            .map(|_| ())
+            .unwrap_err();
+        assert_matches!(error, Error::ErrorResponse(_));
+
+        // Dropping a stream discards the remaining rows.
+        let query = "SELECT x FROM generate_series(0, 9) AS x";
+        let statement = client.prepare(query, &[]).unwrap();
+        let mut stream = client.row_stream_prepared(&statement, &[], &[],
+                                                    &[FormatCode::Binary])
+            .unwrap();
+        let row = stream.next_row().unwrap().unwrap();
+        assert_eq!(row.values().next(), Some(Some(&[0, 0, 0, 0][..])));
+        drop(stream);
+
+        let result = client.simple_query("SELECT 1").unwrap();
+        assert_eq!(result.rows, [[Some(b"1".to_vec())]]);
+    });
+}
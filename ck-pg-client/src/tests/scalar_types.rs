@@ -0,0 +1,92 @@
+use {
+    crate::{Error, types::Type},
+    super::{WithCluster, connect, with_cluster},
+    std::assert_matches::assert_matches,
+};
+
+#[test]
+fn scalar_types()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let query = "SELECT $1::bool, $2::\"char\", $3::int2, $4::int4, \
+                            $5::int8, $6::float4, $7::float8, $8::oid, \
+                            $9::text, $10::bytea";
+        let bytes: Vec<u8> = (0 ..= 255).collect();
+        let rows = client.query(query, &[
+            &true, &-1i8, &i16::MIN, &i32::MAX, &i64::MIN,
+            &f32::NEG_INFINITY, &f64::MIN_POSITIVE, &u32::MAX,
+            &"\u{1F418}", &bytes,
+        ]).unwrap();
+        let row = &rows[0];
+        assert!(row.get::<bool, _>(0).unwrap());
+        assert_eq!(row.get::<i8, _>(1).unwrap(), -1);
+        assert_eq!(row.get::<i16, _>(2).unwrap(), i16::MIN);
+        assert_eq!(row.get::<i32, _>(3).unwrap(), i32::MAX);
+        assert_eq!(row.get::<i64, _>(4).unwrap(), i64::MIN);
+        assert_eq!(row.get::<f32, _>(5).unwrap(), f32::NEG_INFINITY);
+        assert_eq!(row.get::<f64, _>(6).unwrap(), f64::MIN_POSITIVE);
+        assert_eq!(row.get::<u32, _>(7).unwrap(), u32::MAX);
+        assert_eq!(row.get::<&str, _>(8).unwrap(), "\u{1F418}");
+        assert_eq!(row.get::<&[u8], _>(9).unwrap(), bytes);
+
+        let rows = client.query(
+            "SELECT 'ab'::char(4), 'cd'::varchar, 'ef'::name, 'NaN'::float8",
+            &[],
+        ).unwrap();
+        let row = &rows[0];
+        assert_eq!(row.get::<String, _>(0).unwrap(), "ab  ");
+        assert_eq!(row.get::<String, _>(1).unwrap(), "cd");
+        assert_eq!(row.get::<String, _>(2).unwrap(), "ef");
+        assert!(row.get::<f64, _>(3).unwrap().is_nan());
+
+        // Text format, as used by simple queries.
+        let result = client.simple_query(
+            "SELECT 'f'::bool, '\\377'::\"char\", '-Infinity'::float4, \
+                    '\\x0001ff'::bytea"
+        ).unwrap();
+        let row = result.into_rows().unwrap().remove(0);
+        assert!(!row.get::<bool, _>(0).unwrap());
+        assert_eq!(row.get::<i8, _>(1).unwrap(), -1);
+        assert_eq!(row.get::<f32, _>(2).unwrap(), f32::NEG_INFINITY);
+        assert_eq!(row.get::<Vec<u8>, _>(3).unwrap(), [0x00, 0x01, 0xFF]);
+
+        // OID alias types, transaction identifiers, and xml.
+        let rows = client.query(
+            "SELECT 'pg_class'::regclass, $1::regtype::text, $2::xid, \
+                    $3::xid8, $4::cid, $5::xml",
+            &[&23u32, &u32::MAX, &u64::MAX, &7u32, &"<a>b</a>"],
+        ).unwrap();
+        let row = &rows[0];
+        assert_eq!(row.get::<u32, _>(0).unwrap(), 1259);
+        assert_eq!(row.get::<&str, _>(1).unwrap(), "integer");
+        assert_eq!(row.get::<u32, _>(2).unwrap(), u32::MAX);
+        assert_eq!(row.get::<u64, _>(3).unwrap(), u64::MAX);
+        assert_eq!(row.get::<u32, _>(4).unwrap(), 7);
+        assert_eq!(row.get::<&str, _>(5).unwrap(), "<a>b</a>");
+
+        // In text format, OID alias types are names.
+        let result = client.simple_query(
+            "SELECT oid::regclass, relnamespace::regnamespace, \
+                    reltype::regtype \
+             FROM pg_class WHERE relname = 'pg_class'"
+        ).unwrap();
+        let row = result.into_rows().unwrap().remove(0);
+        assert_eq!(row.get::<&str, _>(0).unwrap(), "pg_class");
+        assert_eq!(row.get::<String, _>(1).unwrap(), "pg_catalog");
+        assert_eq!(row.get::<&str, _>(2).unwrap(), "pg_class");
+        assert_matches!(rows[0].get::<&str, _>(0),
+                        Err(Error::Conversion(..)));
+
+        // The server checks that xml is well-formed.
+        let error = client.query("SELECT $1::xml", &[&"<a>"]).unwrap_err();
+        assert_matches!(error, Error::ErrorResponse(..));
+
+        // Integer types are not converted implicitly.
+        let error = client.query("SELECT $1::int8", &[&1i32]).unwrap_err();
+        assert_matches!(error, Error::WrongType{pg_type, ..}
+                               if pg_type == Type::INT8);
+    });
+}
@@ -0,0 +1,127 @@
+use {
+    crate::Error,
+    super::{WithCluster, connect, with_cluster},
+    std::{assert_matches::assert_matches, ffi::CString},
+};
+
+#[test]
+fn simple_query_rows()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let result = client.simple_query(
+            "SELECT 1 AS a, NULL::text AS b UNION ALL SELECT 2, 'x'"
+        ).unwrap();
+
+        let names: Vec<_> = result.columns.iter()
+            .map(|column| column.name.to_bytes())
+            .collect();
+        assert_eq!(names, [b"a", b"b"]);
+        assert_eq!(result.columns[0].data_type_oid, 23);
+        assert_eq!(result.columns[0].format_code, 0);
+
+        assert_eq!(result.rows, [
+            [Some(b"1".to_vec()), None],
+            [Some(b"2".to_vec()), Some(b"x".to_vec())],
+        ]);
+        assert_eq!(result.command_tag.unwrap().to_bytes(), b"SELECT 2");
+
+        let result = client.simple_query("").unwrap();
+        assert!(result.columns.is_empty());
+        assert!(result.rows.is_empty());
+        assert!(result.command_tag.is_none());
+    });
+}
+
+#[test]
+fn simple_query_error()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let error = client.simple_query("SELECT * FROM nonexistent")
+            .unwrap_err();
+        assert_matches!(
+            error,
+            Error::ErrorResponse(response)
+                if response.code().unwrap().to_bytes() == b"42P01",
+        );
+
+        // The client must be usable after the error.
+        let result = client.simple_query("SET DateStyle = 'German'").unwrap();
+        assert_eq!(result.command_tag.unwrap().to_bytes(), b"SET");
+
+        let name = CString::new("DateStyle").unwrap();
+        let date_style = client.parameter_status(&name).unwrap();
+        assert_eq!(date_style.to_bytes(), b"German, DMY");
+    });
+}
+
+#[test]
+fn simple_query_copy()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        client.simple_query("CREATE TABLE t (x int)").unwrap();
+
+        let error = client.simple_query("COPY t FROM STDIN").unwrap_err();
+        assert_matches!(error, Error::CopyUnsupported);
+
+        // The client must be usable after the rejected COPY.
+        let result = client.simple_query("SELECT count(*) FROM t").unwrap();
+        assert_eq!(result.rows, [[Some(b"0".to_vec())]]);
+
+        let error = client.simple_query(
+            "COPY (SELECT generate_series(1, 10000)) TO STDOUT"
+        ).unwrap_err();
+        assert_matches!(error, Error::CopyUnsupported);
+
+        let result = client.simple_query("SELECT 1").unwrap();
+        assert_eq!(result.rows, [[Some(b"1".to_vec())]]);
+
+        // Statements after a COPY FROM STDIN are not executed.
+        let error = client.simple_query_multi(
+            "COPY t FROM STDIN; INSERT INTO t VALUES (1)"
+        ).unwrap_err();
+        assert_matches!(error, Error::CopyUnsupported);
+
+        let result = client.simple_query("SELECT count(*) FROM t").unwrap();
+        assert_eq!(result.rows, [[Some(b"0".to_vec())]]);
+    });
+}
+
+#[test]
+fn simple_query_multi()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let results = client.simple_query_multi(
+            "CREATE TABLE t (x int);
+             INSERT INTO t VALUES (1), (2);
+             SELECT x FROM t ORDER BY x;"
+        ).unwrap();
+        assert!(results.error.is_none());
+        let tags: Vec<_> = results.results.iter()
+            .map(|result| result.command_tag.as_ref().unwrap().to_bytes())
+            .collect();
+        assert_eq!(tags, [&b"CREATE TABLE"[..], b"INSERT 0 2", b"SELECT 2"]);
+        assert!(results.results[0].columns.is_empty());
+        assert_eq!(results.results[2].columns.len(), 1);
+        assert_eq!(results.results[2].rows.len(), 2);
+
+        let results = client.simple_query_multi(
+            "SELECT 1; SELECT 1 / 0; SELECT 3;"
+        ).unwrap();
+        assert_eq!(results.results.len(), 1);
+        assert_eq!(results.results[0].rows, [[Some(b"1".to_vec())]]);
+        let error = results.error.unwrap();
+        assert_eq!(error.statement_index, 1);
+        assert_eq!(error.error_response.code().unwrap().to_bytes(), b"22012");
+    });
+}
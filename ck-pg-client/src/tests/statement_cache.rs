@@ -0,0 +1,108 @@
+use {
+    crate::{Error, PgClient},
+    super::{WithCluster, connect, with_cluster},
+    std::assert_matches::assert_matches,
+};
+
+/// The names of the prepared statements that exist on the server.
+fn prepared_statements(client: &mut PgClient) -> Vec<Vec<u8>>
+{
+    let result = client.simple_query(
+        "SELECT name FROM pg_prepared_statements ORDER BY name"
+    ).unwrap();
+    result.rows.into_iter()
+        .map(|row| row[0].clone().unwrap())
+        .collect()
+}
+
+#[test]
+fn statement_cache_eviction()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        client.set_statement_cache_capacity(2).unwrap();
+
+        for query in ["SELECT 1", "SELECT 2", "SELECT 1", "SELECT 3"] {
+            client.query_raw(query, &[], &[], &[], &[]).unwrap();
+        }
+
+        // "SELECT 2" was least recently used, so it was evicted.
+        assert_eq!(
+            prepared_statements(&mut client),
+            [b"ck_pg_client_0".to_vec(), b"ck_pg_client_2".to_vec()],
+        );
+
+        client.set_statement_cache_capacity(0).unwrap();
+        assert!(prepared_statements(&mut client).is_empty());
+
+        let result = client.query_raw("SELECT 4", &[], &[], &[], &[])
+            .unwrap();
+        assert_eq!(result.rows, [[Some(b"4".to_vec())]]);
+        assert!(prepared_statements(&mut client).is_empty());
+    });
+}
+
+#[test]
+fn statement_cache_result_type_change()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        let select = |client: &mut PgClient| {
+            client.query_raw("SELECT * FROM t", &[], &[], &[], &[])
+        };
+
+        client.simple_query("CREATE TABLE t (a int)").unwrap();
+        assert_eq!(select(&mut client).unwrap().columns.len(), 1);
+
+        // Outside a transaction block, the statement is prepared again.
+        client.simple_query("ALTER TABLE t ADD b int").unwrap();
+        assert_eq!(select(&mut client).unwrap().columns.len(), 2);
+
+        // Inside a transaction block, the error is returned,
+        // and the statement is prepared again on the next use.
+        client.simple_query("BEGIN").unwrap();
+        client.simple_query("ALTER TABLE t ADD c int").unwrap();
+        assert_matches!(
+            select(&mut client).unwrap_err(),
+            Error::ErrorResponse(response)
+                if response.code().unwrap().to_bytes() == b"0A000",
+        );
+        assert_eq!(client.transaction_status_indicator(), b'E');
+        client.simple_query("ROLLBACK").unwrap();
+        assert_eq!(client.transaction_status_indicator(), b'I');
+        assert_eq!(select(&mut client).unwrap().columns.len(), 2);
+    });
+}
+
+#[test]
+fn prepare_and_close()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        client.set_statement_cache_capacity(0).unwrap();
+
+        let statement = client.prepare("SELECT $1::int4 * 2 AS x", &[])
+            .unwrap();
+        assert_eq!(statement.parameter_type_oids(), [23]);
+        assert_eq!(statement.columns()[0].name.to_bytes(), b"x");
+
+        for i in 0 .. 3 {
+            let value = i.to_string();
+            let result = client.query_prepared(
+                &statement,
+                &[Some(value.as_bytes())],
+                &[],
+                &[],
+            ).unwrap();
+            let expected = (i * 2).to_string().into_bytes();
+            assert_eq!(result.rows, [[Some(expected)]]);
+        }
+
+        assert_eq!(prepared_statements(&mut client).len(), 1);
+        client.close_statement(statement).unwrap();
+        assert!(prepared_statements(&mut client).is_empty());
+    });
+}
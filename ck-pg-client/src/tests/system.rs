@@ -0,0 +1,62 @@
+use {
+    crate::types::{PgLsn, Snapshot, Tid},
+    super::{WithCluster, connect, with_cluster},
+};
+
+#[test]
+fn system()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        // pg_snapshot and txid_snapshot share their binary format.
+        let tid = Tid{block: 3, offset: 7};
+        let lsn = PgLsn(0x16_B374_D848);
+        let snapshot = Snapshot{xmin: 10, xmax: 20, xip: vec![10, 14, 15]};
+        let rows = client.query("SELECT $1::tid, \
+                                        format('%s', $1::tid), \
+                                        $2::pg_lsn, \
+                                        ($2::pg_lsn - '16/B374D800')::int8, \
+                                        $3::pg_snapshot, \
+                                        format('%s', $3::pg_snapshot), \
+                                        $4::txid_snapshot, \
+                                        pg_visible_in_snapshot('14', $3)",
+                                &[&tid, &lsn, &snapshot, &snapshot])
+            .unwrap();
+        let row = &rows[0];
+        assert_eq!(row.get::<Tid, _>(0).unwrap(), tid);
+        assert_eq!(row.get::<&str, _>(1).unwrap(), "(3,7)");
+        assert_eq!(row.get::<PgLsn, _>(2).unwrap(), lsn);
+        assert_eq!(row.get::<i64, _>(3).unwrap(), 0x48);
+        assert_eq!(row.get::<Snapshot, _>(4).unwrap(), snapshot);
+        assert_eq!(row.get::<&str, _>(5).unwrap(), "10:20:10,14,15");
+        assert_eq!(row.get::<Snapshot, _>(6).unwrap(), snapshot);
+        assert!(!row.get::<bool, _>(7).unwrap());
+
+        // xmin is a transaction ID, which is decoded as u32.
+        client.simple_query("CREATE TEMPORARY TABLE t (x int)").unwrap();
+        client.simple_query("INSERT INTO t VALUES (1), (2)").unwrap();
+        let rows = client.query("SELECT ctid FROM t ORDER BY x", &[])
+            .unwrap();
+        assert_eq!(rows[1].get::<Tid, _>(0).unwrap(),
+                   Tid{block: 0, offset: 2});
+        let rows = client.query("SELECT pg_current_wal_lsn(), \
+                                        pg_current_snapshot(), \
+                                        xmin \
+                                 FROM t LIMIT 1", &[]).unwrap();
+        assert!(rows[0].get::<PgLsn, _>(0).unwrap() > PgLsn(0));
+        let current = rows[0].get::<Snapshot, _>(1).unwrap();
+        assert!(current.xmin <= current.xmax);
+        assert!(rows[0].get::<u32, _>(2).unwrap() > 0);
+
+        // In text format, LSNs are two hexadecimal halves.
+        let rows = client.simple_query("SELECT '(3,7)'::tid, \
+                                               '16/B374D848'::pg_lsn, \
+                                               '10:20:10,14,15'::pg_snapshot")
+            .unwrap().into_rows().unwrap();
+        assert_eq!(rows[0].get::<Tid, _>(0).unwrap(), tid);
+        assert_eq!(rows[0].get::<PgLsn, _>(1).unwrap(), lsn);
+        assert_eq!(rows[0].get::<Snapshot, _>(2).unwrap(), snapshot);
+    });
+}
@@ -0,0 +1,108 @@
+use {
+    crate::{
+        Error,
+        types::{
+            TsLexeme,
+            TsPosition,
+            TsQuery,
+            TsQueryNode,
+            TsVector,
+            TsWeight,
+            TsWeights,
+        },
+    },
+    super::{WithCluster, connect, with_cluster},
+    std::assert_matches::assert_matches,
+};
+
+#[test]
+fn text_search()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let lexeme = |word: &str, positions: &[(u16, TsWeight)]| {
+            let positions = positions.iter()
+                .map(|&(position, weight)| TsPosition{position, weight})
+                .collect();
+            TsLexeme::new(word.to_owned(), positions).unwrap()
+        };
+        let node = |word: &str| Box::new(TsQueryNode::lexeme(word));
+
+        // The server sorts lexemes and merges the positions of duplicates.
+        let rows = client.query("SELECT to_tsvector('simple', \
+                                                    'the cat saw the dog'), \
+                                        setweight('fat:1 rat:2'::tsvector, \
+                                                  'A'), \
+                                        ''::tsvector, \
+                                        to_tsquery('simple', 'fat & !rat'), \
+                                        'ca:*B <3> dog'::tsquery",
+                                &[]).unwrap();
+        assert_eq!(rows[0].get::<TsVector, _>(0).unwrap().lexemes(),
+                   [lexeme("cat", &[(2, TsWeight::D)]),
+                    lexeme("dog", &[(5, TsWeight::D)]),
+                    lexeme("saw", &[(3, TsWeight::D)]),
+                    lexeme("the", &[(1, TsWeight::D), (4, TsWeight::D)])]);
+        assert_eq!(rows[0].get::<TsVector, _>(1).unwrap().lexemes(),
+                   [lexeme("fat", &[(1, TsWeight::A)]),
+                    lexeme("rat", &[(2, TsWeight::A)])]);
+        assert_eq!(rows[0].get::<TsVector, _>(2).unwrap(), TsVector::default());
+        assert_eq!(rows[0].get::<TsQuery, _>(3).unwrap().root(),
+                   Some(&TsQueryNode::And(node("fat"),
+                                          Box::new(TsQueryNode::Not(
+                                              node("rat"))))));
+        let prefix = TsQueryNode::Lexeme{
+            word: "ca".to_owned(),
+            weights: [TsWeight::B].into_iter().collect(),
+            prefix: true,
+        };
+        assert_eq!(rows[0].get::<TsQuery, _>(4).unwrap().root(),
+                   Some(&TsQueryNode::Phrase{left: Box::new(prefix),
+                                             right: node("dog"),
+                                             distance: 3}));
+
+        // The client does the same, so the value is sent back unchanged.
+        let vector = TsVector::new(vec![
+            lexeme("rat", &[(2, TsWeight::B), (7, TsWeight::D)]),
+            lexeme("fat", &[(1, TsWeight::A)]),
+            lexeme("o'brien", &[]),
+            lexeme("rat", &[(3, TsWeight::C)]),
+        ]).unwrap();
+        let query = TsQuery::new(Some(TsQueryNode::Or(
+            Box::new(TsQueryNode::Phrase{left: node("fat"),
+                                         right: node("rat"),
+                                         distance: 1}),
+            Box::new(TsQueryNode::Lexeme{word: "o'b".to_owned(),
+                                         weights: TsWeights::default(),
+                                         prefix: true}),
+        ))).unwrap();
+        let rows = client.query("SELECT $1::tsvector, \
+                                        format('%s', $1::tsvector), \
+                                        $2::tsquery, \
+                                        format('%s', $2::tsquery), \
+                                        $1 @@ $2, \
+                                        ARRAY[$1, $1]",
+                                &[&vector, &query]).unwrap();
+        assert_eq!(rows[0].get::<TsVector, _>(0).unwrap(), vector);
+        assert_eq!(rows[0].get::<&str, _>(1).unwrap(),
+                   "'fat':1A 'o''brien' 'rat':2B,3C,7");
+        assert_eq!(rows[0].get::<TsQuery, _>(2).unwrap(), query);
+        assert_eq!(rows[0].get::<&str, _>(3).unwrap(),
+                   "'fat' <-> 'rat' | 'o''b':*");
+        assert!(rows[0].get::<bool, _>(4).unwrap());
+        assert_eq!(rows[0].get::<Vec<TsVector>, _>(5).unwrap(),
+                   [vector.clone(), vector]);
+
+        // The server sends empty queries, but does not accept them.
+        assert_matches!(client.query("SELECT $1::tsquery",
+                                     &[&TsQuery::default()]),
+                        Err(Error::ErrorResponse(..)));
+
+        // Text format is not supported.
+        let rows = client.simple_query("SELECT 'a'::tsvector, 'a'::tsquery")
+            .unwrap().into_rows().unwrap();
+        assert!(rows[0].get::<TsVector, _>(0).is_err());
+        assert!(rows[0].get::<TsQuery, _>(1).is_err());
+    });
+}
@@ -0,0 +1,103 @@
+use {
+    crate::{
+        Error,
+        Result,
+        protocol::FormatCode,
+        types::{Context, FromSql, IsNull, ToSql, Type},
+    },
+    super::{WithCluster, connect, with_cluster},
+    std::assert_matches::assert_matches,
+};
+
+/// Minimal `int4` codec, so that the typed query flow
+/// can be tested independently of the built-in codecs.
+#[derive(Debug, PartialEq)]
+struct Int4(i32);
+
+impl ToSql for Int4
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::INT4
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        assert_eq!(cx.format_code(), FormatCode::Binary);
+        buf.extend_from_slice(&self.0.to_be_bytes());
+        Ok(IsNull::No)
+    }
+}
+
+impl<'a> FromSql<'a> for Int4
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::INT4
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        assert_eq!(cx.format_code(), FormatCode::Binary);
+        Ok(Self(i32::from_be_bytes(raw.try_into().unwrap())))
+    }
+}
+
+#[test]
+fn typed_query()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+
+        let query = "SELECT $1::int4 + 1 AS x, $2::int4 AS y, 'z' AS z";
+        let rows = client.query(query, &[&Int4(41), &None::<Int4>]).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get::<Int4, _>("x").unwrap(), Int4(42));
+        assert_eq!(rows[0].get::<Option<Int4>, _>(0).unwrap(), Some(Int4(42)));
+        assert_eq!(rows[0].get::<Option<Int4>, _>("y").unwrap(), None);
+        assert_eq!(rows[0].description().types()[2], Type::TEXT);
+
+        assert_matches!(
+            rows[0].get::<Int4, _>("y").unwrap_err(),
+            Error::UnexpectedNull,
+        );
+        assert_matches!(
+            rows[0].get::<Int4, _>("z").unwrap_err(),
+            Error::WrongType{pg_type, ..} if pg_type == Type::TEXT,
+        );
+
+        // The parameter types reported by the server are checked.
+        let error = client.query("SELECT $1::int8", &[&Int4(1)]).unwrap_err();
+        assert_matches!(
+            &error,
+            Error::WrongType{pg_type, ..} if *pg_type == Type::INT8,
+        );
+        assert!(error.to_string().ends_with("PostgreSQL type int8"));
+
+        let error = client.query(query, &[&Int4(1)]).unwrap_err();
+        assert_matches!(error, Error::ParameterCount{expected: 2, actual: 1});
+
+        // Rows received by a row stream can be decoded too.
+        let mut stream = client.row_stream_raw(query, &[], &[Some(b"1"), None],
+                                               &[], &[FormatCode::Binary])
+            .unwrap();
+        let row = stream.next_row().unwrap().unwrap();
+        assert_eq!(row.get::<Int4, _>("x").unwrap(), Int4(2));
+        assert_eq!(row.get::<Option<Int4>, _>(1).unwrap(), None);
+        drop(stream);
+
+        // Statements are cached, so altering a table is handled.
+        client.simple_query("CREATE TABLE t (a int4)").unwrap();
+        client.simple_query("INSERT INTO t VALUES (1)").unwrap();
+        let rows = client.query("SELECT * FROM t", &[]).unwrap();
+        assert_eq!(rows[0].len(), 1);
+        client.simple_query("ALTER TABLE t ADD COLUMN b int4").unwrap();
+        let rows = client.query("SELECT * FROM t", &[]).unwrap();
+        assert_eq!(rows[0].len(), 2);
+
+        client.set_statement_cache_capacity(0).unwrap();
+        let rows = client.query("SELECT $1::int4 AS a", &[&Int4(7)]).unwrap();
+        assert_eq!(rows[0].get::<Int4, _>("a").unwrap(), Int4(7));
+    });
+}
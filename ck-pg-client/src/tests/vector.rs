@@ -0,0 +1,58 @@
+use {
+    crate::{Error, types::{SparseVector, VectorRef}},
+    super::{WithCluster, connect, create_extension, with_cluster},
+    std::assert_matches::assert_matches,
+};
+
+#[test]
+fn vector()
+{
+    let options = WithCluster{enable_ssl: false};
+    with_cluster(options, |_sockets_dir, port| {
+        let mut client = connect(port);
+        if !create_extension(&mut client, "vector") {
+            return;
+        }
+
+        // The text output of sparsevec numbers elements from one.
+        let embedding = [1.5f32, -2.0, 0.25];
+        let sparse = SparseVector::from_dense(&[0.0, 1.5, 0.0, -2.0]).unwrap();
+        let rows = client.query("SELECT $1::vector, \
+                                        format('%s', $1::vector), \
+                                        $1::halfvec, \
+                                        $2::sparsevec, \
+                                        format('%s', $2::sparsevec), \
+                                        $1::vector <-> '[1.5,-2,1.25]', \
+                                        ARRAY[$1::vector, $1]",
+                                &[&embedding.as_slice(), &sparse]).unwrap();
+        assert_eq!(rows[0].get::<Vec<f32>, _>(0).unwrap(), embedding);
+        assert_eq!(rows[0].get::<&str, _>(1).unwrap(), "[1.5,-2,0.25]");
+        assert_eq!(rows[0].get::<Vec<f32>, _>(2).unwrap(), embedding);
+        for index in [0, 2] {
+            let vector = rows[0].get::<VectorRef, _>(index).unwrap();
+            assert_eq!(vector.to_vec(), embedding);
+        }
+        assert_eq!(rows[0].get::<SparseVector, _>(3).unwrap(), sparse);
+        assert_eq!(rows[0].get::<&str, _>(4).unwrap(), "{2:1.5,4:-2}/4");
+        assert_eq!(rows[0].get::<f64, _>(5).unwrap(), 1.0);
+        assert_eq!(rows[0].get::<Vec<Vec<f32>>, _>(6).unwrap(),
+                   [embedding.to_vec(), embedding.to_vec()]);
+
+        // Elements of halfvec are rounded to half precision.
+        let rows = client.query("SELECT $1::halfvec::vector",
+                                &[&vec![0.1f32]]).unwrap();
+        assert_eq!(rows[0].get::<Vec<f32>, _>(0).unwrap(), [0.099975586]);
+
+        let rows = client.simple_query("SELECT '[1.5,-2,0.25]'::vector, \
+                                               '[1.5,-2,0.25]'::halfvec, \
+                                               '{2:1.5,4:-2}/4'::sparsevec")
+            .unwrap().into_rows().unwrap();
+        assert_eq!(rows[0].get::<Vec<f32>, _>(0).unwrap(), embedding);
+        assert_eq!(rows[0].get::<Vec<f32>, _>(1).unwrap(), embedding);
+        assert_eq!(rows[0].get::<SparseVector, _>(2).unwrap(), sparse);
+
+        // Limits are checked by the server.
+        assert_matches!(client.query("SELECT $1::vector", &[&vec![f32::NAN]]),
+                        Err(Error::ErrorResponse(..)));
+    });
+}
@@ -0,0 +1,234 @@
+use crate::{
+    Error,
+    PgClient,
+    Result,
+    Statement,
+    types::{Field, Kind, Type},
+};
+
+/// The maximum depth to which the types of fields and elements
+/// are looked up, which guards against cycles in the catalogs.
+const MAX_DEPTH: usize = 32;
+
+/// The name of the savepoint in which types are looked up
+/// in a transaction block.
+const LOOKUP_SAVEPOINT: &str = "ck_pg_client_type_lookup";
+
+impl PgClient
+{
+    /// Look up a type that is not built in, such as a composite type
+    /// created with `CREATE TYPE`, in the catalogs of the server.
+    ///
+    /// The name is resolved like a cast to `regtype`, so it may be
+    /// schema-qualified, and is otherwise looked up in the search path.
+    /// The fields of composite types, the element types of arrays
+    /// and ranges, the range types of multiranges, and the base types
+    /// of domains are looked up recursively, as is the array type
+    /// of the type. The labels of enum types are looked up as well.
+    ///
+    /// The types that are looked up are remembered by the client,
+    /// so that parameters and columns of these types can be converted
+    /// by typed queries, such as [`query`][`Self::query`].
+    /// Typed queries and [`row_stream_prepared`] look up the types
+    /// of parameters and columns that are not yet known
+    /// before executing the statement, so this method need only be
+    /// called to convert values of such types in other results,
+    /// such as those of [`row_stream_raw`].
+    /// In a transaction block, they do so in a savepoint,
+    /// so that a failed lookup does not abort the transaction.
+    ///
+    /// Each type is looked up only once, so changes made to it
+    /// afterwards, such as by `ALTER TYPE`, or by dropping it and
+    /// creating it again, go unnoticed, and values of the changed type
+    /// may fail to convert or convert incorrectly. Use [`clear_types`]
+    /// after changing types that the client has looked up.
+    ///
+    /// [`clear_types`]: `Self::clear_types`
+    /// [`row_stream_prepared`]: `Self::row_stream_prepared`
+    /// [`row_stream_raw`]: `Self::row_stream_raw`
+    pub fn lookup_type(&mut self, name: &str) -> Result<Type>
+    {
+        let rows = self.query("SELECT $1::text::regtype::oid", &[&name])?;
+        let oid = rows.first()
+            .ok_or_else(|| Error::NoSuchType(name.to_owned()))?
+            .get(0)?;
+        self.lookup_type_oid(oid, 0)
+    }
+
+    /// Forget the types that were looked up,
+    /// so that they are looked up again when next needed.
+    ///
+    /// The statements in the statement cache are closed as well,
+    /// as they were described in terms of the types as they were.
+    /// See [`lookup_type`] for when this is needed.
+    ///
+    /// [`lookup_type`]: `Self::lookup_type`
+    pub fn clear_types(&mut self) -> Result<()>
+    {
+        self.types.clear();
+        for statement in self.statement_cache.clear() {
+            self.close_statement(statement)?;
+        }
+        Ok(())
+    }
+
+    /// The type with the given OID, if it is built in
+    /// or has been looked up, or else a type without a name.
+    pub (crate) fn type_from_oid(&self, oid: u32) -> Type
+    {
+        Type::from_oid(oid)
+            .or_else(|| self.types.get(&oid).cloned())
+            .unwrap_or_else(|| Type::from_oid_or_unknown(oid))
+    }
+
+    /// Look up the types of the parameters and columns of a statement
+    /// that are neither built in nor known yet.
+    ///
+    /// Returns whether any types were looked up,
+    /// which executes queries using the unnamed prepared statement.
+    /// Nothing is looked up in a failed transaction,
+    /// in which queries cannot be executed.
+    /// In a transaction block, the types are looked up in a savepoint,
+    /// which is rolled back if the lookup fails, so that the failure
+    /// does not abort the transaction of the caller.
+    pub (crate) fn lookup_statement_types(&mut self, statement: &Statement)
+        -> Result<bool>
+    {
+        if self.transaction_status_indicator == b'E' {
+            return Ok(false);
+        }
+        let unknown: Vec<u32> = statement.parameter_type_oids().iter()
+            .copied()
+            .chain(statement.columns().iter().map(|c| c.data_type_oid))
+            .filter(|&oid| {
+                Type::from_oid(oid).is_none() && !self.types.contains_key(&oid)
+            })
+            .collect();
+        if unknown.is_empty() {
+            return Ok(false);
+        }
+
+        let in_block = self.transaction_status_indicator == b'T';
+        if in_block {
+            self.simple_query(&format!("SAVEPOINT {LOOKUP_SAVEPOINT}"))?;
+        }
+        let mut result = unknown.iter()
+            .try_for_each(|&oid| self.lookup_type_oid(oid, 0).map(drop));
+        if in_block {
+            let release = format!("RELEASE SAVEPOINT {LOOKUP_SAVEPOINT}");
+            let cleanup = if result.is_ok() {
+                self.simple_query(&release)
+            } else {
+                self.simple_query(&format!(
+                    "ROLLBACK TO SAVEPOINT {LOOKUP_SAVEPOINT}; {release}",
+                ))
+            };
+            // The error of the lookup takes precedence.
+            result = result.and(cleanup.map(drop));
+        }
+        result.map(|()| true)
+    }
+
+    fn lookup_type_oid(&mut self, oid: u32, depth: usize) -> Result<Type>
+    {
+        if let Some(ty) = Type::from_oid(oid) {
+            return Ok(ty);
+        }
+        if let Some(ty) = self.types.get(&oid) {
+            return Ok(ty.clone());
+        }
+        if depth == MAX_DEPTH {
+            let message = format!("type with OID {oid} is nested too deeply");
+            return Err(Error::Conversion(message.into()));
+        }
+
+        let rows = self.query(
+            "SELECT typname, typtype, typcategory, typelem, typrelid, \
+                    typarray, typbasetype \
+             FROM pg_catalog.pg_type \
+             WHERE oid = $1",
+            &[&oid],
+        )?;
+        let row = rows.first()
+            .ok_or_else(|| Error::NoSuchType(format!("OID {oid}")))?;
+        let name: String = row.get(0)?;
+        let typtype = row.get::<i8, _>(1)?.cast_unsigned();
+        let typcategory = row.get::<i8, _>(2)?.cast_unsigned();
+        let typelem: u32 = row.get(3)?;
+        let typrelid: u32 = row.get(4)?;
+        let typarray: u32 = row.get(5)?;
+        let typbasetype: u32 = row.get(6)?;
+
+        let kind = match (typtype, typcategory) {
+            (b'c', _) => {
+                let rows = self.query(
+                    "SELECT attname, atttypid \
+                     FROM pg_catalog.pg_attribute \
+                     WHERE attrelid = $1 AND attnum > 0 \
+                       AND NOT attisdropped \
+                     ORDER BY attnum",
+                    &[&typrelid],
+                )?;
+                let mut fields = Vec::with_capacity(rows.len());
+                for row in rows {
+                    let ty = self.lookup_type_oid(row.get(1)?, depth + 1)?;
+                    fields.push(Field::new(row.get(0)?, ty));
+                }
+                Kind::Composite(fields)
+            },
+            (b'd', _) =>
+                Kind::Domain(self.lookup_type_oid(typbasetype, depth + 1)?),
+            (b'e', _) => {
+                let rows = self.query(
+                    "SELECT enumlabel \
+                     FROM pg_catalog.pg_enum \
+                     WHERE enumtypid = $1 \
+                     ORDER BY enumsortorder",
+                    &[&oid],
+                )?;
+                let labels = rows.iter()
+                    .map(|row| row.get(0))
+                    .collect::<Result<_>>()?;
+                Kind::Enum(labels)
+            },
+            (b'r', _) => {
+                let subtype = self.query_range(
+                    "SELECT rngsubtype FROM pg_catalog.pg_range \
+                     WHERE rngtypid = $1",
+                    oid,
+                )?;
+                Kind::Range(self.lookup_type_oid(subtype, depth + 1)?)
+            },
+            (b'm', _) => {
+                let range_type = self.query_range(
+                    "SELECT rngtypid FROM pg_catalog.pg_range \
+                     WHERE rngmultitypid = $1",
+                    oid,
+                )?;
+                Kind::Multirange(self.lookup_type_oid(range_type, depth + 1)?)
+            },
+            (_, b'A') if typelem != 0 =>
+                Kind::Array(self.lookup_type_oid(typelem, depth + 1)?),
+            (b'p', _) =>
+                Kind::Pseudo,
+            _ =>
+                Kind::Simple,
+        };
+
+        let ty = Type::new(oid, name, kind);
+        self.types.insert(oid, ty.clone());
+        if typarray != 0 {
+            self.lookup_type_oid(typarray, depth + 1)?;
+        }
+        Ok(ty)
+    }
+
+    /// Query `pg_range` for the OID of a related type.
+    fn query_range(&mut self, query: &str, oid: u32) -> Result<u32>
+    {
+        let rows = self.query(query, &[&oid])?;
+        rows.first()
+            .ok_or_else(|| Error::NoSuchType(format!("range of OID {oid}")))?
+            .get(0)
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    Error,
+    PgClient,
+    Result,
+    Row,
+    Statement,
+    protocol::{FormatCode, write_bind, write_execute, write_sync},
+    statement_cache::{is_cached_plan_result_type_change, result_columns},
+    types::{Context, IsNull, Parameter, to_sql_checked},
+};
+
+impl PgClient
+{
+    /// Execute a statement with typed parameters.
+    ///
+    /// The statement is prepared and stored in the statement cache,
+    /// unless it is already cached; see [`set_statement_cache_capacity`].
+    /// The parameter types are inferred by the server,
+    /// and each parameter is checked against the inferred type
+    /// before it is encoded; see the [`types`] module for details.
+    /// Use a cast such as `$1::int8` to select a particular type.
+    /// Types of parameters and columns that are not built in
+    /// are looked up first; see [`lookup_type`][`Self::lookup_type`].
+    ///
+    /// Parameters and results are transferred in binary format,
+    /// so the values returned by [`Row::get_raw`] are in binary format.
+    ///
+    /// [`set_statement_cache_capacity`]: `Self::set_statement_cache_capacity`
+    /// [`types`]: `crate::types`
+    pub fn query(&mut self, query: &str, parameters: &[&dyn Parameter])
+        -> Result<Vec<Row>>
+    {
+        let mut retried = false;
+
+        loop {
+            let mut statement = self.cached_statement(query)?;
+            // Looking up types executes queries, which may replace
+            // the unnamed prepared statement or evict the statement
+            // from the cache, so the statement must be found again.
+            if self.lookup_statement_types(&statement)? {
+                statement = self.cached_statement(query)?;
+            }
+            match self.query_statement(&statement, parameters) {
+                Err(Error::ErrorResponse(error))
+                    if !statement.name().is_empty()
+                    && is_cached_plan_result_type_change(&error) =>
+                {
+                    // See query_cached for why this is done.
+                    self.uncache_statement(query)?;
+                    let idle = self.transaction_status_indicator == b'I';
+                    if idle && !retried {
+                        retried = true;
+                        continue;
+                    }
+                    return Err(Error::ErrorResponse(error));
+                },
+                result =>
+                    return result,
+            }
+        }
+    }
+
+    /// Execute a prepared statement with typed parameters.
+    ///
+    /// See [`query`] for details.
+    /// Types of parameters and columns that are not built in
+    /// are looked up first; see [`lookup_type`][`Self::lookup_type`].
+    /// Returns [`Error::ParameterCount`] if the number of parameters
+    /// does not match the number of parameters of the statement.
+    ///
+    /// [`query`]: `Self::query`
+    pub fn query_statement(
+        &mut self,
+        statement: &Statement,
+        parameters: &[&dyn Parameter],
+    ) -> Result<Vec<Row>>
+    {
+        let parameter_type_oids = statement.parameter_type_oids();
+        if parameters.len() != parameter_type_oids.len() {
+            return Err(Error::ParameterCount{
+                expected: parameter_type_oids.len(),
+                actual: parameters.len(),
+            });
+        }
+
+        self.lookup_statement_types(statement)?;
+        let parameter_types: Vec<_> = parameter_type_oids.iter()
+            .map(|&oid| self.type_from_oid(oid))
+            .collect();
+
+        let session_parameters = self.session_parameters.clone();
+        self.write_messages(|buf| {
+            write_bind(
+                buf,
+                b"",
+                statement.name().as_bytes(),
+                &[FormatCode::Binary],
+                parameters.len(),
+                |i, buf| {
+                    let cx = Context::new(&parameter_types[i],
+                                          FormatCode::Binary)
+                        .with_session_parameters(&session_parameters);
+                    let is_null = to_sql_checked(parameters[i], &cx, buf)?;
+                    Ok(is_null == IsNull::Yes)
+                },
+                &[FormatCode::Binary],
+            )?;
+            write_execute(buf, b"", 0)?;
+            write_sync(buf);
+            Ok(())
+        })?;
+        self.send()?;
+
+        let responses = self.receive_extended_query_responses()?;
+        let mut result = responses.into_result()?.result;
+        result.columns =
+            result_columns(statement.columns(), &[FormatCode::Binary]);
+        result.into_rows_with_types(&self.types)
+    }
+}
@@ -0,0 +1,643 @@
+//! Codecs for arrays.
+//!
+//! Arrays are encoded generically in terms of the codec
+//! of the element type, which is given by [`Kind::Array`].
+//! In text format, the elements are in the text format
+//! of the element type, and the array literal is parsed and written
+//! by [`parse_array_literal`] and [`write_array_literal`].
+//! Values of `vector` and `halfvec` from the **pgvector** extension
+//! are converted like one-dimensional arrays of `float4`.
+
+use {
+    crate::{Error, Result, protocol::FormatCode},
+    super::{
+        Context,
+        Decode,
+        FromSql,
+        IsNull,
+        Kind,
+        ToSql,
+        Type,
+        from_sql_checked,
+        from_sql_checked_with,
+        invalid_value,
+        is_accepted,
+        parse_array_literal,
+        text_value,
+        to_sql_checked,
+        vector::{
+            ELEMENT_TYPE,
+            is_vector_type,
+            read_vector,
+            write_vector,
+        },
+        write_array_literal,
+    },
+    std::borrow::Cow,
+};
+
+/// The maximum number of dimensions of an array, as with `MAXDIM`.
+pub (super) const MAX_DIMENSIONS: usize = 6;
+
+/// Value of an array type, with any number of dimensions.
+///
+/// The elements are stored in row-major order,
+/// such that the last subscript varies fastest,
+/// which is the order in which PostgreSQL stores them.
+/// Use `Array<Option<T>>` for arrays that may contain NULL.
+///
+/// An empty array has no dimensions, as in PostgreSQL.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Array<T>
+{
+    dimensions: Vec<Dimension>,
+    elements: Vec<T>,
+}
+
+/// Dimension of an [`Array`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Dimension
+{
+    /// The number of elements along the dimension.
+    pub length: usize,
+
+    /// The subscript of the first element along the dimension,
+    /// which is 1 unless specified otherwise.
+    pub lower_bound: i32,
+}
+
+impl<T> Array<T>
+{
+    /// Create an array with the given dimensions and elements.
+    ///
+    /// Returns [`None`] if there are more than six dimensions,
+    /// if any dimension is empty, if the upper bound of any dimension
+    /// exceeds [`i32::MAX`], or if the number of elements does not
+    /// match the dimensions.
+    pub fn new(dimensions: Vec<Dimension>, elements: Vec<T>) -> Option<Self>
+    {
+        if dimensions.len() > MAX_DIMENSIONS {
+            return None;
+        }
+        let mut count = if dimensions.is_empty() { 0 } else { 1 };
+        for dimension in &dimensions {
+            let length = i32::try_from(dimension.length).ok()?;
+            if length == 0 {
+                return None;
+            }
+            dimension.lower_bound.checked_add(length - 1)?;
+            count = dimension.length.checked_mul(count)?;
+        }
+        (count == elements.len()).then_some(Self{dimensions, elements})
+    }
+
+    /// The dimensions of the array.
+    pub fn dimensions(&self) -> &[Dimension]
+    {
+        &self.dimensions
+    }
+
+    /// The elements of the array, in row-major order.
+    pub fn elements(&self) -> &[T]
+    {
+        &self.elements
+    }
+
+    /// Convert into the elements of the array, in row-major order.
+    pub fn into_elements(self) -> Vec<T>
+    {
+        self.elements
+    }
+
+    /// The element at the given subscripts,
+    /// which are relative to the lower bounds.
+    ///
+    /// Returns [`None`] if the number of subscripts does not match
+    /// the number of dimensions, or if any subscript is out of bounds.
+    pub fn get(&self, subscripts: &[i32]) -> Option<&T>
+    {
+        if subscripts.len() != self.dimensions.len() {
+            return None;
+        }
+        let mut index = 0;
+        for (dimension, &subscript) in self.dimensions.iter().zip(subscripts) {
+            let offset = subscript.checked_sub(dimension.lower_bound)?;
+            let offset = usize::try_from(offset).ok()
+                .filter(|&offset| offset < dimension.length)?;
+            index = index * dimension.length + offset;
+        }
+        self.elements.get(index)
+    }
+}
+
+impl<T> Default for Array<T>
+{
+    /// Create an empty array.
+    fn default() -> Self
+    {
+        Self{dimensions: Vec::new(), elements: Vec::new()}
+    }
+}
+
+impl<T> From<Vec<T>> for Array<T>
+{
+    /// Create a one-dimensional array with a lower bound of 1.
+    ///
+    /// # Panics
+    ///
+    /// If the vector has more than [`i32::MAX`] elements.
+    fn from(elements: Vec<T>) -> Self
+    {
+        let dimensions = if elements.is_empty() {
+            Vec::new()
+        } else {
+            assert!(i32::try_from(elements.len()).is_ok(),
+                    "array has too many elements");
+            vec![Dimension{length: elements.len(), lower_bound: 1}]
+        };
+        Self{dimensions, elements}
+    }
+}
+
+/// The element type of an array type,
+/// or `float4` for `vector` and `halfvec`.
+fn element_type(ty: &Type) -> Option<&Type>
+{
+    match ty.kind() {
+        Kind::Array(element_type) => Some(element_type),
+        _ if is_vector_type(ty) => Some(&ELEMENT_TYPE),
+        _ => None,
+    }
+}
+
+/// The delimiter between elements in text format.
+///
+/// This is `typdelim` of the element type,
+/// which is `;` for `box` and `,` for every other built-in type.
+/// Domains have the delimiter of their base type.
+fn delimiter(element_type: &Type) -> u8
+{
+    match element_type.kind() {
+        Kind::Domain(base_type) => delimiter(base_type),
+        _ if *element_type == Type::BOX => b';',
+        _ => b',',
+    }
+}
+
+/// Context for the elements of an array,
+/// which are in the same format as the array.
+///
+/// If the element type is a domain, the elements are converted
+/// by the codec of the base type, as with [`from_sql_checked`].
+fn element_context<'a>(cx: &Context<'a>) -> Result<Context<'a>>
+{
+    let element_type = element_type(cx.ty())
+        .ok_or_else(|| invalid_value(cx, "not an array type"))?;
+    Ok(Context::new(element_type, cx.format_code())
+        .with_session_parameters(cx.session_parameters()))
+}
+
+/// Encode an array.
+///
+/// The dimensions must be valid for the number of elements.
+fn write_array<T>(
+    cx: &Context,
+    dimensions: &[Dimension],
+    elements: &[T],
+    buf: &mut Vec<u8>,
+) -> Result<IsNull>
+    where T: ToSql
+{
+    let ecx = element_context(cx)?;
+    if is_vector_type(cx.ty()) {
+        return write_vector(cx, &ecx, dimensions, elements, buf);
+    }
+    if cx.format_code() == FormatCode::Text {
+        return write_array_text(&ecx, dimensions, elements, buf);
+    }
+
+    let too_large = || invalid_value(cx, "array too large");
+
+    let ndim = i32::try_from(dimensions.len()).map_err(|_| too_large())?;
+    buf.extend_from_slice(&ndim.to_be_bytes());
+    let has_null_at = buf.len();
+    buf.extend_from_slice(&0i32.to_be_bytes());
+    buf.extend_from_slice(&ecx.ty().oid().to_be_bytes());
+    for dimension in dimensions {
+        let length = i32::try_from(dimension.length)
+            .map_err(|_| too_large())?;
+        buf.extend_from_slice(&length.to_be_bytes());
+        buf.extend_from_slice(&dimension.lower_bound.to_be_bytes());
+    }
+
+    let mut has_null = false;
+    for element in elements {
+        let start = buf.len();
+        buf.extend_from_slice(&[0; 4]);
+        let length = match to_sql_checked(element, &ecx, buf)? {
+            IsNull::Yes => {
+                buf.truncate(start + 4);
+                has_null = true;
+                -1
+            },
+            IsNull::No =>
+                i32::try_from(buf.len() - start - 4)
+                    .map_err(|_| too_large())?,
+        };
+        buf[start .. start + 4].copy_from_slice(&length.to_be_bytes());
+    }
+
+    if has_null {
+        buf[has_null_at .. has_null_at + 4]
+            .copy_from_slice(&1i32.to_be_bytes());
+    }
+
+    Ok(IsNull::No)
+}
+
+/// Encode an array in text format, given the context of the elements.
+fn write_array_text<T>(
+    ecx: &Context,
+    dimensions: &[Dimension],
+    elements: &[T],
+    buf: &mut Vec<u8>,
+) -> Result<IsNull>
+    where T: ToSql
+{
+    let mut element_buf = Vec::new();
+    let mut texts = Vec::with_capacity(elements.len());
+    for element in elements {
+        element_buf.clear();
+        let text = match to_sql_checked(element, ecx, &mut element_buf)? {
+            IsNull::Yes => None,
+            IsNull::No => Some(text_value(ecx, &element_buf)?.to_owned()),
+        };
+        texts.push(text);
+    }
+
+    let array = Array{dimensions: dimensions.to_vec(), elements: texts};
+    let mut literal = String::new();
+    write_array_literal(&array, delimiter(ecx.ty()), &mut literal);
+    buf.extend_from_slice(literal.as_bytes());
+    Ok(IsNull::No)
+}
+
+/// Decode an array, decoding the elements with the given function.
+///
+/// In binary format, the element type in the value must be
+/// the element type of the array type in the context.
+fn read_array<'a, 'b, T>(cx: &Context, mut raw: &'b [u8], decode: Decode<'b, T>)
+    -> Result<Array<T>>
+    where T: FromSql<'a>
+{
+    let ecx = element_context(cx)?;
+    if is_vector_type(cx.ty()) {
+        return read_vector(cx, &ecx, raw, decode);
+    }
+    if cx.format_code() == FormatCode::Text {
+        return read_array_text(cx, &ecx, raw, decode);
+    }
+
+    let read_i32 = |raw: &mut &[u8]| -> Result<i32> {
+        let (bytes, rest) = raw.split_first_chunk::<4>()
+            .ok_or_else(|| invalid_value(cx, "malformed binary value"))?;
+        *raw = rest;
+        Ok(i32::from_be_bytes(*bytes))
+    };
+
+    let ndim = usize::try_from(read_i32(&mut raw)?).ok()
+        .filter(|&ndim| ndim <= MAX_DIMENSIONS)
+        .ok_or_else(|| invalid_value(cx, "invalid number of dimensions"))?;
+    if !matches!(read_i32(&mut raw)?, 0 | 1) {
+        return Err(invalid_value(cx, "invalid flags"));
+    }
+    let element_oid = read_i32(&mut raw)?.cast_unsigned();
+    if element_oid != ecx.ty().oid() {
+        let message = format!("invalid {} value: \
+                               element type has OID {element_oid}",
+                              cx.ty());
+        return Err(Error::Conversion(message.into()));
+    }
+
+    let mut dimensions = Vec::with_capacity(ndim);
+    let mut count = if ndim == 0 { 0 } else { 1_usize };
+    for _ in 0 .. ndim {
+        let length = read_i32(&mut raw)?;
+        let lower_bound = read_i32(&mut raw)?;
+        let length = usize::try_from(length).ok()
+            .filter(|_| lower_bound.checked_add(length).is_some())
+            .ok_or_else(|| invalid_value(cx, "invalid dimension"))?;
+        count = count.checked_mul(length)
+            .ok_or_else(|| invalid_value(cx, "too many elements"))?;
+        dimensions.push(Dimension{length, lower_bound});
+    }
+
+    // Arrays with an empty dimension are empty arrays,
+    // which have no dimensions.
+    if count == 0 {
+        dimensions.clear();
+    }
+
+    // Each element takes at least four bytes, so a corrupt
+    // count cannot cause an arbitrarily large allocation.
+    let mut elements = Vec::with_capacity(count.min(raw.len() / 4));
+    for _ in 0 .. count {
+        let length = read_i32(&mut raw)?;
+        let element = if length == -1 {
+            None
+        } else {
+            let value = usize::try_from(length).ok()
+                .and_then(|length| raw.split_at_checked(length))
+                .map(|(value, rest)| { raw = rest; value })
+                .ok_or_else(|| invalid_value(cx, "malformed element"))?;
+            Some(value)
+        };
+        elements.push(from_sql_checked_with(&ecx, element, decode)?);
+    }
+
+    if !raw.is_empty() {
+        return Err(invalid_value(cx, "trailing data"));
+    }
+
+    Ok(Array{dimensions, elements})
+}
+
+/// Decode an array in text format, given the context of the elements.
+///
+/// Elements that contain escapes are decoded
+/// using [`FromSql::from_sql_temporary`].
+fn read_array_text<'a, 'b, T>(
+    cx: &Context,
+    ecx: &Context,
+    raw: &'b [u8],
+    decode: Decode<'b, T>,
+) -> Result<Array<T>>
+    where T: FromSql<'a>
+{
+    let literal = parse_array_literal(text_value(cx, raw)?,
+                                      delimiter(ecx.ty()))
+        .map_err(|_| invalid_value(cx, "malformed array literal"))?;
+    let Array{dimensions, elements} = literal;
+    let elements = elements.into_iter()
+        .map(|element| match element {
+            None =>
+                from_sql_checked(ecx, None),
+            Some(Cow::Borrowed(element)) =>
+                from_sql_checked_with(ecx, Some(element.as_bytes()), decode),
+            Some(Cow::Owned(element)) =>
+                from_sql_checked_with(ecx, Some(element.as_bytes()),
+                                      T::from_sql_temporary),
+        })
+        .collect::<Result<_>>()?;
+    Ok(Array{dimensions, elements})
+}
+
+impl<T> ToSql for Array<T>
+    where T: ToSql
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        element_type(ty).is_some_and(|ty| is_accepted(ty, T::accepts))
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        write_array(cx, &self.dimensions, &self.elements, buf)
+    }
+}
+
+impl<'a, T> FromSql<'a> for Array<T>
+    where T: FromSql<'a>
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        element_type(ty).is_some_and(|ty| is_accepted(ty, T::accepts))
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        read_array(cx, raw, T::from_sql)
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        read_array(cx, raw, T::from_sql_temporary)
+    }
+}
+
+/// Encoded as a one-dimensional array with a lower bound of 1.
+impl<T> ToSql for &[T]
+    where T: ToSql
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        element_type(ty).is_some_and(|ty| is_accepted(ty, T::accepts))
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        let length = self.len();
+        let dimension = [Dimension{length, lower_bound: 1}];
+        let dimensions = if length == 0 { &[][..] } else { &dimension[..] };
+        write_array(cx, dimensions, self, buf)
+    }
+}
+
+/// Encoded as a one-dimensional array with a lower bound of 1.
+impl<T> ToSql for Vec<T>
+    where T: ToSql
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <&[T] as ToSql>::accepts(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        self.as_slice().to_sql(cx, buf)
+    }
+}
+
+/// Decoded from arrays with at most one dimension,
+/// whose lower bound is discarded. Use [`Array`] to decode
+/// multi-dimensional arrays or to retain the lower bound.
+impl<'a, T> FromSql<'a> for Vec<T>
+    where T: FromSql<'a>
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <Array<T> as FromSql>::accepts(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        one_dimensional(cx, read_array(cx, raw, T::from_sql)?)
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        one_dimensional(cx, read_array(cx, raw, T::from_sql_temporary)?)
+    }
+}
+
+/// The elements of an array with at most one dimension.
+fn one_dimensional<T>(cx: &Context, array: Array<T>) -> Result<Vec<T>>
+{
+    if array.dimensions.len() > 1 {
+        return Err(invalid_value(cx, "array is multi-dimensional"));
+    }
+    Ok(array.elements)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::{Rect, tests::{fuzz_from_sql, round_trip}},
+        super::*,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<Array<Option<i32>>>(&Type::INT4_ARRAY);
+        fuzz_from_sql::<Vec<String>>(&Type::TEXT_ARRAY);
+    }
+
+    #[test]
+    fn new()
+    {
+        let dimension = |length, lower_bound| Dimension{length, lower_bound};
+        assert!(Array::new(vec![dimension(2, 1)], vec![1, 2]).is_some());
+        assert!(Array::new(vec![dimension(2, 1)], vec![1]).is_none());
+        assert!(Array::new(vec![dimension(0, 1)], vec![0; 0]).is_none());
+        assert!(Array::new(vec![dimension(1, i32::MAX)], vec![1]).is_some());
+        assert!(Array::new(vec![dimension(2, i32::MAX)], vec![1, 2])
+            .is_none());
+        assert!(Array::new(vec![dimension(1, 1); 7], vec![1]).is_none());
+        assert!(Array::new(vec![], vec![0; 0]).is_some());
+    }
+
+    #[test]
+    fn get()
+    {
+        let dimensions = vec![
+            Dimension{length: 2, lower_bound: 0},
+            Dimension{length: 3, lower_bound: -1},
+        ];
+        let array = Array::new(dimensions, vec![1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(array.get(&[0, -1]), Some(&1));
+        assert_eq!(array.get(&[0, 1]), Some(&3));
+        assert_eq!(array.get(&[1, 0]), Some(&5));
+        assert_eq!(array.get(&[2, 0]), None);
+        assert_eq!(array.get(&[0, -2]), None);
+        assert_eq!(array.get(&[0]), None);
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        let dimensions = vec![
+            Dimension{length: 2, lower_bound: 0},
+            Dimension{length: 2, lower_bound: 5},
+        ];
+        let elements = vec![Some(1), None, Some(3), Some(i64::MAX)];
+        let array = Array::new(dimensions, elements).unwrap();
+        let strings = vec!["a".to_owned(), String::new(), "NULL".to_owned(),
+                           "{\"x\\y\"}".to_owned()];
+
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            round_trip(&Type::INT8_ARRAY, format_code, &array);
+            round_trip(&Type::INT8_ARRAY, format_code,
+                       &Array::<i64>::default());
+            round_trip(&Type::TEXT_ARRAY, format_code, &strings);
+            round_trip(&Type::TEXT_ARRAY, format_code, &Vec::<String>::new());
+            round_trip(&Type::INT4_ARRAY, format_code, &vec![Some(1), None]);
+            round_trip(&Type::BYTEA_ARRAY, format_code,
+                       &vec![vec![0u8, 255], vec![]]);
+        }
+    }
+
+    #[test]
+    fn text()
+    {
+        let cx = Context::new(&Type::TEXT_ARRAY, FormatCode::Text);
+        let raw = br#"{a,"b c","d\"e"}"#;
+        let strings: Vec<String> = from_sql_checked(&cx, Some(raw)).unwrap();
+        assert_eq!(strings, ["a", "b c", "d\"e"]);
+
+        // Elements with escapes cannot be borrowed.
+        let strs = from_sql_checked::<Vec<&str>>(&cx, Some(raw));
+        assert!(matches!(strs, Err(Error::Conversion(..))));
+        let strs: Vec<&str> = from_sql_checked(&cx, Some(b"{a,\"b c\"}"))
+            .unwrap();
+        assert_eq!(strs, ["a", "b c"]);
+
+        let cx = Context::new(&Type::INT4_ARRAY, FormatCode::Text);
+        let numbers: Array<Option<i32>> =
+            from_sql_checked(&cx, Some(b"[0:1]={1,NULL}")).unwrap();
+        assert_eq!(numbers.get(&[0]), Some(&Some(1)));
+        assert_eq!(numbers.get(&[1]), Some(&None));
+        assert!(from_sql_checked::<Vec<i32>>(&cx, Some(b"{1,x}")).is_err());
+
+        // Quoted elements of owned element types are unescaped.
+        let ty = Type::new(0, "_text[]".to_owned(),
+                           Kind::Array(Type::TEXT_ARRAY));
+        let cx = Context::new(&ty, FormatCode::Text);
+        let nested: Vec<Vec<String>> =
+            from_sql_checked(&cx, Some(br#"{"{\"a b\"}"}"#)).unwrap();
+        assert_eq!(nested, [["a b"]]);
+    }
+
+    #[test]
+    fn domains()
+    {
+        let posint = Type::new(0, "posint".to_owned(),
+                               Kind::Domain(Type::INT4));
+        let ty = Type::new(0, "_posint".to_owned(), Kind::Array(posint));
+        assert!(<Vec<i32> as FromSql>::accepts(&ty));
+        assert!(!<Vec<bool> as FromSql>::accepts(&ty));
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            round_trip(&ty, format_code, &vec![1, 2]);
+        }
+
+        // The delimiter is that of the base type.
+        let boxes = Type::new(0, "boxes".to_owned(), Kind::Domain(Type::BOX));
+        let ty = Type::new(0, "_boxes".to_owned(), Kind::Array(boxes));
+        let cx = Context::new(&ty, FormatCode::Text);
+        let rects: Vec<Rect> =
+            from_sql_checked(&cx, Some(b"{(1,1),(0,0);(3,3),(2,2)}")).unwrap();
+        assert_eq!(rects.len(), 2);
+    }
+
+    #[test]
+    fn rejects()
+    {
+        let cx = Context::new(&Type::INT4_ARRAY, FormatCode::Binary);
+        let decode = |raw: &[u8]| from_sql_checked::<Vec<i32>>(&cx, Some(raw));
+
+        // One dimension of length 1 with lower bound 1, then the element.
+        let header = |oid: u32| {
+            let mut raw = vec![0, 0, 0, 1, 0, 0, 0, 0];
+            raw.extend_from_slice(&oid.to_be_bytes());
+            raw.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 1]);
+            raw
+        };
+
+        let mut raw = header(Type::INT4.oid());
+        raw.extend_from_slice(&[0, 0, 0, 4, 0, 0, 0, 7]);
+        assert_eq!(decode(&raw).unwrap(), [7]);
+
+        let mut raw = header(Type::INT8.oid());
+        raw.extend_from_slice(&[0, 0, 0, 4, 0, 0, 0, 7]);
+        assert!(decode(&raw).is_err());
+
+        let mut raw = header(Type::INT4.oid());
+        raw.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF]);
+        assert!(matches!(decode(&raw), Err(Error::UnexpectedNull)));
+
+        let mut raw = header(Type::INT4.oid());
+        raw.extend_from_slice(&[0, 0, 0, 4, 0, 0, 0, 7, 0]);
+        assert!(decode(&raw).is_err());
+    }
+}
@@ -0,0 +1,252 @@
+//! Codecs for `bit` and `varbit`.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{
+        Context,
+        FromSql,
+        IsNull,
+        ToSql,
+        Type,
+        fixed_length,
+        invalid_value,
+        text_value,
+    },
+    std::{fmt, io::Write, str::FromStr},
+    thiserror::Error,
+};
+
+/// Value of type `bit` or `varbit`, which is a sequence of bits.
+///
+/// The bits are stored in bytes, most significant bit first.
+/// Bits in the last byte beyond the length of the bit string are zero.
+/// The server checks the length of values of type `bit(n)`;
+/// the length of a `BitString` is not checked against the type.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BitString
+{
+    len: usize,
+    bytes: Vec<u8>,
+}
+
+/// Error returned when parsing a [`BitString`] fails.
+#[derive(Clone, Debug, Error)]
+#[error("invalid bit string literal")]
+pub struct ParseBitStringError(());
+
+impl BitString
+{
+    /// The maximum number of bits in a bit string.
+    pub const MAX_LEN: usize = i32::MAX as usize - 7;
+
+    /// Create a bit string of the given length from its bytes.
+    ///
+    /// Bits in the last byte beyond the length are cleared.
+    /// Returns [`None`] if the number of bytes does not match the length,
+    /// or if the length exceeds [`MAX_LEN`][`Self::MAX_LEN`].
+    pub fn new(mut bytes: Vec<u8>, len: usize) -> Option<Self>
+    {
+        if len > Self::MAX_LEN || bytes.len() != len.div_ceil(8) {
+            return None;
+        }
+        if let (Some(last), 1 ..) = (bytes.last_mut(), len % 8) {
+            *last &= 0xFF << (8 - len % 8);
+        }
+        Some(Self{len, bytes})
+    }
+
+    /// The number of bits.
+    pub fn len(&self) -> usize
+    {
+        self.len
+    }
+
+    /// Whether there are no bits.
+    pub fn is_empty(&self) -> bool
+    {
+        self.len == 0
+    }
+
+    /// The bytes that store the bits, most significant bit first.
+    pub fn as_bytes(&self) -> &[u8]
+    {
+        &self.bytes
+    }
+
+    /// The bit at the given index, if it is in range.
+    pub fn get(&self, index: usize) -> Option<bool>
+    {
+        (index < self.len)
+            .then(|| self.bytes[index / 8] & (0x80 >> (index % 8)) != 0)
+    }
+
+    /// Iterate over the bits.
+    pub fn iter(&self) -> impl Iterator<Item=bool> + '_
+    {
+        (0 .. self.len).map(|index| self.bytes[index / 8]
+                                    & (0x80 >> (index % 8)) != 0)
+    }
+}
+
+impl FromIterator<bool> for BitString
+{
+    /// Collect bits into a bit string.
+    ///
+    /// # Panics
+    ///
+    /// If there are more than [`BitString::MAX_LEN`] bits.
+    fn from_iter<I>(iter: I) -> Self
+        where I: IntoIterator<Item=bool>
+    {
+        let mut len = 0;
+        let mut bytes = Vec::new();
+        for bit in iter {
+            if len % 8 == 0 {
+                bytes.push(0);
+            }
+            if bit {
+                bytes[len / 8] |= 0x80 >> (len % 8);
+            }
+            len += 1;
+        }
+        assert!(len <= Self::MAX_LEN, "bit string is too long");
+        Self{len, bytes}
+    }
+}
+
+/// Write the bits as `0` and `1` characters, as in `10110`.
+impl fmt::Display for BitString
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        self.iter()
+            .try_for_each(|bit| f.write_str(if bit { "1" } else { "0" }))
+    }
+}
+
+/// Parse bits written as `0` and `1` characters.
+impl FromStr for BitString
+{
+    type Err = ParseBitStringError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        if s.len() > Self::MAX_LEN {
+            return Err(ParseBitStringError(()));
+        }
+        s.bytes()
+            .map(|byte| match byte {
+                b'0' => Ok(false),
+                b'1' => Ok(true),
+                _ => Err(ParseBitStringError(())),
+            })
+            .collect()
+    }
+}
+
+impl ToSql for BitString
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        [Type::BIT, Type::VARBIT].contains(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        match cx.format_code() {
+            FormatCode::Binary => {
+                let len = i32::try_from(self.len)
+                    .map_err(|_| invalid_value(cx, "too many bits"))?;
+                buf.extend_from_slice(&len.to_be_bytes());
+                buf.extend_from_slice(&self.bytes);
+            },
+            FormatCode::Text => write!(buf, "{self}")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl<'a> FromSql<'a> for BitString
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        [Type::BIT, Type::VARBIT].contains(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        match cx.format_code() {
+            FormatCode::Binary => {
+                let (len, bytes) = raw.split_at_checked(4)
+                    .ok_or_else(|| invalid_value(cx, "missing length"))?;
+                let len = i32::from_be_bytes(fixed_length(cx, len)?);
+                usize::try_from(len).ok()
+                    .and_then(|len| Self::new(bytes.to_vec(), len))
+                    .ok_or_else(|| invalid_value(cx, "length out of range"))
+            },
+            FormatCode::Text => text_value(cx, raw)?.parse()
+                .map_err(|_| invalid_value(cx, "malformed text")),
+        }
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::tests::{fuzz_from_sql, round_trip},
+        super::*,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<BitString>(&Type::BIT);
+        fuzz_from_sql::<BitString>(&Type::VARBIT);
+    }
+
+    #[test]
+    fn new()
+    {
+        let bits = BitString::new(vec![0xFF, 0xFF], 10).unwrap();
+        assert_eq!(bits.as_bytes(), [0xFF, 0xC0]);
+        assert_eq!(bits.to_string(), "1111111111");
+        assert_eq!(bits.get(9), Some(true));
+        assert_eq!(bits.get(10), None);
+
+        assert!(BitString::new(vec![], 0).unwrap().is_empty());
+        assert!(BitString::new(vec![0], 0).is_none());
+        assert!(BitString::new(vec![0], 9).is_none());
+        assert!(BitString::new(vec![0, 0], 8).is_none());
+    }
+
+    #[test]
+    fn parse()
+    {
+        let bits: BitString = "101000001".parse().unwrap();
+        assert_eq!(bits.len(), 9);
+        assert_eq!(bits.as_bytes(), [0xA0, 0x80]);
+        assert_eq!(bits, [true, false, true, false, false, false, false,
+                          false, true].into_iter().collect());
+        assert_eq!("".parse::<BitString>().unwrap(), BitString::default());
+        assert!("102".parse::<BitString>().is_err());
+        assert!("x1F".parse::<BitString>().is_err());
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for bits in ["", "0", "1", "10110", "11111111", "111111110"] {
+                let bits: BitString = bits.parse().unwrap();
+                round_trip(&Type::BIT, format_code, &bits);
+                round_trip(&Type::VARBIT, format_code, &bits);
+            }
+        }
+    }
+}
@@ -0,0 +1,435 @@
+//! Codecs for composite types and `record`.
+//!
+//! In binary format, a composite value is the number of fields,
+//! followed by the type OID and the length-prefixed value of each field.
+//! Named composite types must be looked up with [`PgClient::lookup_type`]
+//! before their values can be converted, so that their fields are known.
+//! Values of type `record` describe the type of each field by OID.
+//! Only built-in types can be resolved from these OIDs, so fields
+//! of other types, such as enum, domain, and composite types,
+//! can only be decoded as part of a named composite type.
+//! Text format is not supported; see [`parse_composite_literal`].
+//!
+//! [`PgClient::lookup_type`]: `crate::PgClient::lookup_type`
+//! [`parse_composite_literal`]: `super::parse_composite_literal`
+
+use {
+    crate::{Error, Result, protocol::FormatCode},
+    super::{
+        Context,
+        FromSql,
+        IsNull,
+        Kind,
+        Parameter,
+        SessionParameters,
+        ToSql,
+        Type,
+        from_sql_checked,
+        invalid_value,
+        to_sql_checked,
+    },
+};
+
+/// Value of a composite type or of type `record`,
+/// whose fields are decoded on access.
+///
+/// This can be used to implement [`FromSql`] for a struct,
+/// by decoding the fields of the record by index or by name.
+/// Likewise, [`write_composite`] can be used to implement [`ToSql`].
+/// Composite values can also be converted to and from tuples,
+/// whose elements are the fields in order.
+#[derive(Clone, Debug)]
+pub struct Record<'a>
+{
+    ty: Type,
+    fields: Vec<(Type, Option<&'a [u8]>)>,
+    session_parameters: SessionParameters,
+}
+
+impl<'a> Record<'a>
+{
+    /// The type of the record, which is `record`
+    /// or a type of kind [`Kind::Composite`].
+    pub fn ty(&self) -> &Type
+    {
+        &self.ty
+    }
+
+    /// The number of fields.
+    pub fn len(&self) -> usize
+    {
+        self.fields.len()
+    }
+
+    /// Whether the record has no fields.
+    pub fn is_empty(&self) -> bool
+    {
+        self.fields.is_empty()
+    }
+
+    /// The type of the field with the given index.
+    ///
+    /// If the type of the record is `record`, fields whose types
+    /// are not built in have types of kind [`Kind::Simple`] without
+    /// a name, as the client does not know them, even if they have
+    /// been looked up with [`PgClient::lookup_type`].
+    ///
+    /// [`PgClient::lookup_type`]: `crate::PgClient::lookup_type`
+    pub fn field_type(&self, index: usize) -> Option<&Type>
+    {
+        self.fields.get(index).map(|(ty, _)| ty)
+    }
+
+    /// The name of the field with the given index.
+    ///
+    /// Fields only have names if the type of the record
+    /// is a composite type rather than `record`.
+    pub fn field_name(&self, index: usize) -> Option<&str>
+    {
+        match self.ty.kind() {
+            Kind::Composite(fields) => fields.get(index).map(|f| f.name()),
+            _ => None,
+        }
+    }
+
+    /// The index of the field with the given name.
+    pub fn index_of(&self, name: &str) -> Option<usize>
+    {
+        match self.ty.kind() {
+            Kind::Composite(fields) =>
+                fields.iter().position(|field| field.name() == name),
+            _ => None,
+        }
+    }
+
+    /// Decode the value of the field with the given index.
+    ///
+    /// Returns [`Error::WrongType`] if `T` does not accept the type
+    /// of the field, and [`Error::UnexpectedNull`] if the value is
+    /// SQL NULL and `T` is not an [`Option`].
+    pub fn get<T>(&self, index: usize) -> Result<T>
+        where T: FromSql<'a>
+    {
+        let (ty, value) = self.fields.get(index)
+            .ok_or_else(|| self.no_such_field(&index))?;
+        let cx = Context::new(ty, FormatCode::Binary)
+            .with_session_parameters(&self.session_parameters);
+        from_sql_checked(&cx, *value)
+    }
+
+    /// Decode the value of the field with the given name.
+    ///
+    /// See [`get`][`Self::get`] for details.
+    pub fn get_by_name<T>(&self, name: &str) -> Result<T>
+        where T: FromSql<'a>
+    {
+        let index = self.index_of(name)
+            .ok_or_else(|| self.no_such_field(&name))?;
+        self.get(index)
+    }
+
+    /// The value of the field with the given index, in binary format.
+    ///
+    /// The inner [`None`] represents SQL NULL.
+    pub fn get_raw(&self, index: usize) -> Option<Option<&'a [u8]>>
+    {
+        self.fields.get(index).map(|&(_, value)| value)
+    }
+
+    fn no_such_field(&self, field: &dyn std::fmt::Debug) -> Error
+    {
+        let message = format!("{} value has no field {field:?}", self.ty);
+        Error::Conversion(message.into())
+    }
+}
+
+/// Decoded from values of type `record` and of composite types.
+/// In binary format only.
+impl<'a> FromSql<'a> for Record<'a>
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::RECORD || matches!(ty.kind(), Kind::Composite(..))
+    }
+
+    fn from_sql(cx: &Context, mut raw: &'a [u8]) -> Result<Self>
+    {
+        if cx.format_code() == FormatCode::Text {
+            return Err(invalid_value(cx, "text format not supported"));
+        }
+
+        let read_i32 = |raw: &mut &[u8]| -> Result<i32> {
+            let (bytes, rest) = raw.split_first_chunk::<4>()
+                .ok_or_else(|| invalid_value(cx, "malformed binary value"))?;
+            *raw = rest;
+            Ok(i32::from_be_bytes(*bytes))
+        };
+
+        let declared = match cx.ty().kind() {
+            Kind::Composite(fields) => Some(fields),
+            _ => None,
+        };
+
+        let count = usize::try_from(read_i32(&mut raw)?)
+            .map_err(|_| invalid_value(cx, "invalid number of fields"))?;
+        if declared.is_some_and(|fields| fields.len() != count) {
+            return Err(invalid_value(cx, "wrong number of fields"));
+        }
+
+        // Each field takes at least eight bytes, so a corrupt
+        // count cannot cause an arbitrarily large allocation.
+        let mut fields = Vec::with_capacity(count.min(raw.len() / 8));
+        for index in 0 .. count {
+            let oid = read_i32(&mut raw)?.cast_unsigned();
+            let ty = match declared.and_then(|fields| fields.get(index)) {
+                Some(field) if field.ty().oid() == oid =>
+                    field.ty().clone(),
+                Some(field) => {
+                    let message = format!("invalid {} value: field {} \
+                                           has type OID {oid}",
+                                          cx.ty(), field.name());
+                    return Err(Error::Conversion(message.into()));
+                },
+                None =>
+                    Type::from_oid_or_unknown(oid),
+            };
+
+            let length = read_i32(&mut raw)?;
+            let value = if length == -1 {
+                None
+            } else {
+                let value = usize::try_from(length).ok()
+                    .and_then(|length| raw.split_at_checked(length))
+                    .map(|(value, rest)| { raw = rest; value })
+                    .ok_or_else(|| invalid_value(cx, "malformed field"))?;
+                Some(value)
+            };
+
+            fields.push((ty, value));
+        }
+
+        if !raw.is_empty() {
+            return Err(invalid_value(cx, "trailing data"));
+        }
+
+        let ty = cx.ty().clone();
+        let session_parameters = cx.session_parameters().clone();
+        Ok(Self{ty, fields, session_parameters})
+    }
+}
+
+/// Encode a value of a composite type from the values of its fields.
+///
+/// This can be used to implement [`ToSql`] for a struct.
+/// The type in the context must be of kind [`Kind::Composite`],
+/// with one field for each value. Each value is checked against
+/// the type of its field. Values of type `record` cannot be encoded,
+/// as the server does not support them as parameters.
+/// In binary format only.
+pub fn write_composite(
+    cx: &Context,
+    fields: &[&dyn Parameter],
+    buf: &mut Vec<u8>,
+) -> Result<IsNull>
+{
+    if cx.format_code() == FormatCode::Text {
+        return Err(invalid_value(cx, "text format not supported"));
+    }
+    let Kind::Composite(declared) = cx.ty().kind()
+        else { return Err(invalid_value(cx, "not a composite type")) };
+    if declared.len() != fields.len() {
+        return Err(invalid_value(cx, "wrong number of fields"));
+    }
+
+    let too_large = || invalid_value(cx, "field too large");
+    let count = i32::try_from(fields.len()).map_err(|_| too_large())?;
+    buf.extend_from_slice(&count.to_be_bytes());
+    for (field, value) in declared.iter().zip(fields) {
+        buf.extend_from_slice(&field.ty().oid().to_be_bytes());
+        let start = buf.len();
+        buf.extend_from_slice(&[0; 4]);
+        let fcx = Context::new(field.ty(), FormatCode::Binary)
+            .with_session_parameters(cx.session_parameters());
+        let length = match to_sql_checked(*value, &fcx, buf)? {
+            IsNull::Yes => {
+                buf.truncate(start + 4);
+                -1
+            },
+            IsNull::No =>
+                i32::try_from(buf.len() - start - 4)
+                    .map_err(|_| too_large())?,
+        };
+        buf[start .. start + 4].copy_from_slice(&length.to_be_bytes());
+    }
+
+    Ok(IsNull::No)
+}
+
+/// Whether the type is a composite type with one field for each
+/// of the given predicates, and each field is accepted by its predicate.
+fn accepts_fields(ty: &Type, accepts: &[fn(&Type) -> bool]) -> bool
+{
+    match ty.kind() {
+        Kind::Composite(fields) =>
+            fields.len() == accepts.len()
+                && fields.iter().zip(accepts).all(|(f, a)| a(f.ty())),
+        _ => false,
+    }
+}
+
+/// Implement [`ToSql`] and [`FromSql`] for a tuple type.
+macro_rules! tuple_codec
+{
+    ($len:literal; $($t:ident $i:tt),+) => {
+        /// Converted to and from composite types with the same number
+        /// of fields, and decoded from `record`. In binary format only.
+        impl<$($t),+> ToSql for ($($t,)+)
+            where $($t: ToSql),+
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                accepts_fields(ty, &[$(<$t as ToSql>::accepts),+])
+            }
+
+            fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>)
+                -> Result<IsNull>
+            {
+                write_composite(cx, &[$(&self.$i as &dyn Parameter),+], buf)
+            }
+        }
+
+        /// Converted to and from composite types with the same number
+        /// of fields, and decoded from `record`. In binary format only.
+        impl<'a, $($t),+> FromSql<'a> for ($($t,)+)
+            where $($t: FromSql<'a>),+
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                *ty == Type::RECORD
+                    || accepts_fields(ty, &[$(<$t as FromSql>::accepts),+])
+            }
+
+            fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+            {
+                let record = Record::from_sql(cx, raw)?;
+                if record.len() != $len {
+                    return Err(invalid_value(cx, "wrong number of fields"));
+                }
+                Ok(($(record.get($i)?,)+))
+            }
+        }
+    };
+}
+
+tuple_codec!(1; T0 0);
+tuple_codec!(2; T0 0, T1 1);
+tuple_codec!(3; T0 0, T1 1, T2 2);
+tuple_codec!(4; T0 0, T1 1, T2 2, T3 3);
+tuple_codec!(5; T0 0, T1 1, T2 2, T3 3, T4 4);
+tuple_codec!(6; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5);
+tuple_codec!(7; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6);
+tuple_codec!(8; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7);
+tuple_codec!(9; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8);
+tuple_codec!(10; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+                 T9 9);
+tuple_codec!(11; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+                 T9 9, T10 10);
+tuple_codec!(12; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+                 T9 9, T10 10, T11 11);
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::{
+            Field,
+            tests::{fuzz_from_sql, round_trip},
+        },
+        super::*,
+    };
+
+    fn composite() -> Type
+    {
+        let fields = vec![
+            Field::new("id".into(), Type::INT4),
+            Field::new("name".into(), Type::TEXT),
+            Field::new("tags".into(), Type::TEXT_ARRAY),
+        ];
+        Type::new(100_000, "item".into(), Kind::Composite(fields))
+    }
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<(i32, Option<String>)>(&Type::RECORD);
+        fuzz_from_sql::<(i32, String, Vec<String>)>(&composite());
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        let ty = composite();
+        round_trip(&ty, FormatCode::Binary,
+                   &(1, "a".to_owned(), vec!["b".to_owned()]));
+        round_trip(&ty, FormatCode::Binary,
+                   &(Some(1), None::<String>, Some(Vec::<String>::new())));
+
+        let outer = Type::new(100_001, "outer".into(), Kind::Composite(vec![
+            Field::new("inner".into(), ty.clone()),
+            Field::new("inners".into(), Type::new(
+                100_002, "_item".into(), Kind::Array(ty.clone()),
+            )),
+        ]));
+        let inner = (2, "c".to_owned(), Vec::<String>::new());
+        round_trip(&outer, FormatCode::Binary,
+                   &(inner.clone(), vec![inner.clone(), inner]));
+    }
+
+    #[test]
+    fn record()
+    {
+        let ty = composite();
+        let cx = Context::new(&ty, FormatCode::Binary);
+        let mut buf = Vec::new();
+        write_composite(&cx, &[&7, &"x", &None::<Vec<String>>], &mut buf)
+            .unwrap();
+
+        let record: Record = from_sql_checked(&cx, Some(&buf)).unwrap();
+        assert_eq!(record.len(), 3);
+        assert_eq!(record.field_name(1), Some("name"));
+        assert_eq!(record.field_type(2), Some(&Type::TEXT_ARRAY));
+        assert_eq!(record.get::<i32>(0).unwrap(), 7);
+        assert_eq!(record.get_by_name::<&str>("name").unwrap(), "x");
+        assert_eq!(record.get::<Option<Vec<String>>>(2).unwrap(), None);
+        assert_eq!(record.get_raw(2), Some(None));
+        assert!(matches!(record.get::<i64>(0), Err(Error::WrongType{..})));
+        assert!(matches!(record.get::<i32>(3), Err(Error::Conversion(..))));
+        assert!(record.get_by_name::<i32>("nope").is_err());
+
+        // Decoded as record, the fields are described by OID only.
+        let cx = Context::new(&Type::RECORD, FormatCode::Binary);
+        let record: Record = from_sql_checked(&cx, Some(&buf)).unwrap();
+        assert_eq!(record.field_name(1), None);
+        assert_eq!(record.get::<&str>(1).unwrap(), "x");
+        let tuple = from_sql_checked::<(i32, String)>(&cx, Some(&buf));
+        assert!(matches!(tuple, Err(Error::Conversion(..))));
+    }
+
+    #[test]
+    fn rejects()
+    {
+        let ty = composite();
+        let cx = Context::new(&ty, FormatCode::Binary);
+        let mut buf = Vec::new();
+        assert!(write_composite(&cx, &[&7, &"x"], &mut buf).is_err());
+        assert!(write_composite(&cx, &[&7, &7, &7], &mut buf).is_err());
+
+        // The OID of each field must match the declared type.
+        let mut raw = vec![0, 0, 0, 3];
+        for oid in [Type::INT8.oid(), Type::TEXT.oid(), Type::TEXT.oid()] {
+            raw.extend_from_slice(&oid.to_be_bytes());
+            raw.extend_from_slice(&(-1i32).to_be_bytes());
+        }
+        assert!(from_sql_checked::<Record>(&cx, Some(&raw)).is_err());
+    }
+}
@@ -0,0 +1,772 @@
+//! Codecs for date and time types.
+//!
+//! PostgreSQL represents dates as days since 2000-01-01,
+//! times of day as microseconds since midnight,
+//! and timestamps as microseconds since 2000-01-01 00:00:00.
+//! The types in this module use the same representation,
+//! so that every value round-trips, including `infinity`.
+
+use {
+    crate::{Error, Result, protocol::FormatCode},
+    super::{
+        Context,
+        FromSql,
+        IsNull,
+        ToSql,
+        Type,
+        datetime_text::{
+            parse_date,
+            parse_interval,
+            parse_time,
+            parse_timestamp,
+            parse_timestamptz,
+            parse_timetz,
+        },
+        fixed_length,
+        invalid_value,
+        text_value,
+    },
+    std::{fmt, io::Write},
+};
+
+/// The number of microseconds in a day.
+const USECS_PER_DAY: i64 = 86_400_000_000;
+
+/// The date 1970-01-01, in days since 2000-01-01.
+const UNIX_EPOCH_DAYS: i64 = -10_957;
+
+/// Value of type `date`.
+///
+/// Dates use the proleptic Gregorian calendar with astronomical
+/// year numbering, so year 0 is 1 BC, year -1 is 2 BC, and so on.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Date(i32);
+
+/// Value of type `time`, which is a time of day without a time zone.
+///
+/// This ranges from 00:00:00 up to and including 24:00:00.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Time(i64);
+
+/// Value of type `timetz`, which is a time of day with a UTC offset.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TimeTz
+{
+    time: Time,
+    utc_offset: i32,
+}
+
+/// Value of type `timestamp`, which is a date and time without a time zone.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Timestamp(i64);
+
+/// Value of type `timestamptz`, which is a point in time.
+///
+/// Despite its name, a `timestamptz` value does not include a time zone.
+/// The server converts it to and from the `TimeZone` setting
+/// in text format only.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct TimestampTz(i64);
+
+/// Value of type `interval`.
+///
+/// The three fields are independent, as the number of days in a month
+/// and the number of microseconds in a day vary.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Interval
+{
+    #[allow(missing_docs)]
+    pub months: i32,
+    #[allow(missing_docs)]
+    pub days: i32,
+    #[allow(missing_docs)]
+    pub microseconds: i64,
+}
+
+impl Date
+{
+    /// The date `infinity`, which is later than all other dates.
+    pub const INFINITY: Self = Self(i32::MAX);
+
+    /// The date `-infinity`, which is earlier than all other dates.
+    pub const NEG_INFINITY: Self = Self(i32::MIN);
+
+    /// Create a date from the number of days since 2000-01-01.
+    ///
+    /// [`i32::MAX`] and [`i32::MIN`] are the infinities.
+    pub const fn from_days(days: i32) -> Self
+    {
+        Self(days)
+    }
+
+    /// The number of days since 2000-01-01.
+    pub const fn days(self) -> i32
+    {
+        self.0
+    }
+
+    /// Whether the date is neither `infinity` nor `-infinity`.
+    pub const fn is_finite(self) -> bool
+    {
+        self.0 != i32::MAX && self.0 != i32::MIN
+    }
+
+    /// Create a date from a year, month, and day.
+    ///
+    /// Returns [`None`] if the date does not exist.
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self>
+    {
+        if !(1 ..= 12).contains(&month) || day < 1
+            || day > days_in_month(year, month) {
+            return None;
+        }
+        let days = days_from_civil(year.into(), month.into(), day.into())
+            + UNIX_EPOCH_DAYS;
+        i32::try_from(days).ok().map(Self).filter(|date| date.is_finite())
+    }
+
+    /// The year, month, and day of the date.
+    ///
+    /// Returns [`None`] for the infinities.
+    pub fn to_ymd(self) -> Option<(i32, u32, u32)>
+    {
+        if !self.is_finite() {
+            return None;
+        }
+        let (year, month, day) =
+            civil_from_days(i64::from(self.0) - UNIX_EPOCH_DAYS);
+        // The year is within range, as the number of days is.
+        Some((year as i32, month as u32, day as u32))
+    }
+}
+
+impl Time
+{
+    /// Midnight at the start of the day.
+    pub const MIDNIGHT: Self = Self(0);
+
+    /// Create a time from the number of microseconds since midnight.
+    ///
+    /// Returns [`None`] if this is more than a day.
+    pub fn from_microseconds(microseconds: i64) -> Option<Self>
+    {
+        (0 ..= USECS_PER_DAY).contains(&microseconds)
+            .then_some(Self(microseconds))
+    }
+
+    /// The number of microseconds since midnight.
+    pub const fn microseconds(self) -> i64
+    {
+        self.0
+    }
+
+    /// Create a time from hours, minutes, seconds, and microseconds.
+    ///
+    /// Returns [`None`] if any of the components is out of range,
+    /// except that 24:00:00 is allowed.
+    pub fn from_hms_micro(hour: u32, minute: u32, second: u32, micro: u32)
+        -> Option<Self>
+    {
+        if hour > 24 || minute > 59 || second > 59 || micro > 999_999 {
+            return None;
+        }
+        let microseconds =
+            ((i64::from(hour) * 60 + i64::from(minute)) * 60
+             + i64::from(second)) * 1_000_000 + i64::from(micro);
+        Self::from_microseconds(microseconds)
+    }
+
+    /// The hours, minutes, seconds, and microseconds of the time.
+    pub fn to_hms_micro(self) -> (u32, u32, u32, u32)
+    {
+        let micro = self.0 % 1_000_000;
+        let seconds = self.0 / 1_000_000;
+        // The components fit, as the time is at most a day.
+        let hms = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        (hms.0 as u32, hms.1 as u32, hms.2 as u32, micro as u32)
+    }
+}
+
+impl TimeTz
+{
+    /// Create a time with a UTC offset in seconds east of UTC.
+    ///
+    /// Returns [`None`] if the offset is 16 hours or more,
+    /// which PostgreSQL rejects as out of range.
+    pub fn new(time: Time, utc_offset: i32) -> Option<Self>
+    {
+        (utc_offset.unsigned_abs() < 16 * 3600)
+            .then_some(Self{time, utc_offset})
+    }
+
+    /// The time of day, in the UTC offset.
+    pub fn time(self) -> Time
+    {
+        self.time
+    }
+
+    /// The UTC offset in seconds east of UTC.
+    pub fn utc_offset(self) -> i32
+    {
+        self.utc_offset
+    }
+}
+
+impl Timestamp
+{
+    /// The timestamp `infinity`, which is later than all other timestamps.
+    pub const INFINITY: Self = Self(i64::MAX);
+
+    /// The timestamp `-infinity`, which is earlier than all other timestamps.
+    pub const NEG_INFINITY: Self = Self(i64::MIN);
+
+    /// Create a timestamp from the number of microseconds
+    /// since 2000-01-01 00:00:00.
+    ///
+    /// [`i64::MAX`] and [`i64::MIN`] are the infinities.
+    pub const fn from_microseconds(microseconds: i64) -> Self
+    {
+        Self(microseconds)
+    }
+
+    /// The number of microseconds since 2000-01-01 00:00:00.
+    pub const fn microseconds(self) -> i64
+    {
+        self.0
+    }
+
+    /// Whether the timestamp is neither `infinity` nor `-infinity`.
+    pub const fn is_finite(self) -> bool
+    {
+        self.0 != i64::MAX && self.0 != i64::MIN
+    }
+
+    /// Create a timestamp from a date and a time.
+    ///
+    /// Returns [`None`] if the date is infinite
+    /// or the timestamp is out of range.
+    pub fn new(date: Date, time: Time) -> Option<Self>
+    {
+        if !date.is_finite() {
+            return None;
+        }
+        i64::from(date.0).checked_mul(USECS_PER_DAY)
+            .and_then(|microseconds| microseconds.checked_add(time.0))
+            .map(Self)
+            .filter(|timestamp| timestamp.is_finite())
+    }
+
+    /// The date and time of the timestamp.
+    ///
+    /// Returns [`None`] for the infinities.
+    pub fn to_date_time(self) -> Option<(Date, Time)>
+    {
+        if !self.is_finite() {
+            return None;
+        }
+        // The number of days fits, as there are more than
+        // 2^32 microseconds in a day.
+        let date = Date(self.0.div_euclid(USECS_PER_DAY) as i32);
+        let time = Time(self.0.rem_euclid(USECS_PER_DAY));
+        Some((date, time))
+    }
+}
+
+impl TimestampTz
+{
+    /// The timestamp `infinity`, which is later than all other timestamps.
+    pub const INFINITY: Self = Self(i64::MAX);
+
+    /// The timestamp `-infinity`, which is earlier than all other timestamps.
+    pub const NEG_INFINITY: Self = Self(i64::MIN);
+
+    /// Create a timestamp from the number of microseconds
+    /// since 2000-01-01 00:00:00 UTC.
+    ///
+    /// [`i64::MAX`] and [`i64::MIN`] are the infinities.
+    pub const fn from_microseconds(microseconds: i64) -> Self
+    {
+        Self(microseconds)
+    }
+
+    /// The number of microseconds since 2000-01-01 00:00:00 UTC.
+    pub const fn microseconds(self) -> i64
+    {
+        self.0
+    }
+
+    /// Whether the timestamp is neither `infinity` nor `-infinity`.
+    pub const fn is_finite(self) -> bool
+    {
+        self.0 != i64::MAX && self.0 != i64::MIN
+    }
+
+    /// Interpret a timestamp without a time zone as UTC.
+    pub const fn from_utc(timestamp: Timestamp) -> Self
+    {
+        Self(timestamp.0)
+    }
+
+    /// The timestamp in UTC, without a time zone.
+    pub const fn to_utc(self) -> Timestamp
+    {
+        Timestamp(self.0)
+    }
+}
+
+/// The number of days in a month of a year.
+fn days_in_month(year: i32, month: u32) -> u32
+{
+    let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    match month {
+        2 if is_leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The number of days since 1970-01-01 of a date.
+///
+/// This is the `days_from_civil` algorithm by Howard Hinnant.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64
+{
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100
+        + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date of a number of days since 1970-01-01.
+///
+/// This is the `civil_from_days` algorithm by Howard Hinnant.
+fn civil_from_days(days: i64) -> (i64, i64, i64)
+{
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524
+        - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era
+        - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 }
+                else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+impl fmt::Display for Date
+{
+    /// Format the date in ISO 8601 format, the way PostgreSQL does.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self.to_ymd() {
+            None if *self == Self::INFINITY => f.write_str("infinity"),
+            None => f.write_str("-infinity"),
+            Some((year, month, day)) if year <= 0 =>
+                write!(f, "{:04}-{month:02}-{day:02} BC", 1 - year),
+            Some((year, month, day)) =>
+                write!(f, "{year:04}-{month:02}-{day:02}"),
+        }
+    }
+}
+
+impl fmt::Display for Time
+{
+    /// Format the time in ISO 8601 format, the way PostgreSQL does.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let (hour, minute, second, micro) = self.to_hms_micro();
+        write!(f, "{hour:02}:{minute:02}:{second:02}")?;
+        if micro != 0 {
+            let fraction = format!("{micro:06}");
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TimeTz
+{
+    /// Format the time in ISO 8601 format, the way PostgreSQL does.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let sign = if self.utc_offset < 0 { '-' } else { '+' };
+        let offset = self.utc_offset.unsigned_abs();
+        let (hours, minutes, seconds) =
+            (offset / 3600, offset / 60 % 60, offset % 60);
+        write!(f, "{}{sign}{hours:02}", self.time)?;
+        if minutes != 0 || seconds != 0 {
+            write!(f, ":{minutes:02}")?;
+        }
+        if seconds != 0 {
+            write!(f, ":{seconds:02}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Timestamp
+{
+    /// Format the timestamp in ISO 8601 format, the way PostgreSQL does.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self.to_date_time() {
+            None if *self == Self::INFINITY => f.write_str("infinity"),
+            None => f.write_str("-infinity"),
+            Some((date, time)) => {
+                let (year, month, day) = date.to_ymd().unwrap_or_default();
+                write!(f, "{:04}-{month:02}-{day:02} {time}",
+                       if year <= 0 { 1 - year } else { year })?;
+                if year <= 0 {
+                    f.write_str(" BC")?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl fmt::Display for TimestampTz
+{
+    /// Format the timestamp in ISO 8601 format in UTC.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let timestamp = self.to_utc().to_string();
+        match timestamp.strip_suffix(" BC") {
+            _ if !self.is_finite() => f.write_str(&timestamp),
+            Some(timestamp) => write!(f, "{timestamp}+00 BC"),
+            None => write!(f, "{timestamp}+00"),
+        }
+    }
+}
+
+impl fmt::Display for Interval
+{
+    /// Format the interval in ISO 8601 format with designators,
+    /// which PostgreSQL accepts regardless of `IntervalStyle`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let sign = if self.microseconds < 0 { "-" } else { "" };
+        let microseconds = self.microseconds.unsigned_abs();
+        let seconds = microseconds / 1_000_000;
+        write!(f, "P{}M{}DT{sign}{}H{sign}{}M{sign}{}.{:06}S",
+               self.months, self.days, seconds / 3600, seconds / 60 % 60,
+               seconds % 60, microseconds % 1_000_000)
+    }
+}
+
+/// Check that the server represents date and time values as integers.
+fn check_integer_datetimes(cx: &Context) -> Result<()>
+{
+    if cx.session_parameters().integer_datetimes() {
+        Ok(())
+    } else {
+        let message = "floating-point date and time values are not supported";
+        Err(Error::Conversion(message.into()))
+    }
+}
+
+/// Implement [`ToSql`] and [`FromSql`] for a date or time type.
+///
+/// Values are encoded in text format as `Display` formats them,
+/// which the server accepts regardless of its settings.
+/// Values are decoded from text format in the style
+/// given by the session parameters of the context.
+macro_rules! datetime_codec
+{
+    ($rust:ty, $pg:ident, $encode:expr, $decode:expr, $parse:expr) => {
+        impl ToSql for $rust
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                *ty == Type::$pg
+            }
+
+            fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>)
+                -> Result<IsNull>
+            {
+                match cx.format_code() {
+                    FormatCode::Binary => {
+                        check_integer_datetimes(cx)?;
+                        $encode(self, buf);
+                    },
+                    FormatCode::Text =>
+                        write!(buf, "{self}")?,
+                }
+                Ok(IsNull::No)
+            }
+        }
+
+        impl<'a> FromSql<'a> for $rust
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                *ty == Type::$pg
+            }
+
+            fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+            {
+                match cx.format_code() {
+                    FormatCode::Binary => {
+                        check_integer_datetimes(cx)?;
+                        $decode(cx, raw)
+                    },
+                    FormatCode::Text => {
+                        let text = text_value(cx, raw)?;
+                        $parse(cx.session_parameters(), text)
+                            .ok_or_else(|| invalid_value(cx, "malformed text"))
+                    },
+                }
+            }
+
+            fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+            {
+                <Self as FromSql>::from_sql(cx, raw)
+            }
+        }
+    };
+}
+
+datetime_codec!(
+    Date, DATE,
+    |value: &Date, buf: &mut Vec<u8>| {
+        buf.extend_from_slice(&value.0.to_be_bytes());
+    },
+    |cx, raw| Ok(Date(i32::from_be_bytes(fixed_length(cx, raw)?))),
+    parse_date
+);
+
+datetime_codec!(
+    Time, TIME,
+    |value: &Time, buf: &mut Vec<u8>| {
+        buf.extend_from_slice(&value.0.to_be_bytes());
+    },
+    |cx, raw| {
+        let microseconds = i64::from_be_bytes(fixed_length(cx, raw)?);
+        Time::from_microseconds(microseconds)
+            .ok_or_else(|| invalid_value(cx, "out of range"))
+    },
+    |_, text| parse_time(text)
+);
+
+// The UTC offset is sent in seconds west of UTC.
+datetime_codec!(
+    TimeTz, TIMETZ,
+    |value: &TimeTz, buf: &mut Vec<u8>| {
+        buf.extend_from_slice(&value.time.0.to_be_bytes());
+        buf.extend_from_slice(&(-value.utc_offset).to_be_bytes());
+    },
+    |cx, raw| {
+        let raw: [u8; 12] = fixed_length(cx, raw)?;
+        let time = i64::from_be_bytes(fixed_length(cx, &raw[.. 8])?);
+        let zone = i32::from_be_bytes(fixed_length(cx, &raw[8 ..])?);
+        Time::from_microseconds(time)
+            .and_then(|time| TimeTz::new(time, zone.checked_neg()?))
+            .ok_or_else(|| invalid_value(cx, "out of range"))
+    },
+    |_, text| parse_timetz(text)
+);
+
+datetime_codec!(
+    Timestamp, TIMESTAMP,
+    |value: &Timestamp, buf: &mut Vec<u8>| {
+        buf.extend_from_slice(&value.0.to_be_bytes());
+    },
+    |cx, raw| Ok(Timestamp(i64::from_be_bytes(fixed_length(cx, raw)?))),
+    parse_timestamp
+);
+
+datetime_codec!(
+    TimestampTz, TIMESTAMPTZ,
+    |value: &TimestampTz, buf: &mut Vec<u8>| {
+        buf.extend_from_slice(&value.0.to_be_bytes());
+    },
+    |cx, raw| Ok(TimestampTz(i64::from_be_bytes(fixed_length(cx, raw)?))),
+    parse_timestamptz
+);
+
+datetime_codec!(
+    Interval, INTERVAL,
+    |value: &Interval, buf: &mut Vec<u8>| {
+        buf.extend_from_slice(&value.microseconds.to_be_bytes());
+        buf.extend_from_slice(&value.days.to_be_bytes());
+        buf.extend_from_slice(&value.months.to_be_bytes());
+    },
+    |cx, raw| {
+        let raw: [u8; 16] = fixed_length(cx, raw)?;
+        Ok(Interval{
+            microseconds: i64::from_be_bytes(fixed_length(cx, &raw[.. 8])?),
+            days: i32::from_be_bytes(fixed_length(cx, &raw[8 .. 12])?),
+            months: i32::from_be_bytes(fixed_length(cx, &raw[12 ..])?),
+        })
+    },
+    parse_interval
+);
+
+/// Implement [`ToSql`] and [`FromSql`] for a type from another crate,
+/// by converting it to and from one of the types in this module.
+///
+/// The conversions return [`None`] if the value is out of range.
+#[cfg_attr(
+    not(any(feature = "chrono", feature = "time")),
+    allow(unused_macros),
+)]
+macro_rules! delegate_codec
+{
+    ($rust:ty, $native:ty, $to_native:expr, $from_native:expr) => {
+        impl ToSql for $rust
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                <$native as ToSql>::accepts(ty)
+            }
+
+            fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>)
+                -> Result<IsNull>
+            {
+                let native: Option<$native> = $to_native(self);
+                native.ok_or_else(|| out_of_range::<$native>(cx))?
+                    .to_sql(cx, buf)
+            }
+        }
+
+        impl<'a> FromSql<'a> for $rust
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                <$native as FromSql>::accepts(ty)
+            }
+
+            fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+            {
+                let native = <$native>::from_sql(cx, raw)?;
+                $from_native(native).ok_or_else(|| out_of_range::<Self>(cx))
+            }
+
+            fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+            {
+                <Self as FromSql>::from_sql(cx, raw)
+            }
+        }
+    };
+}
+
+#[allow(unused_imports)]
+pub (crate) use delegate_codec;
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::{
+            SessionParameters,
+            from_sql_checked,
+            tests::{fuzz_from_sql, round_trip},
+        },
+        super::*,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<Date>(&Type::DATE);
+        fuzz_from_sql::<Time>(&Type::TIME);
+        fuzz_from_sql::<TimeTz>(&Type::TIMETZ);
+        fuzz_from_sql::<Timestamp>(&Type::TIMESTAMP);
+        fuzz_from_sql::<TimestampTz>(&Type::TIMESTAMPTZ);
+        fuzz_from_sql::<Interval>(&Type::INTERVAL);
+    }
+
+    #[test]
+    fn calendar()
+    {
+        assert_eq!(Date::from_ymd(2000, 1, 1), Some(Date(0)));
+        assert_eq!(Date::from_ymd(1999, 12, 31), Some(Date(-1)));
+        assert_eq!(Date::from_ymd(2000, 3, 1), Some(Date(60)));
+        assert_eq!(Date::from_ymd(1970, 1, 1), Some(Date(-10_957)));
+        assert_eq!(Date::from_ymd(2000, 2, 30), None);
+        assert_eq!(Date::from_ymd(1900, 2, 29), None);
+        assert_eq!(Date::from_ymd(2000, 13, 1), None);
+        assert_eq!(Date::from_ymd(i32::MAX, 1, 1), None);
+
+        for days in (-3_000_000 .. 3_000_000).step_by(997) {
+            let (year, month, day) = Date(days).to_ymd().unwrap();
+            assert_eq!(Date::from_ymd(year, month, day), Some(Date(days)));
+        }
+
+        assert_eq!(Date::INFINITY.to_ymd(), None);
+        assert!(Date::NEG_INFINITY < Date(i32::MIN + 1));
+        assert!(Timestamp::new(Date::INFINITY, Time::MIDNIGHT).is_none());
+        assert!(TimeTz::new(Time::MIDNIGHT, -(16 * 3600 - 1)).is_some());
+        assert!(TimeTz::new(Time::MIDNIGHT, 16 * 3600).is_none());
+        assert!(TimeTz::new(Time::MIDNIGHT, -16 * 3600).is_none());
+    }
+
+    #[test]
+    fn display()
+    {
+        let date = |y, m, d| Date::from_ymd(y, m, d).unwrap();
+        let time = |h, m, s, us| Time::from_hms_micro(h, m, s, us).unwrap();
+        let timestamp = |d, t| Timestamp::new(d, t).unwrap();
+
+        assert_eq!(date(2024, 2, 29).to_string(), "2024-02-29");
+        assert_eq!(date(0, 1, 1).to_string(), "0001-01-01 BC");
+        assert_eq!(Date::NEG_INFINITY.to_string(), "-infinity");
+        assert_eq!(time(24, 0, 0, 0).to_string(), "24:00:00");
+        assert_eq!(time(1, 2, 3, 4500).to_string(), "01:02:03.0045");
+        assert_eq!(TimeTz::new(time(1, 2, 3, 0), -(5 * 3600 + 30 * 60))
+                   .unwrap().to_string(), "01:02:03-05:30");
+        assert_eq!(timestamp(date(-43, 3, 15), time(12, 0, 0, 0))
+                   .to_string(), "0044-03-15 12:00:00 BC");
+        assert_eq!(TimestampTz::from_microseconds(-1).to_string(),
+                   "1999-12-31 23:59:59.999999+00");
+        assert_eq!(Timestamp::INFINITY.to_string(), "infinity");
+        let interval =
+            Interval{months: 14, days: -3, microseconds: -3_723_000_004};
+        assert_eq!(interval.to_string(), "P14M-3DT-1H-2M-3.000004S");
+    }
+
+    #[test]
+    fn integer_datetimes()
+    {
+        let mut session_parameters = SessionParameters::default();
+        session_parameters.set(b"integer_datetimes", b"off");
+        let cx = Context::new(&Type::DATE, FormatCode::Binary)
+            .with_session_parameters(&session_parameters);
+        assert!(from_sql_checked::<Date>(&cx, Some(&[0; 4])).is_err());
+        assert!(Date(0).to_sql(&cx, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for days in [0, -1, 1, i32::MIN, i32::MAX] {
+                round_trip(&Type::DATE, format_code, &Date(days));
+            }
+            for microseconds in [0, 1, USECS_PER_DAY] {
+                let time = Time::from_microseconds(microseconds).unwrap();
+                round_trip(&Type::TIME, format_code, &time);
+                for utc_offset in [0, 3600, -57599] {
+                    let timetz = TimeTz::new(time, utc_offset).unwrap();
+                    round_trip(&Type::TIMETZ, format_code, &timetz);
+                }
+            }
+            for microseconds in [0, -1, 1, i64::MIN, i64::MAX] {
+                let timestamp = Timestamp(microseconds);
+                round_trip(&Type::TIMESTAMP, format_code, &timestamp);
+                let timestamptz = TimestampTz(microseconds);
+                round_trip(&Type::TIMESTAMPTZ, format_code, &timestamptz);
+            }
+            let interval =
+                Interval{months: -1, days: 2, microseconds: i64::MIN};
+            round_trip(&Type::INTERVAL, format_code, &interval);
+        }
+    }
+}
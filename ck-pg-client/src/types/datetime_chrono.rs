@@ -0,0 +1,174 @@
+//! Codecs for date and time types using the **chrono** crate.
+//!
+//! Sub-microsecond precision is truncated when encoding,
+//! and values that chrono cannot represent, such as the infinities,
+//! result in an error when decoding.
+
+use {
+    crate::Result,
+    super::{
+        Context,
+        Date,
+        FromSql,
+        IsNull,
+        Time,
+        Timestamp,
+        TimestampTz,
+        ToSql,
+        Type,
+        datetime::delegate_codec,
+        out_of_range,
+    },
+    chrono::{
+        DateTime,
+        Datelike,
+        NaiveDate,
+        NaiveDateTime,
+        NaiveTime,
+        TimeZone,
+        Timelike,
+        Utc,
+    },
+};
+
+/// The date 2000-01-01, in days since 0001-01-01, which is day 1.
+const POSTGRES_EPOCH_CE_DAYS: i32 = 730_120;
+
+fn from_naive_date(date: &NaiveDate) -> Option<Date>
+{
+    let days = date.num_days_from_ce().checked_sub(POSTGRES_EPOCH_CE_DAYS)?;
+    Some(Date::from_days(days)).filter(|date| date.is_finite())
+}
+
+fn to_naive_date(date: Date) -> Option<NaiveDate>
+{
+    if !date.is_finite() {
+        return None;
+    }
+    let days = date.days().checked_add(POSTGRES_EPOCH_CE_DAYS)?;
+    NaiveDate::from_num_days_from_ce_opt(days)
+}
+
+/// Leap seconds are rejected, as PostgreSQL does not support them.
+fn from_naive_time(time: &NaiveTime) -> Option<Time>
+{
+    let microseconds = i64::from(time.num_seconds_from_midnight()) * 1_000_000
+        + i64::from(time.nanosecond()) / 1_000;
+    Some(time)
+        .filter(|time| time.nanosecond() < 1_000_000_000)
+        .and_then(|_| Time::from_microseconds(microseconds))
+}
+
+fn to_naive_time(time: Time) -> Option<NaiveTime>
+{
+    let seconds = u32::try_from(time.microseconds() / 1_000_000).ok()?;
+    let nanoseconds = u32::try_from(time.microseconds() % 1_000_000).ok()?;
+    NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanoseconds * 1000)
+}
+
+fn from_naive_date_time(date_time: &NaiveDateTime) -> Option<Timestamp>
+{
+    let date = from_naive_date(&date_time.date())?;
+    let time = from_naive_time(&date_time.time())?;
+    Timestamp::new(date, time)
+}
+
+fn to_naive_date_time(timestamp: Timestamp) -> Option<NaiveDateTime>
+{
+    let (date, time) = timestamp.to_date_time()?;
+    Some(NaiveDateTime::new(to_naive_date(date)?, to_naive_time(time)?))
+}
+
+delegate_codec!(NaiveDate, Date, from_naive_date, to_naive_date);
+delegate_codec!(NaiveTime, Time, from_naive_time, to_naive_time);
+delegate_codec!(
+    NaiveDateTime, Timestamp,
+    from_naive_date_time, to_naive_date_time
+);
+
+/// Date and times in any time zone are encoded as `timestamptz`.
+impl<Tz> ToSql for DateTime<Tz>
+    where Tz: TimeZone
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <TimestampTz as ToSql>::accepts(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        from_naive_date_time(&self.naive_utc())
+            .map(TimestampTz::from_utc)
+            .ok_or_else(|| out_of_range::<TimestampTz>(cx))?
+            .to_sql(cx, buf)
+    }
+}
+
+impl<'a> FromSql<'a> for DateTime<Utc>
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <TimestampTz as FromSql>::accepts(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        let timestamp = TimestampTz::from_sql(cx, raw)?.to_utc();
+        to_naive_date_time(timestamp)
+            .map(|date_time| Utc.from_utc_datetime(&date_time))
+            .ok_or_else(|| out_of_range::<Self>(cx))
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::{protocol::FormatCode, types::tests::round_trip},
+        super::*,
+    };
+
+    #[test]
+    fn conversions()
+    {
+        let cx = Context::new(&Type::DATE, FormatCode::Binary);
+        let date = |days| {
+            let mut buf = Vec::new();
+            Date::from_days(days).to_sql(&cx, &mut buf).unwrap();
+            NaiveDate::from_sql(&cx, &buf).ok()
+        };
+        assert_eq!(date(0), NaiveDate::from_ymd_opt(2000, 1, 1));
+        assert_eq!(date(-730_119), NaiveDate::from_ymd_opt(1, 1, 1));
+        assert_eq!(date(-730_120), NaiveDate::from_ymd_opt(0, 12, 31));
+        assert_eq!(date(i32::MAX), None);
+        assert_eq!(date(i32::MAX - 1), None);
+
+        let cx = Context::new(&Type::TIME, FormatCode::Binary);
+        let midnight = Time::from_hms_micro(24, 0, 0, 0).unwrap();
+        let mut buf = Vec::new();
+        midnight.to_sql(&cx, &mut buf).unwrap();
+        assert!(NaiveTime::from_sql(&cx, &buf).is_err());
+        let leap_second =
+            NaiveTime::from_hms_nano_opt(23, 59, 59, 1_500_000_000).unwrap();
+        assert!(leap_second.to_sql(&cx, &mut Vec::new()).is_err());
+
+        let cx = Context::new(&Type::TIMESTAMPTZ, FormatCode::Binary);
+        let mut buf = Vec::new();
+        TimestampTz::INFINITY.to_sql(&cx, &mut buf).unwrap();
+        assert!(DateTime::<Utc>::from_sql(&cx, &buf).is_err());
+
+        let date_time = NaiveDate::from_ymd_opt(-44, 3, 15).unwrap()
+            .and_hms_micro_opt(12, 34, 56, 789_012).unwrap();
+        let binary = FormatCode::Binary;
+        round_trip(&Type::TIMESTAMP, binary, &date_time);
+        let utc = Utc.from_utc_datetime(&date_time);
+        round_trip(&Type::TIMESTAMPTZ, binary, &utc);
+        round_trip(&Type::DATE, binary, &date_time.date());
+        round_trip(&Type::TIME, binary, &date_time.time());
+    }
+}
@@ -0,0 +1,859 @@
+//! Parsing of date and time values in text format.
+//!
+//! The server formats these values according to the `DateStyle`,
+//! `IntervalStyle`, and `TimeZone` settings of the session.
+//! The parsers in this module accept what the server produces
+//! in the style given by the [`SessionParameters`],
+//! rather than everything the server accepts as input.
+//!
+//! In styles other than ISO, `timestamptz` values are formatted
+//! with a time zone abbreviation rather than a UTC offset.
+//! Abbreviations are interpreted the way the server interprets them
+//! with the default `timezone_abbreviations` setting, so `IST` is
+//! Israel Standard Time even if `TimeZone` is `Asia/Kolkata`.
+//! Abbreviations whose offset depends on the date, and abbreviations
+//! not in that set, such as `LMT`, cannot be interpreted.
+//! Use the ISO style or binary format to avoid this.
+
+use super::{
+    Date,
+    DateOrder,
+    DateStyle,
+    Interval,
+    IntervalStyle,
+    SessionParameters,
+    Time,
+    TimeTz,
+    Timestamp,
+    TimestampTz,
+};
+
+/// The number of microseconds in a second.
+const MICRO: i128 = 1_000_000;
+
+/// Parse a `date` value.
+pub (super) fn parse_date(sp: &SessionParameters, s: &str) -> Option<Date>
+{
+    match s {
+        "infinity" => return Some(Date::INFINITY),
+        "-infinity" => return Some(Date::NEG_INFINITY),
+        _ => (),
+    }
+    let (s, bc) = strip_bc(s);
+    let (year, month, day) = parse_ymd(sp, s)?;
+    Date::from_ymd(era_year(year, bc)?, month, day)
+}
+
+/// Parse a `time` value, which is formatted the same in every style.
+pub (super) fn parse_time(s: &str) -> Option<Time>
+{
+    Time::from_microseconds(parse_hms(s)?.try_into().ok()?)
+}
+
+/// Parse a `timetz` value, which is formatted the same in every style.
+pub (super) fn parse_timetz(s: &str) -> Option<TimeTz>
+{
+    let (time, offset) = split_offset(s);
+    TimeTz::new(parse_time(time)?, parse_offset(offset?)?)
+}
+
+/// Parse a `timestamp` value.
+pub (super) fn parse_timestamp(sp: &SessionParameters, s: &str)
+    -> Option<Timestamp>
+{
+    match s {
+        "infinity" => return Some(Timestamp::INFINITY),
+        "-infinity" => return Some(Timestamp::NEG_INFINITY),
+        _ => (),
+    }
+    match parse_local_timestamp(sp, s)? {
+        (timestamp, None) => Some(timestamp),
+        (_, Some(_)) => None,
+    }
+}
+
+/// Parse a `timestamptz` value.
+pub (super) fn parse_timestamptz(sp: &SessionParameters, s: &str)
+    -> Option<TimestampTz>
+{
+    match s {
+        "infinity" => return Some(TimestampTz::INFINITY),
+        "-infinity" => return Some(TimestampTz::NEG_INFINITY),
+        _ => (),
+    }
+    let (local, zone) = parse_local_timestamp(sp, s)?;
+    let utc_offset = parse_zone(zone?)?;
+    let microseconds = local.microseconds()
+        .checked_sub(i64::from(utc_offset) * 1_000_000)?;
+    let timestamp = Timestamp::from_microseconds(microseconds);
+    timestamp.is_finite().then(|| TimestampTz::from_utc(timestamp))
+}
+
+/// Parse an `interval` value.
+///
+/// The `postgres_verbose` and `iso_8601` styles are recognized
+/// by their first character regardless of the session parameters,
+/// so that the format produced by `Display` is accepted as well.
+pub (super) fn parse_interval(sp: &SessionParameters, s: &str)
+    -> Option<Interval>
+{
+    let [months, days, microseconds] = match sp.interval_style() {
+        _ if s.starts_with('@') => parse_interval_verbose(s)?,
+        _ if s.starts_with('P') => parse_interval_iso_8601(s)?,
+        IntervalStyle::SqlStandard => parse_interval_sql_standard(s)?,
+        _ => parse_interval_postgres(s)?,
+    };
+    Some(Interval{
+        months: months.try_into().ok()?,
+        days: days.try_into().ok()?,
+        microseconds: microseconds.try_into().ok()?,
+    })
+}
+
+/// Remove the ` BC` suffix, which applies to the entire value.
+fn strip_bc(s: &str) -> (&str, bool)
+{
+    match s.strip_suffix(" BC") {
+        Some(s) => (s, true),
+        None => (s, false),
+    }
+}
+
+/// Convert a year before or after Christ to an astronomical year.
+fn era_year(year: u32, bc: bool) -> Option<i32>
+{
+    let year = i32::try_from(year).ok()?;
+    match bc {
+        true if year == 0 => None,
+        true => Some(1 - year),
+        false => Some(year),
+    }
+}
+
+/// Parse the date of a date value, or of a timestamp in any style
+/// but Postgres, returning the year, month, and day.
+///
+/// The Postgres style formats dates the same as the SQL style,
+/// but with dashes instead of slashes.
+fn parse_ymd(sp: &SessionParameters, s: &str) -> Option<(u32, u32, u32)>
+{
+    let separator = match sp.date_style() {
+        DateStyle::Iso | DateStyle::Postgres => '-',
+        DateStyle::Sql => '/',
+        DateStyle::German => '.',
+    };
+    let mut fields = s.splitn(3, separator);
+    let a = digits(fields.next()?)?;
+    let b = digits(fields.next()?)?;
+    let c = digits(fields.next()?)?;
+    match sp.date_style() {
+        DateStyle::Iso => Some((a, b, c)),
+        DateStyle::German => Some((c, b, a)),
+        DateStyle::Sql | DateStyle::Postgres
+            if sp.date_order() == DateOrder::Dmy => Some((c, b, a)),
+        DateStyle::Sql | DateStyle::Postgres => Some((c, a, b)),
+    }
+}
+
+/// Parse a timestamp up to its UTC offset or time zone abbreviation,
+/// which is returned separately if present.
+fn parse_local_timestamp<'a>(sp: &SessionParameters, s: &'a str)
+    -> Option<(Timestamp, Option<&'a str>)>
+{
+    let (s, bc) = strip_bc(s);
+    let mut words = s.split(' ');
+    let ((year, month, day), time, zone) = match sp.date_style() {
+        DateStyle::Iso => {
+            let date = parse_ymd(sp, words.next()?)?;
+            let (time, offset) = split_offset(words.next()?);
+            (date, time, offset)
+        },
+        DateStyle::Sql | DateStyle::German => {
+            let date = parse_ymd(sp, words.next()?)?;
+            (date, words.next()?, words.next())
+        },
+        DateStyle::Postgres => {
+            // Fri Feb 09 07:08:09 2024 PST, or Fri 09 Feb ... with DMY.
+            let _weekday = words.next()?;
+            let (a, b) = (words.next()?, words.next()?);
+            let (month, day) = match (month_number(a), month_number(b)) {
+                (Some(month), None) => (month, b),
+                (None, Some(month)) => (month, a),
+                _ => return None,
+            };
+            let time = words.next()?;
+            let year = digits(words.next()?)?;
+            ((year, month, digits(day)?), time, words.next())
+        },
+    };
+    if words.next().is_some() {
+        return None;
+    }
+    let date = Date::from_ymd(era_year(year, bc)?, month, day)?;
+    Some((Timestamp::new(date, parse_time(time)?)?, zone))
+}
+
+/// The number of a month given its abbreviated name.
+fn month_number(s: &str) -> Option<u32>
+{
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun",
+        "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    (1 ..).zip(MONTHS).find(|&(_, name)| name == s).map(|(number, _)| number)
+}
+
+/// Split a time into the time and the UTC offset that follows it.
+fn split_offset(s: &str) -> (&str, Option<&str>)
+{
+    match s.find(['+', '-']) {
+        Some(i) => (&s[.. i], Some(&s[i ..])),
+        None => (s, None),
+    }
+}
+
+/// Parse a UTC offset or time zone abbreviation,
+/// returning the UTC offset in seconds east of UTC.
+fn parse_zone(s: &str) -> Option<i32>
+{
+    if s.starts_with(['+', '-']) {
+        return parse_offset(s);
+    }
+    let s = s.to_ascii_uppercase();
+    TIMEZONE_ABBREVIATIONS
+        .binary_search_by(|&(abbreviation, _)| abbreviation.cmp(&s))
+        .ok()
+        .map(|i| TIMEZONE_ABBREVIATIONS[i].1)
+}
+
+/// Parse a UTC offset, such as `+05:30` or `+0530`,
+/// returning the UTC offset in seconds east of UTC.
+///
+/// The ISO style uses colons, whereas the other styles use
+/// the abbreviations from the time zone database,
+/// which for some time zones are numeric offsets without colons.
+fn parse_offset(s: &str) -> Option<i32>
+{
+    let (negative, s) = strip_sign(s)?;
+    let (hours, rest) = s.split_at_checked(2)?;
+    let rest = rest.strip_prefix(':').unwrap_or(rest);
+    let (minutes, rest) = rest.split_at_checked(2).unwrap_or(("00", rest));
+    let rest = rest.strip_prefix(':').unwrap_or(rest);
+    let (seconds, rest) = rest.split_at_checked(2).unwrap_or(("00", rest));
+    if !rest.is_empty() {
+        return None;
+    }
+    let offset = digits::<i32>(hours)? * 3600
+        + sexagesimal(minutes)? * 60
+        + sexagesimal(seconds)?;
+    Some(if negative { -offset } else { offset })
+}
+
+/// Parse hours, minutes, and seconds, such as `04:05:06.789`,
+/// returning the number of microseconds.
+///
+/// The number of hours is unbounded, as in intervals.
+fn parse_hms(s: &str) -> Option<i128>
+{
+    let mut fields = s.splitn(3, ':');
+    let hours = digits::<i128>(fields.next()?)?;
+    let minutes = sexagesimal(fields.next()?)?;
+    let seconds = fields.next()?;
+    let (seconds, fraction) = match seconds.split_once('.') {
+        Some((seconds, fraction)) => (seconds, parse_fraction(fraction)?),
+        None => (seconds, 0),
+    };
+    let seconds = sexagesimal(seconds)?;
+    hours.checked_mul(3600 * MICRO)?
+        .checked_add(i128::from(minutes * 60 + seconds) * MICRO + fraction)
+}
+
+/// Parse the fractional digits of a number of seconds,
+/// returning the number of microseconds.
+fn parse_fraction(s: &str) -> Option<i128>
+{
+    let value = digits::<i128>(s)?;
+    let scale = 6usize.checked_sub(s.len())?;
+    Some(value * 10i128.pow(u32::try_from(scale).ok()?))
+}
+
+/// Parse a number with an optional sign and an optional fraction,
+/// returning the number in millionths.
+fn parse_decimal(s: &str) -> Option<i128>
+{
+    let (negative, s) = strip_sign(s).unwrap_or((false, s));
+    let (integer, fraction) = match s.split_once('.') {
+        Some((integer, fraction)) => (integer, parse_fraction(fraction)?),
+        None => (s, 0),
+    };
+    let value = digits::<i128>(integer)?
+        .checked_mul(MICRO)?
+        .checked_add(fraction)?;
+    Some(if negative { -value } else { value })
+}
+
+/// Remove a leading sign, returning whether it is a minus sign.
+fn strip_sign(s: &str) -> Option<(bool, &str)>
+{
+    match s.strip_prefix('-') {
+        Some(s) => Some((true, s)),
+        None => Some((false, s.strip_prefix('+')?)),
+    }
+}
+
+/// Parse a non-empty sequence of decimal digits.
+fn digits<T>(s: &str) -> Option<T>
+    where T: std::str::FromStr
+{
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Parse two decimal digits that form a number of minutes or seconds.
+fn sexagesimal(s: &str) -> Option<i32>
+{
+    let value = digits(s).filter(|&value| value < 60)?;
+    (s.len() == 2).then_some(value)
+}
+
+/// A unit of an interval in text format.
+#[derive(Clone, Copy)]
+enum Unit
+{
+    Years,
+    Months,
+    Days,
+    Hours,
+    Minutes,
+    Seconds,
+}
+
+impl Unit
+{
+    /// The unit with the given name in the Postgres styles.
+    fn from_name(name: &str) -> Option<Self>
+    {
+        match name {
+            "year" | "years" => Some(Self::Years),
+            "mon" | "mons" => Some(Self::Months),
+            "day" | "days" => Some(Self::Days),
+            "hour" | "hours" => Some(Self::Hours),
+            "min" | "mins" => Some(Self::Minutes),
+            "sec" | "secs" => Some(Self::Seconds),
+            _ => None,
+        }
+    }
+}
+
+/// Add a number of units, in millionths, to the months,
+/// days, and microseconds of an interval.
+///
+/// Fractions are permitted only for units smaller than a day.
+/// Each field is kept within the range of `i64`,
+/// so that it can be negated and added to without overflow.
+fn add(fields: &mut [i128; 3], unit: Unit, value: i128) -> Option<()>
+{
+    let whole = (value % MICRO == 0).then_some(value / MICRO);
+    let (index, amount) = match unit {
+        Unit::Years => (0, whole?.checked_mul(12)?),
+        Unit::Months => (0, whole?),
+        Unit::Days => (1, whole?),
+        Unit::Hours => (2, value.checked_mul(3600)?),
+        Unit::Minutes => (2, value.checked_mul(60)?),
+        Unit::Seconds => (2, value),
+    };
+    let sum = i64::try_from(fields[index].checked_add(amount)?).ok()?;
+    fields[index] = i128::from(sum);
+    Some(())
+}
+
+/// Parse an interval in the `postgres` style,
+/// such as `-1 years -2 mons +3 days -04:05:06`.
+fn parse_interval_postgres(s: &str) -> Option<[i128; 3]>
+{
+    let mut fields = [0; 3];
+    let mut words = s.split(' ');
+    while let Some(word) = words.next() {
+        if word.contains(':') {
+            let (negative, hms) = strip_sign(word).unwrap_or((false, word));
+            let value = parse_hms(hms)?;
+            let value = if negative { -value } else { value };
+            add(&mut fields, Unit::Seconds, value)?;
+        } else {
+            let value = parse_decimal(word)?;
+            add(&mut fields, Unit::from_name(words.next()?)?, value)?;
+        }
+    }
+    Some(fields)
+}
+
+/// Parse an interval in the `postgres_verbose` style,
+/// such as `@ 1 year 2 mons -3 days 4 hours 5 mins 6 secs ago`.
+fn parse_interval_verbose(s: &str) -> Option<[i128; 3]>
+{
+    let s = s.strip_prefix("@ ")?;
+    if s == "0" {
+        return Some([0; 3]);
+    }
+    let (s, ago) = match s.strip_suffix(" ago") {
+        Some(s) => (s, true),
+        None => (s, false),
+    };
+    if s.contains(':') {
+        return None;
+    }
+    let fields = parse_interval_postgres(s)?;
+    Some(if ago { fields.map(|field| -field) } else { fields })
+}
+
+/// Parse an interval in the `sql_standard` style,
+/// such as `-1-2 +3 -4:05:06`.
+///
+/// The year-month, day, and time fields each appear at most once,
+/// in that order. If the fields have different signs, each field has
+/// an explicit sign. Otherwise, a minus sign on the first field
+/// applies to all fields.
+fn parse_interval_sql_standard(s: &str) -> Option<[i128; 3]>
+{
+    if s == "0" {
+        return Some([0; 3]);
+    }
+    let negate_all = s.starts_with('-')
+        && !s.split(' ').skip(1).any(|word| word.starts_with(['+', '-']));
+    let mut fields = [0; 3];
+    let mut next_index = 0;
+    for word in s.split(' ') {
+        let (negative, word) = strip_sign(word).unwrap_or((false, word));
+        let sign = if negative || negate_all { -1 } else { 1 };
+        let index =
+            if word.contains(':') {
+                add(&mut fields, Unit::Seconds, sign * parse_hms(word)?)?;
+                2
+            } else if let Some((years, months)) = word.split_once('-') {
+                let months = digits::<i128>(months).filter(|&m| m < 12)?;
+                let years = digits::<i128>(years)?.checked_mul(MICRO)?;
+                add(&mut fields, Unit::Years, sign * years)?;
+                add(&mut fields, Unit::Months, sign * months * MICRO)?;
+                0
+            } else {
+                let days = digits::<i128>(word)?.checked_mul(MICRO)?;
+                add(&mut fields, Unit::Days, sign * days)?;
+                1
+            };
+        if index < next_index {
+            return None;
+        }
+        next_index = index + 1;
+    }
+    // A day field is always followed by a time field,
+    // as a lone number would be a number of seconds.
+    (next_index != 2).then_some(fields)
+}
+
+/// Parse an interval in the `iso_8601` style,
+/// such as `P1Y2M-3DT4H5M6.789S`.
+fn parse_interval_iso_8601(s: &str) -> Option<[i128; 3]>
+{
+    let s = s.strip_prefix('P')?;
+    let (date, time) = match s.split_once('T') {
+        Some((date, time)) if !time.is_empty() => (date, time),
+        Some(_) => return None,
+        None if !s.is_empty() => (s, ""),
+        None => return None,
+    };
+    let mut fields = [0; 3];
+    let date_units =
+        [('Y', Unit::Years), ('M', Unit::Months), ('D', Unit::Days)];
+    let time_units =
+        [('H', Unit::Hours), ('M', Unit::Minutes), ('S', Unit::Seconds)];
+    parse_designators(&mut fields, date, &date_units)?;
+    parse_designators(&mut fields, time, &time_units)?;
+    Some(fields)
+}
+
+/// Parse numbers followed by designators, such as `1Y2M`,
+/// where the designators appear in the given order.
+fn parse_designators(
+    fields: &mut [i128; 3],
+    mut s: &str,
+    units: &[(char, Unit)],
+) -> Option<()>
+{
+    let mut units = units.iter();
+    while !s.is_empty() {
+        let end = s.find(|c: char| c.is_ascii_alphabetic())?;
+        let designator = s[end ..].chars().next()?;
+        let &(_, unit) = units.find(|&&(d, _)| d == designator)?;
+        add(fields, unit, parse_decimal(&s[.. end])?)?;
+        s = &s[end + 1 ..];
+    }
+    Some(())
+}
+
+/// Time zone abbreviations with a fixed UTC offset in seconds east of UTC,
+/// sorted by abbreviation.
+///
+/// These are the abbreviations in the `Default` set of the server,
+/// which is the default value of `timezone_abbreviations`.
+/// Abbreviations that the server resolves using the time zone database,
+/// because their UTC offset changed over time, are not included.
+const TIMEZONE_ABBREVIATIONS: &[(&str, i32)] = &[
+    ("ACDT", 37800),
+    ("ACSST", 37800),
+    ("ACST", 34200),
+    ("ACT", -18000),
+    ("ACWST", 31500),
+    ("ADT", -10800),
+    ("AEDT", 39600),
+    ("AESST", 39600),
+    ("AEST", 36000),
+    ("AFT", 16200),
+    ("AKDT", -28800),
+    ("AKST", -32400),
+    ("ALMST", 25200),
+    ("ALMT", 21600),
+    ("AMT", -14400),
+    ("AST", -14400),
+    ("AWSST", 32400),
+    ("AWST", 28800),
+    ("AZOST", 0),
+    ("AZOT", -3600),
+    ("BDST", 7200),
+    ("BDT", 21600),
+    ("BNT", 28800),
+    ("BORT", 28800),
+    ("BOT", -14400),
+    ("BRA", -10800),
+    ("BRST", -7200),
+    ("BRT", -10800),
+    ("BST", 3600),
+    ("BTT", 21600),
+    ("CADT", 37800),
+    ("CAST", 34200),
+    ("CCT", 28800),
+    ("CDT", -18000),
+    ("CEST", 7200),
+    ("CET", 3600),
+    ("CETDST", 7200),
+    ("CHADT", 49500),
+    ("CHAST", 45900),
+    ("CHUT", 36000),
+    ("CLST", -10800),
+    ("COT", -18000),
+    ("CST", -21600),
+    ("CXT", 25200),
+    ("DDUT", 36000),
+    ("EAT", 10800),
+    ("EDT", -14400),
+    ("EEST", 10800),
+    ("EET", 7200),
+    ("EETDST", 10800),
+    ("EGST", 0),
+    ("EGT", -3600),
+    ("EST", -18000),
+    ("FET", 10800),
+    ("FJST", 46800),
+    ("FJT", 43200),
+    ("FNST", -3600),
+    ("FNT", -7200),
+    ("GALT", -21600),
+    ("GAMT", -32400),
+    ("GFT", -10800),
+    ("GILT", 43200),
+    ("GMT", 0),
+    ("HKT", 28800),
+    ("HST", -36000),
+    ("ICT", 25200),
+    ("IDT", 10800),
+    ("IRT", 12600),
+    ("IST", 7200),
+    ("JAYT", 32400),
+    ("JST", 32400),
+    ("KDT", 36000),
+    ("KGST", 21600),
+    ("KST", 32400),
+    ("LHST", 37800),
+    ("LIGT", 36000),
+    ("MART", -34200),
+    ("MDT", -21600),
+    ("MEST", 7200),
+    ("MESZ", 7200),
+    ("MET", 3600),
+    ("METDST", 7200),
+    ("MEZ", 3600),
+    ("MHT", 43200),
+    ("MMT", 23400),
+    ("MPT", 36000),
+    ("MSD", 14400),
+    ("MST", -25200),
+    ("MUST", 18000),
+    ("MUT", 14400),
+    ("MVT", 18000),
+    ("MYT", 28800),
+    ("NDT", -9000),
+    ("NFT", -12600),
+    ("NPT", 20700),
+    ("NST", -12600),
+    ("NZDT", 46800),
+    ("NZST", 43200),
+    ("NZT", 43200),
+    ("PDT", -25200),
+    ("PET", -18000),
+    ("PGT", 36000),
+    ("PHT", 28800),
+    ("PKST", 21600),
+    ("PKT", 18000),
+    ("PMDT", -7200),
+    ("PMST", -10800),
+    ("PONT", 39600),
+    ("PST", -28800),
+    ("PWT", 32400),
+    ("PYST", -10800),
+    ("RET", 14400),
+    ("SADT", 37800),
+    ("SAST", 7200),
+    ("SCT", 14400),
+    ("TAHT", -36000),
+    ("TFT", 18000),
+    ("TJT", 18000),
+    ("TOT", 46800),
+    ("TRUT", 36000),
+    ("TVT", 43200),
+    ("UCT", 0),
+    ("ULAST", 32400),
+    ("UT", 0),
+    ("UTC", 0),
+    ("UYST", -7200),
+    ("UYT", -10800),
+    ("UZST", 21600),
+    ("UZT", 18000),
+    ("VUT", 39600),
+    ("WADT", 28800),
+    ("WAKT", 43200),
+    ("WAST", 25200),
+    ("WAT", 3600),
+    ("WDT", 32400),
+    ("WET", 0),
+    ("WETDST", 3600),
+    ("WFT", 43200),
+    ("WGST", -7200),
+    ("WGT", -10800),
+    ("XJT", 21600),
+    ("YAPT", 36000),
+    ("YEKST", 21600),
+    ("Z", 0),
+    ("ZULU", 0),
+];
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn session_parameters(date_style: &str, interval_style: &str)
+        -> SessionParameters
+    {
+        let mut session_parameters = SessionParameters::default();
+        session_parameters.set(b"DateStyle", date_style.as_bytes());
+        session_parameters.set(b"IntervalStyle", interval_style.as_bytes());
+        session_parameters
+    }
+
+    fn timestamp(y: i32, m: u32, d: u32, time: (u32, u32, u32, u32))
+        -> Timestamp
+    {
+        let date = Date::from_ymd(y, m, d).unwrap();
+        let time = Time::from_hms_micro(time.0, time.1, time.2, time.3);
+        Timestamp::new(date, time.unwrap()).unwrap()
+    }
+
+    const SAMPLES: [(&str, &str, &str, &str); 8] = [
+        ("ISO, MDY", "2024-02-09", "0044-03-15 BC",
+         "2024-07-09 00:08:09.123456-07"),
+        ("ISO, DMY", "2024-02-09", "0044-03-15 BC",
+         "2024-07-09 12:38:09.123456+05:30"),
+        ("SQL, MDY", "02/09/2024", "03/15/0044 BC",
+         "07/09/2024 00:08:09.123456 PDT"),
+        ("SQL, DMY", "09/02/2024", "15/03/0044 BC",
+         "09/07/2024 04:08:09.123456 -03"),
+        ("Postgres, MDY", "02-09-2024", "03-15-0044 BC",
+         "Tue Jul 09 12:53:09.123456 2024 +0545"),
+        ("Postgres, DMY", "09-02-2024", "15-03-0044 BC",
+         "Tue 09 Jul 07:08:09.123456 2024 UTC"),
+        ("Postgres, YMD", "02-09-2024", "03-15-0044 BC",
+         "Tue Jul 09 09:08:09.123456 2024 IST"),
+        ("German, MDY", "09.02.2024", "15.03.0044 BC",
+         "09.07.2024 00:08:09.123456 PDT"),
+    ];
+
+    #[test]
+    fn dates()
+    {
+        let expected = Date::from_ymd(2024, 2, 9);
+        let expected_bc = Date::from_ymd(-43, 3, 15);
+        let timestamptz = timestamp(2024, 7, 9, (7, 8, 9, 123_456));
+        for (date_style, date, date_bc, other) in SAMPLES {
+            let sp = session_parameters(date_style, "postgres");
+            assert_eq!(parse_date(&sp, date), expected, "{date}");
+            assert_eq!(parse_date(&sp, date_bc), expected_bc, "{date_bc}");
+            assert_eq!(parse_date(&sp, "-infinity"), Some(Date::NEG_INFINITY));
+            assert_eq!(parse_timestamptz(&sp, other),
+                       Some(TimestampTz::from_utc(timestamptz)), "{other}");
+            assert_eq!(parse_timestamp(&sp, other), None, "{other}");
+        }
+    }
+
+    #[test]
+    fn timestamps()
+    {
+        let cases = [
+            ("ISO, MDY", "2024-02-09 07:08:09.5"),
+            ("SQL, MDY", "02/09/2024 07:08:09.5"),
+            ("SQL, DMY", "09/02/2024 07:08:09.5"),
+            ("Postgres, MDY", "Fri Feb 09 07:08:09.5 2024"),
+            ("Postgres, DMY", "Fri 09 Feb 07:08:09.5 2024"),
+            ("German, DMY", "09.02.2024 07:08:09.5"),
+        ];
+        let expected = timestamp(2024, 2, 9, (7, 8, 9, 500_000));
+        for (date_style, text) in cases {
+            let sp = session_parameters(date_style, "postgres");
+            assert_eq!(parse_timestamp(&sp, text), Some(expected), "{text}");
+        }
+
+        let sp = session_parameters("ISO, MDY", "postgres");
+        let bc = timestamp(-43, 3, 15, (7, 8, 9, 0));
+        assert_eq!(parse_timestamptz(&sp, "0044-03-14 23:15:11-07:52:58 BC"),
+                   Some(TimestampTz::from_utc(bc)));
+        assert_eq!(parse_timestamp(&sp, "0044-03-15 07:08:09 BC"), Some(bc));
+        assert_eq!(parse_timestamp(&sp, "2024-02-09 07:08:09 BC extra"), None);
+        assert_eq!(parse_timestamptz(&sp, "infinity"),
+                   Some(TimestampTz::INFINITY));
+
+        let sp = session_parameters("SQL, MDY", "postgres");
+        assert_eq!(parse_timestamptz(&sp, "03/14/0044 23:15:11 LMT BC"), None);
+        assert_eq!(parse_timestamptz(&sp, "03/14/0044 23:15:11 BC"), None);
+
+        assert_eq!(parse_time("24:00:00"), Time::from_hms_micro(24, 0, 0, 0));
+        assert_eq!(parse_time("24:00:01"), None);
+        assert_eq!(parse_time("1:02:03.0000001"), None);
+        assert_eq!(parse_timetz("01:02:03.4-05:30"),
+                   TimeTz::new(Time::from_hms_micro(1, 2, 3, 400_000).unwrap(),
+                               -19800));
+        assert_eq!(parse_timetz("01:02:03"), None);
+    }
+
+    #[test]
+    fn intervals()
+    {
+        // The same values formatted in each style by the server.
+        let cases: [(i32, i32, i64, [&str; 4]); 10] = [
+            (0, 0, 0,
+             ["00:00:00", "@ 0", "0", "PT0S"]),
+            (14, 3, 14_706_789_000,
+             ["1 year 2 mons 3 days 04:05:06.789",
+              "@ 1 year 2 mons 3 days 4 hours 5 mins 6.789 secs",
+              "+1-2 +3 +4:05:06.789",
+              "P1Y2M3DT4H5M6.789S"]),
+            (-14, 3, -14_706_000_000,
+             ["-1 years -2 mons +3 days -04:05:06",
+              "@ 1 year 2 mons -3 days 4 hours 5 mins 6 secs ago",
+              "-1-2 +3 -4:05:06",
+              "P-1Y-2M3DT-4H-5M-6S"]),
+            (0, 1, -1_000_000,
+             ["1 day -00:00:01", "@ 1 day -1 sec",
+              "+0-0 +1 -0:00:01", "P1DT-1S"]),
+            (0, 0, -500_000,
+             ["-00:00:00.5", "@ 0.5 secs ago", "-0:00:00.5", "PT-0.5S"]),
+            (0, 0, 360_000_000_000,
+             ["100:00:00", "@ 100 hours", "100:00:00", "PT100H"]),
+            (1, -1, 0,
+             ["1 mon -1 days", "@ 1 mon -1 days",
+              "+0-1 -1 +0:00:00", "P1M-1D"]),
+            (-1, 0, 0,
+             ["-1 mons", "@ 1 mon ago", "-0-1", "P-1M"]),
+            (-14, -3, -14_706_000_000,
+             ["-1 years -2 mons -3 days -04:05:06",
+              "@ 1 year 2 mons 3 days 4 hours 5 mins 6 secs ago",
+              "-1-2 -3 -4:05:06",
+              "P-1Y-2M-3DT-4H-5M-6S"]),
+            (0, -3, 0,
+             ["-3 days", "@ 3 days ago", "-3 0:00:00", "P-3D"]),
+        ];
+        let styles =
+            ["postgres", "postgres_verbose", "sql_standard", "iso_8601"];
+        for (months, days, microseconds, texts) in cases {
+            let expected = Interval{months, days, microseconds};
+            for (style, text) in styles.into_iter().zip(texts) {
+                let sp = session_parameters("ISO, MDY", style);
+                assert_eq!(parse_interval(&sp, text), Some(expected),
+                           "{style} {text}");
+            }
+        }
+
+        let extreme = Interval{
+            months: i32::MAX,
+            days: i32::MAX,
+            microseconds: i64::MAX,
+        };
+        let sp = session_parameters("ISO, MDY", "postgres");
+        assert_eq!(parse_interval(&sp, "178956970 years 7 mons \
+                                        2147483647 days \
+                                        2562047788:00:54.775807"),
+                   Some(extreme));
+        assert_eq!(parse_interval(&sp, &extreme.to_string()), Some(extreme));
+        assert_eq!(parse_interval(&sp, "178956970 years 8 mons"), None);
+        assert_eq!(parse_interval(&sp, "1.5 days"), None);
+        assert_eq!(parse_interval(&sp, ""), None);
+
+        let sp = session_parameters("ISO, MDY", "sql_standard");
+        assert_eq!(parse_interval(&sp, "3"), None);
+        assert_eq!(parse_interval(&sp, "0:00:00 3"), None);
+        assert_eq!(parse_interval(&sp, "1-12"), None);
+    }
+
+    #[test]
+    fn session_parameters_set()
+    {
+        let sp = session_parameters("SQL, DMY", "iso_8601");
+        assert_eq!(sp.date_style(), DateStyle::Sql);
+        assert_eq!(sp.date_order(), DateOrder::Dmy);
+        assert_eq!(sp.interval_style(), IntervalStyle::Iso8601);
+        let sp = session_parameters("bogus", "bogus");
+        assert_eq!(sp, SessionParameters::DEFAULT);
+    }
+
+    #[test]
+    fn fuzz()
+    {
+        let mut inputs = vec![];
+        for (_, date, date_bc, other) in SAMPLES {
+            inputs.extend([date, date_bc, other]);
+        }
+        inputs.extend(["@ 1 year 2 mons -3 days 4 hours 5 mins 6 secs ago",
+                       "+1-2 +3 +4:05:06.789", "P1Y2M3DT4H5M6.789S",
+                       "-1 years -2 mons +3 days -04:05:06",
+                       "01:02:03.4-05:30", "Fri 09 Feb 07:08:09.5 2024"]);
+        let date_styles = ["ISO, MDY", "SQL, DMY", "Postgres, MDY", "German"];
+        let interval_styles = ["postgres", "sql_standard"];
+        for input in inputs {
+            for i in 0 .. input.len() {
+                for text in [&input[.. i], &input[i ..]] {
+                    for date_style in date_styles {
+                        for interval_style in interval_styles {
+                            let sp =
+                                session_parameters(date_style, interval_style);
+                            parse_date(&sp, text);
+                            parse_time(text);
+                            parse_timetz(text);
+                            parse_timestamp(&sp, text);
+                            parse_timestamptz(&sp, text);
+                            parse_interval(&sp, text);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Codecs for date and time types using the **time** crate.
+//!
+//! Sub-microsecond precision is truncated when encoding,
+//! and values that the time crate cannot represent, such as
+//! the infinities, result in an error when decoding.
+
+use {
+    crate::Result,
+    super::{
+        Context,
+        Date,
+        FromSql,
+        IsNull,
+        Time,
+        Timestamp,
+        TimestampTz,
+        ToSql,
+        Type,
+        datetime::delegate_codec,
+        out_of_range,
+    },
+    time::{OffsetDateTime, PrimitiveDateTime},
+};
+
+/// The date 2000-01-01, as a Julian day number.
+const POSTGRES_EPOCH_JULIAN_DAY: i32 = 2_451_545;
+
+/// The time 2000-01-01 00:00:00 UTC, in microseconds since
+/// 1970-01-01 00:00:00 UTC.
+const POSTGRES_EPOCH_UNIX_MICROSECONDS: i128 = 946_684_800_000_000;
+
+fn from_date(date: &time::Date) -> Option<Date>
+{
+    let days = date.to_julian_day().checked_sub(POSTGRES_EPOCH_JULIAN_DAY)?;
+    Some(Date::from_days(days)).filter(|date| date.is_finite())
+}
+
+fn to_date(date: Date) -> Option<time::Date>
+{
+    if !date.is_finite() {
+        return None;
+    }
+    let julian_day = date.days().checked_add(POSTGRES_EPOCH_JULIAN_DAY)?;
+    time::Date::from_julian_day(julian_day).ok()
+}
+
+fn from_time(time: &time::Time) -> Option<Time>
+{
+    let (hour, minute, second, micro) = time.as_hms_micro();
+    Time::from_hms_micro(hour.into(), minute.into(), second.into(), micro)
+}
+
+fn to_time(time: Time) -> Option<time::Time>
+{
+    let (hour, minute, second, micro) = time.to_hms_micro();
+    let (hour, minute, second) = (
+        u8::try_from(hour).ok()?,
+        u8::try_from(minute).ok()?,
+        u8::try_from(second).ok()?,
+    );
+    time::Time::from_hms_micro(hour, minute, second, micro).ok()
+}
+
+fn from_primitive_date_time(date_time: &PrimitiveDateTime)
+    -> Option<Timestamp>
+{
+    let date = from_date(&date_time.date())?;
+    let time = from_time(&date_time.time())?;
+    Timestamp::new(date, time)
+}
+
+fn to_primitive_date_time(timestamp: Timestamp) -> Option<PrimitiveDateTime>
+{
+    let (date, time) = timestamp.to_date_time()?;
+    Some(PrimitiveDateTime::new(to_date(date)?, to_time(time)?))
+}
+
+fn from_offset_date_time(date_time: &OffsetDateTime) -> Option<TimestampTz>
+{
+    let microseconds = date_time.unix_timestamp_nanos().div_euclid(1000)
+        - POSTGRES_EPOCH_UNIX_MICROSECONDS;
+    let microseconds = i64::try_from(microseconds).ok()?;
+    Some(TimestampTz::from_microseconds(microseconds))
+        .filter(|timestamp| timestamp.is_finite())
+}
+
+fn to_offset_date_time(timestamp: TimestampTz) -> Option<OffsetDateTime>
+{
+    if !timestamp.is_finite() {
+        return None;
+    }
+    let microseconds = i128::from(timestamp.microseconds())
+        + POSTGRES_EPOCH_UNIX_MICROSECONDS;
+    OffsetDateTime::from_unix_timestamp_nanos(microseconds * 1000).ok()
+}
+
+delegate_codec!(time::Date, Date, from_date, to_date);
+delegate_codec!(time::Time, Time, from_time, to_time);
+delegate_codec!(
+    PrimitiveDateTime, Timestamp,
+    from_primitive_date_time, to_primitive_date_time
+);
+delegate_codec!(
+    OffsetDateTime, TimestampTz,
+    from_offset_date_time, to_offset_date_time
+);
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::{protocol::FormatCode, types::tests::round_trip},
+        super::*,
+        time::{Month, UtcOffset},
+    };
+
+    #[test]
+    fn conversions()
+    {
+        let cx = Context::new(&Type::DATE, FormatCode::Binary);
+        let date = |days| {
+            let mut buf = Vec::new();
+            Date::from_days(days).to_sql(&cx, &mut buf).unwrap();
+            time::Date::from_sql(&cx, &buf).ok()
+        };
+        let calendar_date = |year, month, day| {
+            time::Date::from_calendar_date(year, month, day).ok()
+        };
+        assert_eq!(date(0), calendar_date(2000, Month::January, 1));
+        assert_eq!(date(-1), calendar_date(1999, Month::December, 31));
+        assert_eq!(date(i32::MIN), None);
+        assert_eq!(date(100_000_000), None);
+
+        let date_time = time::Date::from_calendar_date(1, Month::March, 1)
+            .unwrap()
+            .with_hms_micro(12, 34, 56, 789_012).unwrap();
+        let offset = UtcOffset::from_hms(-5, -30, 0).unwrap();
+        let binary = FormatCode::Binary;
+        round_trip(&Type::TIMESTAMP, binary, &date_time);
+        round_trip(&Type::TIMESTAMPTZ, binary, &date_time.assume_utc());
+        round_trip(&Type::DATE, binary, &date_time.date());
+        round_trip(&Type::TIME, binary, &date_time.time());
+
+        // The offset is not preserved, but the point in time is.
+        let cx = Context::new(&Type::TIMESTAMPTZ, binary);
+        let mut buf = Vec::new();
+        date_time.assume_offset(offset).to_sql(&cx, &mut buf).unwrap();
+        let decoded = OffsetDateTime::from_sql(&cx, &buf).unwrap();
+        assert_eq!(decoded, date_time.assume_offset(offset));
+        assert_eq!(decoded.offset(), UtcOffset::UTC);
+    }
+}
@@ -0,0 +1,783 @@
+//! Codecs for `point`, `line`, `lseg`, `box`, `path`, `polygon`,
+//! and `circle`.
+//!
+//! Coordinates are `float8` values. In text format,
+//! infinities are spelled `Infinity` and `-Infinity`, as in PostgreSQL.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{
+        Context,
+        FromSql,
+        IsNull,
+        ToSql,
+        Type,
+        fixed_length,
+        invalid_value,
+        literal::is_space,
+        text_value,
+    },
+    std::{fmt, io::Write, str::FromStr},
+    thiserror::Error,
+};
+
+/// Value of type `point`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point
+{
+    #[allow(missing_docs)]
+    pub x: f64,
+    #[allow(missing_docs)]
+    pub y: f64,
+}
+
+/// Value of type `line`, which is the infinite line
+/// given by the equation _Ax_ + _By_ + _C_ = 0.
+///
+/// PostgreSQL requires that _A_ and _B_ are not both zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Line
+{
+    #[allow(missing_docs)]
+    pub a: f64,
+    #[allow(missing_docs)]
+    pub b: f64,
+    #[allow(missing_docs)]
+    pub c: f64,
+}
+
+/// Value of type `lseg`, which is a line segment.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LineSegment
+{
+    #[allow(missing_docs)]
+    pub start: Point,
+    #[allow(missing_docs)]
+    pub end: Point,
+}
+
+/// Value of type `box`, which is a rectangle
+/// given by its upper right and lower left corners.
+///
+/// PostgreSQL swaps coordinates as necessary,
+/// such that `high` is the upper right corner;
+/// [`Rect::new`] does the same.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rect
+{
+    /// The upper right corner.
+    pub high: Point,
+
+    /// The lower left corner.
+    pub low: Point,
+}
+
+/// Value of type `path`, which is a sequence of points
+/// connected by line segments.
+///
+/// A closed path also connects the last point to the first.
+/// PostgreSQL requires that a path has at least one point.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Path
+{
+    /// Whether the path is closed, rather than open.
+    pub closed: bool,
+
+    #[allow(missing_docs)]
+    pub points: Vec<Point>,
+}
+
+/// Value of type `polygon`, which is given by its vertices.
+///
+/// PostgreSQL requires that a polygon has at least one vertex.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Polygon(pub Vec<Point>);
+
+/// Value of type `circle`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Circle
+{
+    #[allow(missing_docs)]
+    pub center: Point,
+    #[allow(missing_docs)]
+    pub radius: f64,
+}
+
+/// Error returned when parsing a geometric value fails.
+#[derive(Clone, Debug, Error)]
+#[error("invalid geometric literal")]
+pub struct ParseGeometricError(());
+
+impl Point
+{
+    /// Create a point with the given coordinates.
+    pub const fn new(x: f64, y: f64) -> Self
+    {
+        Self{x, y}
+    }
+}
+
+impl Rect
+{
+    /// Create a box with the given opposite corners,
+    /// swapping coordinates as necessary, as PostgreSQL does.
+    pub fn new(a: Point, b: Point) -> Self
+    {
+        Self{
+            high: Point::new(a.x.max(b.x), a.y.max(b.y)),
+            low: Point::new(a.x.min(b.x), a.y.min(b.y)),
+        }
+    }
+}
+
+/// Format a coordinate the way PostgreSQL does.
+///
+/// Infinities are spelled the way PostgreSQL spells them,
+/// which is not the way Rust spells them.
+fn write_float(f: &mut fmt::Formatter, value: f64) -> fmt::Result
+{
+    match value {
+        f64::INFINITY => f.write_str("Infinity"),
+        f64::NEG_INFINITY => f.write_str("-Infinity"),
+        _ => write!(f, "{value}"),
+    }
+}
+
+fn write_points(f: &mut fmt::Formatter, points: &[Point]) -> fmt::Result
+{
+    for (i, point) in points.iter().enumerate() {
+        if i != 0 {
+            f.write_str(",")?;
+        }
+        write!(f, "{point}")?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Point
+{
+    /// Format the point like `(1,2)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        f.write_str("(")?;
+        write_float(f, self.x)?;
+        f.write_str(",")?;
+        write_float(f, self.y)?;
+        f.write_str(")")
+    }
+}
+
+impl fmt::Display for Line
+{
+    /// Format the line like `{1,-1,0}`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        f.write_str("{")?;
+        write_float(f, self.a)?;
+        f.write_str(",")?;
+        write_float(f, self.b)?;
+        f.write_str(",")?;
+        write_float(f, self.c)?;
+        f.write_str("}")
+    }
+}
+
+impl fmt::Display for LineSegment
+{
+    /// Format the line segment like `[(0,0),(1,1)]`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "[{},{}]", self.start, self.end)
+    }
+}
+
+impl fmt::Display for Rect
+{
+    /// Format the box like `(1,1),(0,0)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{},{}", self.high, self.low)
+    }
+}
+
+impl fmt::Display for Path
+{
+    /// Format the path like `((0,0),(1,1))` if it is closed,
+    /// or like `[(0,0),(1,1)]` if it is open.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        f.write_str(if self.closed { "(" } else { "[" })?;
+        write_points(f, &self.points)?;
+        f.write_str(if self.closed { ")" } else { "]" })
+    }
+}
+
+impl fmt::Display for Polygon
+{
+    /// Format the polygon like `((0,0),(1,1),(1,0))`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        f.write_str("(")?;
+        write_points(f, &self.0)?;
+        f.write_str(")")
+    }
+}
+
+impl fmt::Display for Circle
+{
+    /// Format the circle like `<(0,0),1>`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "<{},", self.center)?;
+        write_float(f, self.radius)?;
+        f.write_str(">")
+    }
+}
+
+/// Cursor over a geometric literal.
+///
+/// Only ASCII characters are examined, so positions
+/// at which the parser stops are always character boundaries.
+struct Parser<'a>
+{
+    s: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_>
+{
+    fn peek(&mut self) -> Option<u8>
+    {
+        while self.s.as_bytes().get(self.pos).copied().is_some_and(is_space) {
+            self.pos += 1;
+        }
+        self.s.as_bytes().get(self.pos).copied()
+    }
+
+    /// Consume the byte, skipping whitespace before it.
+    fn eat(&mut self, byte: u8) -> bool
+    {
+        let found = self.peek() == Some(byte);
+        self.pos += usize::from(found);
+        found
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()>
+    {
+        self.eat(byte).then_some(())
+    }
+
+    /// Whether the rest of the literal is whitespace.
+    fn at_end(&mut self) -> bool
+    {
+        self.peek().is_none()
+    }
+
+    /// Whether the byte after any whitespace after the next byte
+    /// is the given byte.
+    fn second_is(&self, byte: u8) -> bool
+    {
+        let mut p = Parser{s: self.s, pos: self.pos};
+        p.peek();
+        p.pos += 1;
+        p.peek() == Some(byte)
+    }
+
+    fn float(&mut self) -> Option<f64>
+    {
+        self.peek();
+        let start = self.pos;
+        let end = self.s.as_bytes()[start ..].iter()
+            .position(|&byte| {
+                is_space(byte) || b",()[]<>{}".contains(&byte)
+            })
+            .map_or(self.s.len(), |length| start + length);
+        self.pos = end;
+        self.s.get(start .. end)?.parse().ok()
+    }
+
+    /// Parse a point, such as `(1,2)` or `1,2`.
+    fn point(&mut self) -> Option<Point>
+    {
+        let parenthesized = self.eat(b'(');
+        let x = self.float()?;
+        self.expect(b',')?;
+        let y = self.float()?;
+        if parenthesized {
+            self.expect(b')')?;
+        }
+        Some(Point::new(x, y))
+    }
+
+    /// Parse points separated by commas,
+    /// optionally enclosed in parentheses or, if allowed, brackets,
+    /// as with `path_decode`.
+    ///
+    /// Returns the points and whether they were enclosed in brackets.
+    fn points(&mut self, count: Option<usize>, allow_brackets: bool)
+        -> Option<(Vec<Point>, bool)>
+    {
+        let bracketed = allow_brackets && self.eat(b'[');
+
+        // Parentheses enclose the points if they are followed by
+        // another parenthesis, as in `((1,2),(3,4))`, or if they are
+        // the only parentheses, as in `(1,2,3,4)`.
+        let parenthesized = !bracketed && self.peek() == Some(b'(') && (
+            self.second_is(b'(')
+                || !self.s[self.pos + 1 ..].contains('(')
+        );
+        self.pos += usize::from(parenthesized);
+
+        let mut points = Vec::new();
+        loop {
+            points.push(self.point()?);
+            if count == Some(points.len()) || !self.eat(b',') {
+                break;
+            }
+        }
+        if count.is_some_and(|count| count != points.len()) {
+            return None;
+        }
+
+        if bracketed {
+            self.expect(b']')?;
+        }
+        if parenthesized {
+            self.expect(b')')?;
+        }
+        Some((points, bracketed))
+    }
+
+    fn parse<T>(s: &str, f: impl FnOnce(&mut Parser) -> Option<T>)
+        -> std::result::Result<T, ParseGeometricError>
+    {
+        let mut p = Parser{s, pos: 0};
+        f(&mut p)
+            .filter(|_| p.at_end())
+            .ok_or(ParseGeometricError(()))
+    }
+}
+
+impl FromStr for Point
+{
+    type Err = ParseGeometricError;
+
+    /// Parse a point, such as `(1,2)` or `1,2`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        Parser::parse(s, |p| p.point())
+    }
+}
+
+impl FromStr for Line
+{
+    type Err = ParseGeometricError;
+
+    /// Parse a line given by its coefficients, such as `{1,-1,0}`.
+    ///
+    /// Unlike PostgreSQL, this does not accept
+    /// a line given by two points on it.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        Parser::parse(s, |p| {
+            p.expect(b'{')?;
+            let a = p.float()?;
+            p.expect(b',')?;
+            let b = p.float()?;
+            p.expect(b',')?;
+            let c = p.float()?;
+            p.expect(b'}')?;
+            Some(Self{a, b, c})
+        })
+    }
+}
+
+impl FromStr for LineSegment
+{
+    type Err = ParseGeometricError;
+
+    /// Parse a line segment, such as `[(0,0),(1,1)]`,
+    /// `((0,0),(1,1))`, `(0,0),(1,1)`, or `0,0,1,1`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        Parser::parse(s, |p| {
+            let (points, _) = p.points(Some(2), true)?;
+            Some(Self{start: points[0], end: points[1]})
+        })
+    }
+}
+
+impl FromStr for Rect
+{
+    type Err = ParseGeometricError;
+
+    /// Parse a box, such as `(1,1),(0,0)`, `((1,1),(0,0))`,
+    /// or `1,1,0,0`, swapping coordinates as necessary.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        Parser::parse(s, |p| {
+            let (points, _) = p.points(Some(2), false)?;
+            Some(Self::new(points[0], points[1]))
+        })
+    }
+}
+
+impl FromStr for Path
+{
+    type Err = ParseGeometricError;
+
+    /// Parse a path, which is open if enclosed in brackets,
+    /// such as `[(0,0),(1,1)]`, and closed otherwise,
+    /// such as `((0,0),(1,1))` or `0,0,1,1`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        Parser::parse(s, |p| {
+            let (points, bracketed) = p.points(None, true)?;
+            Some(Self{closed: !bracketed, points})
+        })
+    }
+}
+
+impl FromStr for Polygon
+{
+    type Err = ParseGeometricError;
+
+    /// Parse a polygon, such as `((0,0),(1,1),(1,0))` or `0,0,1,1,1,0`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        Parser::parse(s, |p| Some(Self(p.points(None, false)?.0)))
+    }
+}
+
+impl FromStr for Circle
+{
+    type Err = ParseGeometricError;
+
+    /// Parse a circle, such as `<(0,0),1>`, `((0,0),1)`,
+    /// `(0,0),1`, or `0,0,1`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        Parser::parse(s, |p| {
+            let close = if p.eat(b'<') {
+                Some(b'>')
+            } else if p.peek() == Some(b'(') && p.second_is(b'(') {
+                p.pos += 1;
+                Some(b')')
+            } else {
+                None
+            };
+            let center = p.point()?;
+            p.expect(b',')?;
+            let radius = p.float()?;
+            if let Some(close) = close {
+                p.expect(close)?;
+            }
+            Some(Self{center, radius})
+        })
+    }
+}
+
+fn write_floats(buf: &mut Vec<u8>, floats: &[f64])
+{
+    for float in floats {
+        buf.extend_from_slice(&float.to_be_bytes());
+    }
+}
+
+fn write_points_binary(buf: &mut Vec<u8>, points: &[Point]) -> Result<()>
+{
+    let count = i32::try_from(points.len())
+        .map_err(|_| crate::Error::Conversion("too many points".into()))?;
+    buf.extend_from_slice(&count.to_be_bytes());
+    for point in points {
+        write_floats(buf, &[point.x, point.y]);
+    }
+    Ok(())
+}
+
+fn read_floats<const N: usize>(cx: &Context, raw: &[u8]) -> Result<[f64; N]>
+{
+    if raw.len() != N * 8 {
+        return Err(invalid_value(cx, "malformed binary value"));
+    }
+    let mut floats = [0.0; N];
+    for (float, bytes) in floats.iter_mut().zip(raw.chunks_exact(8)) {
+        *float = f64::from_be_bytes(fixed_length(cx, bytes)?);
+    }
+    Ok(floats)
+}
+
+fn read_point(cx: &Context, raw: &[u8]) -> Result<Point>
+{
+    let [x, y] = read_floats(cx, raw)?;
+    Ok(Point::new(x, y))
+}
+
+/// Read the number of points and the points.
+fn read_points_binary(cx: &Context, raw: &[u8]) -> Result<Vec<Point>>
+{
+    let (count, raw) = raw.split_first_chunk::<4>()
+        .ok_or_else(|| invalid_value(cx, "malformed binary value"))?;
+    let count = usize::try_from(i32::from_be_bytes(*count)).ok()
+        .filter(|&count| Some(raw.len()) == count.checked_mul(16))
+        .ok_or_else(|| invalid_value(cx, "malformed binary value"))?;
+    let mut points = Vec::with_capacity(count);
+    for raw in raw.chunks_exact(16) {
+        points.push(read_point(cx, raw)?);
+    }
+    Ok(points)
+}
+
+/// Implement [`ToSql`] and [`FromSql`] for a geometric type.
+///
+/// The text format is what `Display` produces and `FromStr` parses.
+macro_rules! geometric_codec
+{
+    ($rust:ty, $pg:ident, $encode:expr, $decode:expr) => {
+        impl ToSql for $rust
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                *ty == Type::$pg
+            }
+
+            fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>)
+                -> Result<IsNull>
+            {
+                match cx.format_code() {
+                    FormatCode::Binary => $encode(self, buf)?,
+                    FormatCode::Text => write!(buf, "{self}")?,
+                }
+                Ok(IsNull::No)
+            }
+        }
+
+        impl<'a> FromSql<'a> for $rust
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                *ty == Type::$pg
+            }
+
+            fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+            {
+                match cx.format_code() {
+                    FormatCode::Binary => $decode(cx, raw),
+                    FormatCode::Text => text_value(cx, raw)?.parse()
+                        .map_err(|_| invalid_value(cx, "malformed text")),
+                }
+            }
+
+            fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+            {
+                <Self as FromSql>::from_sql(cx, raw)
+            }
+        }
+    };
+}
+
+geometric_codec!(
+    Point, POINT,
+    |value: &Point, buf: &mut Vec<u8>| -> Result<()> {
+        write_floats(buf, &[value.x, value.y]);
+        Ok(())
+    },
+    read_point
+);
+
+geometric_codec!(
+    Line, LINE,
+    |value: &Line, buf: &mut Vec<u8>| -> Result<()> {
+        write_floats(buf, &[value.a, value.b, value.c]);
+        Ok(())
+    },
+    |cx, raw| {
+        let [a, b, c] = read_floats(cx, raw)?;
+        Ok(Line{a, b, c})
+    }
+);
+
+geometric_codec!(
+    LineSegment, LSEG,
+    |value: &LineSegment, buf: &mut Vec<u8>| -> Result<()> {
+        let LineSegment{start, end} = value;
+        write_floats(buf, &[start.x, start.y, end.x, end.y]);
+        Ok(())
+    },
+    |cx, raw| {
+        let [x1, y1, x2, y2] = read_floats(cx, raw)?;
+        Ok(LineSegment{start: Point::new(x1, y1), end: Point::new(x2, y2)})
+    }
+);
+
+geometric_codec!(
+    Rect, BOX,
+    |value: &Rect, buf: &mut Vec<u8>| -> Result<()> {
+        let Rect{high, low} = value;
+        write_floats(buf, &[high.x, high.y, low.x, low.y]);
+        Ok(())
+    },
+    |cx, raw| {
+        let [x1, y1, x2, y2] = read_floats(cx, raw)?;
+        Ok(Rect::new(Point::new(x1, y1), Point::new(x2, y2)))
+    }
+);
+
+geometric_codec!(
+    Path, PATH,
+    |value: &Path, buf: &mut Vec<u8>| {
+        buf.push(u8::from(value.closed));
+        write_points_binary(buf, &value.points)
+    },
+    |cx, raw: &[u8]| {
+        let (&closed, raw) = raw.split_first()
+            .ok_or_else(|| invalid_value(cx, "malformed binary value"))?;
+        let points = read_points_binary(cx, raw)?;
+        Ok(Path{closed: closed != 0, points})
+    }
+);
+
+geometric_codec!(
+    Polygon, POLYGON,
+    |value: &Polygon, buf: &mut Vec<u8>| write_points_binary(buf, &value.0),
+    |cx, raw| read_points_binary(cx, raw).map(Polygon)
+);
+
+geometric_codec!(
+    Circle, CIRCLE,
+    |value: &Circle, buf: &mut Vec<u8>| -> Result<()> {
+        let Circle{center, radius} = value;
+        write_floats(buf, &[center.x, center.y, *radius]);
+        Ok(())
+    },
+    |cx, raw| {
+        let [x, y, radius] = read_floats(cx, raw)?;
+        Ok(Circle{center: Point::new(x, y), radius})
+    }
+);
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::tests::{fuzz_from_sql, round_trip},
+        super::*,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<Point>(&Type::POINT);
+        fuzz_from_sql::<Line>(&Type::LINE);
+        fuzz_from_sql::<LineSegment>(&Type::LSEG);
+        fuzz_from_sql::<Rect>(&Type::BOX);
+        fuzz_from_sql::<Path>(&Type::PATH);
+        fuzz_from_sql::<Polygon>(&Type::POLYGON);
+        fuzz_from_sql::<Circle>(&Type::CIRCLE);
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        let a = Point::new(1.5, -2.0);
+        let b = Point::new(f64::INFINITY, 0.1);
+        let c = Point::new(-0.0, f64::NEG_INFINITY);
+
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            round_trip(&Type::POINT, format_code, &a);
+            round_trip(&Type::POINT, format_code, &b);
+            round_trip(&Type::LINE, format_code,
+                       &Line{a: 1.0, b: -1.0, c: 1e-300});
+            round_trip(&Type::LSEG, format_code,
+                       &LineSegment{start: a, end: b});
+            round_trip(&Type::BOX, format_code, &Rect::new(a, c));
+            for closed in [false, true] {
+                round_trip(&Type::PATH, format_code,
+                           &Path{closed, points: vec![a, b, c]});
+                round_trip(&Type::PATH, format_code,
+                           &Path{closed, points: vec![a]});
+            }
+            round_trip(&Type::POLYGON, format_code, &Polygon(vec![a, b, c]));
+            round_trip(&Type::CIRCLE, format_code,
+                       &Circle{center: c, radius: 2.5});
+        }
+    }
+
+    #[test]
+    fn parse()
+    {
+        fn check<T>(s: &str, expected: T)
+            where T: FromStr + fmt::Debug + PartialEq
+        {
+            assert_eq!(s.parse::<T>().ok(), Some(expected), "{s}");
+        }
+
+        let o = Point::new(0.0, 0.0);
+        let p = Point::new(1.0, 2.0);
+        let q = Point::new(-3.5, f64::INFINITY);
+
+        check("(1,2)", p);
+        check(" ( 1 , 2 ) ", p);
+        check("1,2", p);
+        check("(-3.5,Infinity)", q);
+        check("{1,-1,0}", Line{a: 1.0, b: -1.0, c: 0.0});
+        check(" { 1 , 2 , 3 } ", Line{a: 1.0, b: 2.0, c: 3.0});
+
+        let segment = LineSegment{start: p, end: q};
+        for s in ["[(1,2),(-3.5,Infinity)]", "((1,2),(-3.5,Infinity))",
+                  "(1,2),(-3.5,Infinity)", "1,2,-3.5,Infinity",
+                  "(1,2,-3.5,Infinity)"] {
+            check(s, segment);
+        }
+
+        let rect = Rect{high: p, low: o};
+        for s in ["(1,2),(0,0)", "((0,2),(1,0))", "0,0,1,2"] {
+            check(s, rect);
+        }
+
+        let points = vec![o, p, q];
+        check("[(0,0),(1,2),(-3.5,Infinity)]",
+              Path{closed: false, points: points.clone()});
+        check("((0,0),(1,2),(-3.5,Infinity))",
+              Path{closed: true, points: points.clone()});
+        check("(0,0),(1,2),(-3.5,Infinity)",
+              Path{closed: true, points: points.clone()});
+        check("[0,0,1,2]", Path{closed: false, points: vec![o, p]});
+        check("((0,0),(1,2),(-3.5,Infinity))", Polygon(points.clone()));
+        check("(0,0,1,2,-3.5,Infinity)", Polygon(points));
+
+        let circle = Circle{center: p, radius: 3.0};
+        for s in ["<(1,2),3>", "((1,2),3)", "(1,2),3", "1,2,3"] {
+            check(s, circle);
+        }
+
+        for s in ["", "(1)", "(1,2", "1,2)", "(1,2),", "(1,x)", "{1,2}",
+                  "[(1,2)]x", "((1,2),(3,4)"] {
+            assert!(s.parse::<Point>().is_err(), "{s}");
+            assert!(s.parse::<Rect>().is_err(), "{s}");
+            assert!(s.parse::<Path>().is_err(), "{s}");
+        }
+        assert!("[(0,0),(1,1)]".parse::<Rect>().is_err());
+        assert!("[(0,0),(1,1)]".parse::<Polygon>().is_err());
+        assert!("(0,0),(1,1),(2,2)".parse::<LineSegment>().is_err());
+        assert!("<(0,0),1)".parse::<Circle>().is_err());
+    }
+
+    #[test]
+    fn display()
+    {
+        let p = Point::new(1.5, f64::NEG_INFINITY);
+        let q = Point::new(0.0, 2.0);
+        assert_eq!(p.to_string(), "(1.5,-Infinity)");
+        assert_eq!(Line{a: 1.0, b: -1.0, c: 0.0}.to_string(), "{1,-1,0}");
+        assert_eq!(LineSegment{start: p, end: q}.to_string(),
+                   "[(1.5,-Infinity),(0,2)]");
+        assert_eq!(Rect::new(p, q).to_string(), "(1.5,2),(0,-Infinity)");
+        assert_eq!(Path{closed: true, points: vec![p, q]}.to_string(),
+                   "((1.5,-Infinity),(0,2))");
+        assert_eq!(Path{closed: false, points: vec![p, q]}.to_string(),
+                   "[(1.5,-Infinity),(0,2)]");
+        assert_eq!(Polygon(vec![q]).to_string(), "((0,2))");
+        assert_eq!(Circle{center: q, radius: 1.0}.to_string(), "<(0,2),1>");
+    }
+}
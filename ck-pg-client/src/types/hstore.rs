@@ -0,0 +1,312 @@
+//! Codec for `hstore` from the extension of that name.
+//!
+//! The OID of `hstore` differs between databases,
+//! so the type is accepted by its name, in any schema.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{
+        Context,
+        FromSql,
+        IsNull,
+        ToSql,
+        Type,
+        invalid_value,
+        literal::is_space,
+        text_value,
+    },
+    std::{
+        collections::HashMap,
+        hash::BuildHasher,
+        iter::Peekable,
+        str::Chars,
+    },
+};
+
+/// Whether a type is `hstore`.
+fn is_hstore_type(ty: &Type) -> bool
+{
+    ty.name() == "hstore"
+}
+
+/// Values of `hstore` are maps from keys to values that may be NULL.
+impl<S> ToSql for HashMap<String, Option<String>, S>
+    where S: BuildHasher
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_hstore_type(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        if cx.format_code() == FormatCode::Text {
+            write_hstore_text(self, buf);
+            return Ok(IsNull::No);
+        }
+
+        let too_large = || invalid_value(cx, "hstore too large");
+        let write_string = |string: &str, buf: &mut Vec<u8>| -> Result<()> {
+            let length = i32::try_from(string.len()).map_err(|_| too_large())?;
+            buf.extend_from_slice(&length.to_be_bytes());
+            buf.extend_from_slice(string.as_bytes());
+            Ok(())
+        };
+
+        let count = i32::try_from(self.len()).map_err(|_| too_large())?;
+        buf.extend_from_slice(&count.to_be_bytes());
+        for (key, value) in self {
+            write_string(key, buf)?;
+            match value {
+                Some(value) => write_string(value, buf)?,
+                None => buf.extend_from_slice(&(-1i32).to_be_bytes()),
+            }
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl<'a, S> FromSql<'a> for HashMap<String, Option<String>, S>
+    where S: BuildHasher + Default
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_hstore_type(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        let pairs = match cx.format_code() {
+            FormatCode::Binary => read_hstore_binary(cx, raw)?,
+            FormatCode::Text =>
+                parse_hstore(text_value(cx, raw)?)
+                    .ok_or_else(|| invalid_value(cx, "malformed literal"))?,
+        };
+
+        // The server removes duplicate keys, keeping the first.
+        let mut map = Self::with_capacity_and_hasher(pairs.len(),
+                                                     S::default());
+        for (key, value) in pairs {
+            map.entry(key).or_insert(value);
+        }
+        Ok(map)
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+type Pairs = Vec<(String, Option<String>)>;
+
+fn read_hstore_binary(cx: &Context, mut raw: &[u8]) -> Result<Pairs>
+{
+    let malformed = || invalid_value(cx, "malformed binary value");
+
+    let read_i32 = |raw: &mut &[u8]| -> Result<i32> {
+        let (bytes, rest) = raw.split_first_chunk::<4>()
+            .ok_or_else(malformed)?;
+        *raw = rest;
+        Ok(i32::from_be_bytes(*bytes))
+    };
+    let read_string = |raw: &mut &[u8], length: usize| -> Result<String> {
+        let (string, rest) = raw.split_at_checked(length)
+            .ok_or_else(malformed)?;
+        *raw = rest;
+        Ok(text_value(cx, string)?.to_owned())
+    };
+
+    let count = usize::try_from(read_i32(&mut raw)?)
+        .map_err(|_| malformed())?;
+
+    // Each pair takes at least eight bytes, so a corrupt
+    // count cannot cause an arbitrarily large allocation.
+    let mut pairs = Vec::with_capacity(count.min(raw.len() / 8));
+    for _ in 0 .. count {
+        let length = usize::try_from(read_i32(&mut raw)?)
+            .map_err(|_| invalid_value(cx, "key is NULL"))?;
+        let key = read_string(&mut raw, length)?;
+        let value = match read_i32(&mut raw)? {
+            -1 => None,
+            length => {
+                let length = usize::try_from(length)
+                    .map_err(|_| malformed())?;
+                Some(read_string(&mut raw, length)?)
+            },
+        };
+        pairs.push((key, value));
+    }
+
+    if !raw.is_empty() {
+        return Err(invalid_value(cx, "trailing data"));
+    }
+
+    Ok(pairs)
+}
+
+/// Parse an `hstore` literal, such as `"a"=>"1", b=>NULL`.
+///
+/// Keys and values may be quoted, and unquoted `NULL` values are NULL.
+fn parse_hstore(s: &str) -> Option<Pairs>
+{
+    let mut chars = s.chars().peekable();
+    let mut pairs = Vec::new();
+    loop {
+        skip_space(&mut chars);
+        if chars.peek().is_none() {
+            return Some(pairs);
+        }
+
+        let (key, _) = parse_hstore_word(&mut chars, '=')?;
+        skip_space(&mut chars);
+        if chars.next() != Some('=') || chars.next() != Some('>') {
+            return None;
+        }
+        skip_space(&mut chars);
+        let (value, quoted) = parse_hstore_word(&mut chars, ',')?;
+        let value = (quoted || !value.eq_ignore_ascii_case("NULL"))
+            .then_some(value);
+        pairs.push((key, value));
+
+        skip_space(&mut chars);
+        match chars.next() {
+            None => return Some(pairs),
+            Some(',') => (),
+            Some(_) => return None,
+        }
+    }
+}
+
+/// Whether the character is whitespace, as with `scanner_isspace`.
+fn is_space_char(c: char) -> bool
+{
+    u8::try_from(c).is_ok_and(is_space)
+}
+
+fn skip_space(chars: &mut Peekable<Chars>)
+{
+    while chars.next_if(|&c| is_space_char(c)).is_some() {}
+}
+
+/// Parse a key or value, which is either quoted,
+/// or ends at whitespace or the terminator.
+///
+/// Returns the unescaped key or value and whether it was quoted.
+fn parse_hstore_word(chars: &mut Peekable<Chars>, terminator: char)
+    -> Option<(String, bool)>
+{
+    let mut word = String::new();
+
+    if chars.next_if_eq(&'"').is_some() {
+        loop {
+            match chars.next()? {
+                '"' => return Some((word, true)),
+                '\\' => word.push(chars.next()?),
+                c => word.push(c),
+            }
+        }
+    }
+
+    while let Some(&c) = chars.peek() {
+        if c == terminator || is_space_char(c) {
+            break;
+        }
+        chars.next();
+        word.push(if c == '\\' { chars.next()? } else { c });
+    }
+    (!word.is_empty()).then_some((word, false))
+}
+
+/// Write an `hstore` literal, as with `hstore_out`.
+fn write_hstore_text<S>(map: &HashMap<String, Option<String>, S>,
+                        buf: &mut Vec<u8>)
+{
+    let write_quoted = |string: &str, buf: &mut Vec<u8>| {
+        buf.push(b'"');
+        for &byte in string.as_bytes() {
+            if matches!(byte, b'"' | b'\\') {
+                buf.push(b'\\');
+            }
+            buf.push(byte);
+        }
+        buf.push(b'"');
+    };
+
+    for (i, (key, value)) in map.iter().enumerate() {
+        if i != 0 {
+            buf.extend_from_slice(b", ");
+        }
+        write_quoted(key, buf);
+        buf.extend_from_slice(b"=>");
+        match value {
+            Some(value) => write_quoted(value, buf),
+            None => buf.extend_from_slice(b"NULL"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::{Kind, tests::{fuzz_from_sql, round_trip}},
+        super::*,
+    };
+
+    type Hstore = HashMap<String, Option<String>>;
+
+    fn hstore() -> Type
+    {
+        Type::new(0, "hstore".to_owned(), Kind::Simple)
+    }
+
+    fn pairs(pairs: &[(&str, Option<&str>)]) -> Hstore
+    {
+        pairs.iter()
+            .map(|&(key, value)| (key.to_owned(), value.map(str::to_owned)))
+            .collect()
+    }
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<Hstore>(&hstore());
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            round_trip(&hstore(), format_code, &Hstore::new());
+            round_trip(&hstore(), format_code,
+                       &pairs(&[("a", Some("1")), ("", Some("")),
+                                ("NULL", None), ("n", Some("NULL")),
+                                ("x\"y\\z", Some(" => , "))]));
+        }
+    }
+
+    #[test]
+    fn text()
+    {
+        let ty = hstore();
+        let cx = Context::new(&ty, FormatCode::Text);
+        let check = |s: &str, expected: &[(&str, Option<&str>)]| {
+            let map: Hstore = Hstore::from_sql(&cx, s.as_bytes()).unwrap();
+            assert_eq!(map, pairs(expected), "{s}");
+        };
+
+        check("", &[]);
+        check(" ", &[]);
+        check("a=>1", &[("a", Some("1"))]);
+        check(r#" "a b" => "c" , d=>null, "e"=>"NULL","#,
+              &[("a b", Some("c")), ("d", None), ("e", Some("NULL"))]);
+        check(r#"a\ b=>c\"d,e=>f=>g"#,
+              &[("a b", Some("c\"d")), ("e", Some("f=>g"))]);
+        check("a=>1, a=>2", &[("a", Some("1"))]);
+
+        for s in ["a", "a=>", "=>b", "a=b", "a=>b c=>d", "\"a=>b", "a=>b,,"] {
+            assert!(Hstore::from_sql(&cx, s.as_bytes()).is_err(), "{s}");
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Support for `json` and `jsonb`.
+//!
+//! Both types are represented by their JSON text, so `&str` and `String`
+//! can be used for them; decoding into `&str` does not allocate.
+//! In binary format, `jsonb` values are preceded by a version number,
+//! which is added and removed by the codecs.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{Context, Type, invalid_value},
+};
+
+/// The version of the binary format of `jsonb`.
+const JSONB_VERSION: u8 = 1;
+
+/// Whether a type is `json` or `jsonb`.
+pub (super) fn is_json_type(ty: &Type) -> bool
+{
+    *ty == Type::JSON || *ty == Type::JSONB
+}
+
+/// Write what precedes the JSON text of a value of the given type,
+/// which is the version number for `jsonb` in binary format.
+pub (super) fn write_json_header(cx: &Context, buf: &mut Vec<u8>)
+{
+    if *cx.ty() == Type::JSONB && cx.format_code() == FormatCode::Binary {
+        buf.push(JSONB_VERSION);
+    }
+}
+
+/// Remove what precedes the JSON text of a value of the given type,
+/// which is the version number for `jsonb` in binary format.
+pub (super) fn strip_json_header<'a>(cx: &Context, raw: &'a [u8])
+    -> Result<&'a [u8]>
+{
+    if *cx.ty() != Type::JSONB || cx.format_code() == FormatCode::Text {
+        return Ok(raw);
+    }
+    match raw {
+        [JSONB_VERSION, json @ ..] => Ok(json),
+        [_, ..] => Err(invalid_value(cx, "unsupported jsonb version")),
+        [] => Err(invalid_value(cx, "missing jsonb version")),
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::{
+            FromSql,
+            ToSql,
+            tests::{fuzz_from_sql, round_trip},
+        },
+        super::*,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<String>(&Type::JSONB);
+        fuzz_from_sql::<String>(&Type::JSON);
+    }
+
+    #[test]
+    fn jsonb_version()
+    {
+        let cx = Context::new(&Type::JSONB, FormatCode::Binary);
+        let mut buf = Vec::new();
+        r#"{"a": 1}"#.to_sql(&cx, &mut buf).unwrap();
+        assert_eq!(buf, b"\x01{\"a\": 1}");
+        assert_eq!(<&str>::from_sql(&cx, b"\x01[]").unwrap(), "[]");
+        assert!(<&str>::from_sql(&cx, b"\x02[]").is_err());
+        assert!(<&str>::from_sql(&cx, b"").is_err());
+
+        let cx = Context::new(&Type::JSON, FormatCode::Binary);
+        assert_eq!(<&str>::from_sql(&cx, b"\x01[]").unwrap(), "\x01[]");
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for ty in [Type::JSON, Type::JSONB] {
+                round_trip(&ty, format_code, &String::from(r#"{"a": [1]}"#));
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Codecs for `json` and `jsonb` using the **serde_json** crate.
+
+use {
+    crate::{Error, Result},
+    super::{
+        Context,
+        FromSql,
+        IsNull,
+        ToSql,
+        Type,
+        json::{is_json_type, strip_json_header, write_json_header},
+    },
+    serde::{Deserialize, Serialize},
+};
+
+/// Wrapper for encoding and decoding values of type `json` or `jsonb`
+/// using [`Serialize`] and [`Deserialize`].
+///
+/// Values are deserialized from the buffer they were received in,
+/// so they may borrow from it.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Json<T>(pub T);
+
+impl<T> ToSql for Json<T>
+    where T: Serialize
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_json_type(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        write_json_header(cx, buf);
+        serde_json::to_writer(buf, &self.0)
+            .map_err(|err| Error::Conversion(err.into()))?;
+        Ok(IsNull::No)
+    }
+}
+
+impl<'a, T> FromSql<'a> for Json<T>
+    where T: Deserialize<'a>
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_json_type(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        serde_json::from_slice(strip_json_header(cx, raw)?)
+            .map(Self)
+            .map_err(|err| Error::Conversion(err.into()))
+    }
+
+    /// Deserializes through [`serde_json::Value`],
+    /// so `T` cannot borrow from the value.
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        let value = serde_json::Value::from_sql_temporary(cx, raw)?;
+        T::deserialize(value)
+            .map(Self)
+            .map_err(|err| Error::Conversion(err.into()))
+    }
+}
+
+impl ToSql for serde_json::Value
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_json_type(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        Json(self).to_sql(cx, buf)
+    }
+}
+
+impl<'a> FromSql<'a> for serde_json::Value
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_json_type(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        Json::from_sql(cx, raw).map(|Json(value)| value)
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::{
+            protocol::FormatCode,
+            types::tests::{fuzz_from_sql, round_trip},
+        },
+        super::*,
+        std::collections::BTreeMap,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<serde_json::Value>(&Type::JSONB);
+        fuzz_from_sql::<Json<Vec<String>>>(&Type::JSON);
+    }
+
+    #[test]
+    fn borrowed()
+    {
+        let cx = Context::new(&Type::JSONB, FormatCode::Binary);
+        let raw = b"\x01{\"a\": [\"b\", \"c\"]}";
+        let Json(value) =
+            Json::<BTreeMap<&str, Vec<&str>>>::from_sql(&cx, raw).unwrap();
+        assert_eq!(value["a"], ["b", "c"]);
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        let value = serde_json::json!({"a": [1, 2.5, null, "\u{1F600}"]});
+        let map = BTreeMap::from([(String::from("a"), vec![1, 2])]);
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for ty in [Type::JSON, Type::JSONB] {
+                round_trip(&ty, format_code, &value);
+                round_trip(&ty, format_code, &Json(map.clone()));
+            }
+        }
+    }
+}
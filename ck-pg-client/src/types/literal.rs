@@ -0,0 +1,898 @@
+//! Text format of arrays, composite values and ranges.
+//!
+//! Arrays are written like `{1,"a b",NULL}`, composite values
+//! like `(1,"x,y",)`, ranges like `[1,5)` and multiranges
+//! like `{[1,5),[7,)}`. The elements, fields and bounds are themselves
+//! in the text format of their types, quoted when necessary.
+//! These functions work on the elements, fields and bounds as strings,
+//! so they can be used for any element type and any composite type.
+
+use {
+    super::{Array, Dimension, MAX_DIMENSIONS, Range},
+    std::{borrow::Cow, ops::Bound},
+    thiserror::Error,
+};
+
+/// Error returned when parsing an array literal, a composite literal
+/// or a range literal fails.
+#[derive(Clone, Debug, Error)]
+#[error("invalid array, composite or range literal")]
+pub struct ParseLiteralError(());
+
+/// An element of an array or a field of a composite value.
+///
+/// [`None`] is SQL NULL. Values are borrowed from the literal
+/// unless they contain escapes, which must be removed.
+type Item<'a> = Option<Cow<'a, str>>;
+
+/// Parse an array literal, such as `{{1,2},{3,NULL}}` or `[0:1]={a,b}`.
+///
+/// The elements are separated by the delimiter of the element type,
+/// which is `;` for `box` and `,` for every other built-in type.
+/// Unquoted `NULL` elements are [`None`].
+/// The elements are borrowed from the literal
+/// unless they contain backslash escapes.
+pub fn parse_array_literal(s: &str, delimiter: u8)
+    -> Result<Array<Option<Cow<'_, str>>>, ParseLiteralError>
+{
+    let mut p = Parser{s, pos: 0};
+    parse_array(&mut p, delimiter).ok_or(ParseLiteralError(()))
+}
+
+/// Parse a composite literal, such as `(1,"x,y",)`.
+///
+/// Empty fields are [`None`], whereas `""` is the empty string.
+/// Note that a composite value with no fields is written as `()`,
+/// which is parsed as a single [`None`] field.
+/// The fields are borrowed from the literal unless they contain
+/// backslash escapes or doubled quotes.
+pub fn parse_composite_literal(s: &str)
+    -> Result<Vec<Option<Cow<'_, str>>>, ParseLiteralError>
+{
+    let mut p = Parser{s, pos: 0};
+    parse_composite(&mut p).ok_or(ParseLiteralError(()))
+}
+
+/// Parse a range literal, such as `[1,5)`, `(,"a b"]` or `empty`.
+///
+/// Missing bounds are [`Bound::Unbounded`], whereas `""` is
+/// the empty string. The bounds are borrowed from the literal
+/// unless they contain backslash escapes or doubled quotes.
+pub fn parse_range_literal(s: &str)
+    -> Result<Range<Cow<'_, str>>, ParseLiteralError>
+{
+    let mut p = Parser{s, pos: 0};
+    p.skip_space();
+    let range = parse_range(&mut p).ok_or(ParseLiteralError(()))?;
+    p.skip_space();
+    if !p.at_end() {
+        return Err(ParseLiteralError(()));
+    }
+    Ok(range)
+}
+
+/// Parse a multirange literal, such as `{[1,5),[7,)}`.
+///
+/// The ranges are parsed as with [`parse_range_literal`].
+pub fn parse_multirange_literal(s: &str)
+    -> Result<Vec<Range<Cow<'_, str>>>, ParseLiteralError>
+{
+    let mut p = Parser{s, pos: 0};
+    parse_multirange(&mut p).ok_or(ParseLiteralError(()))
+}
+
+/// Write an array literal, quoting elements where necessary.
+///
+/// The delimiter must be an ASCII character.
+/// The dimensions are written only if a lower bound is not 1,
+/// as PostgreSQL does.
+pub fn write_array_literal<S>(
+    array: &Array<Option<S>>,
+    delimiter: u8,
+    out: &mut String,
+)
+    where S: AsRef<str>
+{
+    let dimensions = array.dimensions();
+    let elements = array.elements();
+
+    if dimensions.iter().any(|dimension| dimension.lower_bound != 1) {
+        for &Dimension{length, lower_bound} in dimensions {
+            // Array::new ensures that the upper bound fits.
+            let offset = u32::try_from(length.saturating_sub(1))
+                .unwrap_or(u32::MAX);
+            let upper_bound = lower_bound.saturating_add_unsigned(offset);
+            out.push_str(&format!("[{lower_bound}:{upper_bound}]"));
+        }
+        out.push('=');
+    }
+
+    if elements.is_empty() {
+        out.push_str("{}");
+        return;
+    }
+
+    // The number of elements in a sub-array at each depth.
+    let strides: Vec<usize> = (0 .. dimensions.len())
+        .map(|depth| {
+            dimensions[depth ..].iter().map(|d| d.length).product()
+        })
+        .collect();
+
+    for (i, element) in elements.iter().enumerate() {
+        let boundaries = strides.iter().filter(|&&s| i % s == 0).count();
+        if i != 0 {
+            out.extend(std::iter::repeat_n('}', boundaries));
+            out.push(char::from(delimiter));
+        }
+        out.extend(std::iter::repeat_n('{', boundaries));
+        match element {
+            None => out.push_str("NULL"),
+            Some(element) => write_array_element(element.as_ref(),
+                                                 delimiter, out),
+        }
+    }
+    out.extend(std::iter::repeat_n('}', dimensions.len()));
+}
+
+/// Write a composite literal, quoting fields where necessary.
+pub fn write_composite_literal<I, S>(fields: I, out: &mut String)
+    where I: IntoIterator<Item = Option<S>>, S: AsRef<str>
+{
+    out.push('(');
+    for (i, field) in fields.into_iter().enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        if let Some(field) = field {
+            write_composite_field(field.as_ref(), out);
+        }
+    }
+    out.push(')');
+}
+
+/// Write a range literal, quoting bounds where necessary.
+pub fn write_range_literal<S>(range: &Range<S>, out: &mut String)
+    where S: AsRef<str>
+{
+    let (lower, upper) = match range {
+        Range::Empty => {
+            out.push_str("empty");
+            return;
+        },
+        Range::NonEmpty{lower, upper} => (lower, upper),
+    };
+
+    out.push(if matches!(lower, Bound::Included(..)) { '[' } else { '(' });
+    if let Bound::Included(bound) | Bound::Excluded(bound) = lower {
+        write_range_bound(bound.as_ref(), out);
+    }
+    out.push(',');
+    if let Bound::Included(bound) | Bound::Excluded(bound) = upper {
+        write_range_bound(bound.as_ref(), out);
+    }
+    out.push(if matches!(upper, Bound::Included(..)) { ']' } else { ')' });
+}
+
+/// Write a multirange literal, quoting bounds where necessary.
+pub fn write_multirange_literal<S>(ranges: &[Range<S>], out: &mut String)
+    where S: AsRef<str>
+{
+    out.push('{');
+    for (i, range) in ranges.iter().enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        write_range_literal(range, out);
+    }
+    out.push('}');
+}
+
+/// Cursor over a literal.
+///
+/// Positions are byte offsets. Only ASCII characters are examined,
+/// so positions at which the parser stops are always character
+/// boundaries, but slicing is checked regardless.
+struct Parser<'a>
+{
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a>
+{
+    fn peek(&self) -> Option<u8>
+    {
+        self.s.as_bytes().get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<u8>
+    {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn eat(&mut self, byte: u8) -> bool
+    {
+        let found = self.peek() == Some(byte);
+        self.pos += usize::from(found);
+        found
+    }
+
+    /// Consume the ASCII keyword, ignoring case.
+    fn eat_keyword(&mut self, keyword: &str) -> bool
+    {
+        let end = self.pos + keyword.len();
+        let found = self.slice(self.pos, end)
+            .is_some_and(|word| word.eq_ignore_ascii_case(keyword));
+        if found {
+            self.pos = end;
+        }
+        found
+    }
+
+    fn skip_space(&mut self)
+    {
+        while self.peek().is_some_and(is_space) {
+            self.pos += 1;
+        }
+    }
+
+    fn at_end(&self) -> bool
+    {
+        self.pos >= self.s.len()
+    }
+
+    fn slice(&self, start: usize, end: usize) -> Option<&'a str>
+    {
+        self.s.get(start .. end)
+    }
+
+    /// Parse an optionally signed decimal integer.
+    fn integer(&mut self) -> Option<i32>
+    {
+        let start = self.pos;
+        let _ = self.eat(b'+') || self.eat(b'-');
+        while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.slice(start, self.pos)?.parse().ok()
+    }
+}
+
+/// Whether the byte is whitespace, as with `scanner_isspace`.
+pub (super) fn is_space(byte: u8) -> bool
+{
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | b'\x0B' | b'\x0C')
+}
+
+/// Structure of an array discovered while parsing it.
+struct Shape<'a>
+{
+    /// The number of dimensions, known once an element is found.
+    ndim: Option<usize>,
+
+    /// The length of the sub-arrays at each depth,
+    /// known once the first sub-array at that depth is complete.
+    lengths: [Option<usize>; MAX_DIMENSIONS],
+
+    elements: Vec<Item<'a>>,
+}
+
+fn parse_array<'a>(p: &mut Parser<'a>, delimiter: u8)
+    -> Option<Array<Item<'a>>>
+{
+    // Optional dimensions, such as `[0:1][-1:1]=`.
+    // The lower bound defaults to 1.
+    let mut bounds = Vec::new();
+    p.skip_space();
+    while p.eat(b'[') {
+        if bounds.len() == MAX_DIMENSIONS {
+            return None;
+        }
+        p.skip_space();
+        let first = p.integer()?;
+        p.skip_space();
+        let bound = if p.eat(b':') {
+            p.skip_space();
+            let upper_bound = p.integer()?;
+            p.skip_space();
+            (first, upper_bound)
+        } else {
+            (1, first)
+        };
+        if !p.eat(b']') {
+            return None;
+        }
+        bounds.push(bound);
+        p.skip_space();
+    }
+    if !bounds.is_empty() {
+        if !p.eat(b'=') {
+            return None;
+        }
+        p.skip_space();
+    }
+
+    if !p.eat(b'{') {
+        return None;
+    }
+    p.skip_space();
+    let mut shape = Shape{
+        ndim: None,
+        lengths: [None; MAX_DIMENSIONS],
+        elements: Vec::new(),
+    };
+    let empty = p.eat(b'}');
+    if !empty {
+        parse_sub_array(p, delimiter, 0, &mut shape)?;
+    }
+    p.skip_space();
+    if !p.at_end() {
+        return None;
+    }
+    if empty {
+        return bounds.is_empty().then(Array::default);
+    }
+
+    let ndim = shape.ndim?;
+    let lengths = shape.lengths.get(.. ndim)?;
+    let dimensions = if bounds.is_empty() {
+        lengths.iter()
+            .map(|&length| Some(Dimension{length: length?, lower_bound: 1}))
+            .collect::<Option<Vec<_>>>()?
+    } else if bounds.len() == ndim {
+        bounds.iter().zip(lengths)
+            .map(|(&(lower_bound, upper_bound), &length)| {
+                let length = length?;
+                let expected = i64::from(upper_bound) - i64::from(lower_bound);
+                (usize::try_from(expected + 1).ok() == Some(length))
+                    .then_some(Dimension{length, lower_bound})
+            })
+            .collect::<Option<Vec<_>>>()?
+    } else {
+        return None;
+    };
+
+    Array::new(dimensions, shape.elements)
+}
+
+/// Parse the items of a sub-array at the given depth,
+/// after its opening brace, up to and including its closing brace.
+///
+/// The depth is at most [`MAX_DIMENSIONS`], which bounds the recursion.
+fn parse_sub_array<'a>(
+    p: &mut Parser<'a>,
+    delimiter: u8,
+    depth: usize,
+    shape: &mut Shape<'a>,
+) -> Option<()>
+{
+    let mut length = 0;
+    loop {
+        p.skip_space();
+        if p.eat(b'{') {
+            let depth = depth + 1;
+            if depth == MAX_DIMENSIONS
+                || shape.ndim.is_some_and(|ndim| depth >= ndim) {
+                return None;
+            }
+            parse_sub_array(p, delimiter, depth, shape)?;
+        } else {
+            if *shape.ndim.get_or_insert(depth + 1) != depth + 1 {
+                return None;
+            }
+            let element = parse_array_element(p, delimiter)?;
+            shape.elements.push(element);
+        }
+        length += 1;
+
+        p.skip_space();
+        match p.next()? {
+            b'}' => break,
+            byte if byte == delimiter => (),
+            _ => return None,
+        }
+    }
+
+    // All sub-arrays at the same depth must have the same length.
+    let expected = shape.lengths.get_mut(depth)?;
+    (*expected.get_or_insert(length) == length).then_some(())
+}
+
+/// Parse an element of an array, up to the delimiter or closing brace.
+fn parse_array_element<'a>(p: &mut Parser<'a>, delimiter: u8)
+    -> Option<Item<'a>>
+{
+    let mut escaped = false;
+
+    if p.eat(b'"') {
+        let start = p.pos;
+        loop {
+            match p.next()? {
+                b'"' => break,
+                b'\\' => {
+                    p.next()?;
+                    escaped = true;
+                },
+                _ => (),
+            }
+        }
+        let raw = p.slice(start, p.pos - 1)?;
+        return Some(Some(unescape_array_element(raw, escaped)));
+    }
+
+    // Unquoted elements end at the delimiter or closing brace,
+    // and trailing whitespace is not part of the element
+    // unless it is escaped.
+    let start = p.pos;
+    let mut end = start;
+    loop {
+        match p.peek()? {
+            b'"' | b'{' => return None,
+            b'}' => break,
+            byte if byte == delimiter => break,
+            b'\\' => {
+                p.pos += 1;
+                p.next()?;
+                escaped = true;
+                end = p.pos;
+            },
+            byte => {
+                p.pos += 1;
+                if !is_space(byte) {
+                    end = p.pos;
+                }
+            },
+        }
+    }
+
+    let raw = p.slice(start, end)?;
+    if raw.is_empty() {
+        return None;
+    }
+    if !escaped && raw.eq_ignore_ascii_case("NULL") {
+        return Some(None);
+    }
+    Some(Some(unescape_array_element(raw, escaped)))
+}
+
+/// Remove backslash escapes from an array element.
+fn unescape_array_element(raw: &str, escaped: bool) -> Cow<'_, str>
+{
+    if !escaped {
+        return Cow::Borrowed(raw);
+    }
+    let mut unescaped = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    Cow::Owned(unescaped)
+}
+
+fn parse_composite<'a>(p: &mut Parser<'a>) -> Option<Vec<Item<'a>>>
+{
+    p.skip_space();
+    if !p.eat(b'(') {
+        return None;
+    }
+    let mut fields = Vec::new();
+    loop {
+        fields.push(parse_field(p, b",)")?);
+        match p.next()? {
+            b',' => (),
+            b')' => break,
+            _ => return None,
+        }
+    }
+    p.skip_space();
+    p.at_end().then_some(fields)
+}
+
+/// Parse a field of a composite value or a bound of a range,
+/// up to any of the unquoted terminators.
+///
+/// Unlike array elements, fields may be partially quoted,
+/// and whitespace is part of the field.
+/// An empty field is [`None`].
+fn parse_field<'a>(p: &mut Parser<'a>, terminators: &[u8])
+    -> Option<Item<'a>>
+{
+    if p.peek().is_some_and(|byte| terminators.contains(&byte)) {
+        return Some(None);
+    }
+
+    let start = p.pos;
+    let mut quoted = false;
+    let mut escaped = false;
+    loop {
+        match p.peek()? {
+            byte if !quoted && terminators.contains(&byte) => break,
+            b'\\' => {
+                p.pos += 1;
+                p.next()?;
+                escaped = true;
+            },
+            b'"' => {
+                p.pos += 1;
+                quoted = !quoted;
+                escaped = true;
+            },
+            _ => p.pos += 1,
+        }
+    }
+
+    let raw = p.slice(start, p.pos)?;
+
+    // The common case of a quoted field without escapes can be borrowed.
+    let inner = raw.strip_prefix('"')
+        .and_then(|raw| raw.strip_suffix('"'))
+        .filter(|inner| !inner.contains(['"', '\\']));
+    if let Some(inner) = inner {
+        return Some(Some(Cow::Borrowed(inner)));
+    }
+
+    if !escaped {
+        return Some(Some(Cow::Borrowed(raw)));
+    }
+    let mut unescaped = String::with_capacity(raw.len());
+    let mut quoted = false;
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                unescaped.push('"');
+            },
+            '"' => quoted = !quoted,
+            c => unescaped.push(c),
+        }
+    }
+    Some(Some(Cow::Owned(unescaped)))
+}
+
+/// Parse a range, which is `empty` or has bounds in brackets
+/// or parentheses, depending on whether they are inclusive.
+fn parse_range<'a>(p: &mut Parser<'a>) -> Option<Range<Cow<'a, str>>>
+{
+    if p.eat_keyword("empty") {
+        return Some(Range::Empty);
+    }
+
+    let bound = |bound, inclusive| match bound {
+        None => Bound::Unbounded,
+        Some(bound) if inclusive => Bound::Included(bound),
+        Some(bound) => Bound::Excluded(bound),
+    };
+
+    let lower_inclusive = match p.next()? {
+        b'[' => true,
+        b'(' => false,
+        _ => return None,
+    };
+    let lower = parse_field(p, b",)]")?;
+    if !p.eat(b',') {
+        return None;
+    }
+    let upper = parse_field(p, b",)]")?;
+    let upper_inclusive = match p.next()? {
+        b']' => true,
+        b')' => false,
+        _ => return None,
+    };
+
+    Some(Range::NonEmpty{
+        lower: bound(lower, lower_inclusive),
+        upper: bound(upper, upper_inclusive),
+    })
+}
+
+fn parse_multirange<'a>(p: &mut Parser<'a>)
+    -> Option<Vec<Range<Cow<'a, str>>>>
+{
+    p.skip_space();
+    if !p.eat(b'{') {
+        return None;
+    }
+    p.skip_space();
+    let mut ranges = Vec::new();
+    if !p.eat(b'}') {
+        loop {
+            p.skip_space();
+            ranges.push(parse_range(p)?);
+            p.skip_space();
+            match p.next()? {
+                b',' => (),
+                b'}' => break,
+                _ => return None,
+            }
+        }
+    }
+    p.skip_space();
+    p.at_end().then_some(ranges)
+}
+
+/// Write an element of an array, as with `array_out`.
+fn write_array_element(element: &str, delimiter: u8, out: &mut String)
+{
+    let needs_quotes = element.is_empty()
+        || element.eq_ignore_ascii_case("NULL")
+        || element.bytes().any(|byte| {
+            matches!(byte, b'"' | b'\\' | b'{' | b'}')
+                || byte == delimiter
+                || is_space(byte)
+        });
+
+    if !needs_quotes {
+        out.push_str(element);
+        return;
+    }
+
+    out.push('"');
+    for c in element.chars() {
+        if matches!(c, '"' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+}
+
+/// Write a field of a composite value, as with `record_out`.
+fn write_composite_field(field: &str, out: &mut String)
+{
+    write_field(field, b"\"\\(),", out);
+}
+
+/// Write a bound of a range, as with `range_out`.
+fn write_range_bound(bound: &str, out: &mut String)
+{
+    write_field(bound, b"\"\\()[],", out);
+}
+
+/// Write a field or bound, quoting it if it is empty or contains
+/// whitespace or any of the special characters,
+/// and doubling any quotes and backslashes within the quotes.
+fn write_field(field: &str, special: &[u8], out: &mut String)
+{
+    let needs_quotes = field.is_empty()
+        || field.bytes().any(|byte| special.contains(&byte) || is_space(byte));
+
+    if !needs_quotes {
+        out.push_str(field);
+        return;
+    }
+
+    out.push('"');
+    for c in field.chars() {
+        if matches!(c, '"' | '\\') {
+            out.push(c);
+        }
+        out.push(c);
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {crate::types::tests::fuzz_inputs, super::*};
+
+    fn check_array(
+        s: &str,
+        delimiter: u8,
+        dimensions: &[(usize, i32)],
+        elements: &[Option<&str>],
+    )
+    {
+        let dimensions = dimensions.iter()
+            .map(|&(length, lower_bound)| Dimension{length, lower_bound})
+            .collect();
+        let expected = Array::new(dimensions, items(elements)).unwrap();
+        assert_eq!(parse_array_literal(s, delimiter).unwrap(), expected,
+                   "{s}");
+    }
+
+    fn items(items: &[Option<&str>]) -> Vec<Item<'static>>
+    {
+        items.iter()
+            .map(|item| item.map(|item| Cow::Owned(item.to_owned())))
+            .collect()
+    }
+
+    #[test]
+    fn parse_array()
+    {
+        check_array("{}", b',', &[], &[]);
+        check_array(" { } ", b',', &[], &[]);
+        check_array("{1,2,3}", b',', &[(3, 1)],
+                    &[Some("1"), Some("2"), Some("3")]);
+        check_array(r#"{1,"a b",NULL}"#, b',', &[(3, 1)],
+                    &[Some("1"), Some("a b"), None]);
+        check_array(r#"{"NULL",null,"",Null}"#, b',', &[(4, 1)],
+                    &[Some("NULL"), None, Some(""), None]);
+        check_array(r#"{"a\"b\\c",\{x\}}"#, b',', &[(2, 1)],
+                    &[Some(r#"a"b\c"#), Some("{x}")]);
+        check_array("{ a b , c\\  }", b',', &[(2, 1)],
+                    &[Some("a b"), Some("c ")]);
+        check_array("{{1,2},{3,4},{5,6}}", b',', &[(3, 1), (2, 1)],
+                    &[Some("1"), Some("2"), Some("3"),
+                      Some("4"), Some("5"), Some("6")]);
+        check_array("[0:1][-1:-1]={{1},{2}}", b',', &[(2, 0), (1, -1)],
+                    &[Some("1"), Some("2")]);
+        check_array("[2]={a,b}", b',', &[(2, 1)], &[Some("a"), Some("b")]);
+        check_array("{(1,2),(0,0);(3,3),(2,2)}", b';', &[(2, 1)],
+                    &[Some("(1,2),(0,0)"), Some("(3,3),(2,2)")]);
+        check_array("{\u{1F418},\"\\\u{1F418}\"}", b',', &[(2, 1)],
+                    &[Some("\u{1F418}"), Some("\u{1F418}")]);
+        check_array("{{{{{{1}}}}}}", b',', &[(1, 1); 6], &[Some("1")]);
+    }
+
+    #[test]
+    fn parse_array_borrows()
+    {
+        let array = parse_array_literal(r#"{a,"b c","d\"e"}"#, b',')
+            .unwrap();
+        let borrowed: Vec<bool> = array.elements().iter()
+            .map(|element| matches!(element, Some(Cow::Borrowed(..))))
+            .collect();
+        assert_eq!(borrowed, [true, true, false]);
+    }
+
+    #[test]
+    fn parse_array_rejects()
+    {
+        for s in [
+            "", "{", "}", "{1", "{1,}", "{,1}", "{1,,2}", "{{1},2}", "{1,{2}}",
+            "{{1,2},{3}}", "{{},{}}", "{\"a}", "{a\"b\"}", "{a}b", "{a\\}",
+            "[1:2]={1}", "[1:1][1:1]={1}", "[1:1]{1}", "[1:0]={}", "[a]={1}",
+            "{{{{{{{1}}}}}}}", "[2147483647:-2147483648]={1}",
+        ] {
+            assert!(parse_array_literal(s, b',').is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn parse_composite()
+    {
+        let cases: &[(&str, &[Option<&str>])] = &[
+            ("()", &[None]),
+            ("(,)", &[None, None]),
+            (r#"(1,"x,y",)"#, &[Some("1"), Some("x,y"), None]),
+            (r#"("",a b, c )"#, &[Some(""), Some("a b"), Some(" c ")]),
+            (r#"("a""b","c\\d",e"f,g"h)"#,
+             &[Some("a\"b"), Some("c\\d"), Some("ef,gh")]),
+            (r#" ("(1,2)") "#, &[Some("(1,2)")]),
+        ];
+        for &(s, fields) in cases {
+            assert_eq!(parse_composite_literal(s).ok(), Some(items(fields)),
+                       "{s}");
+        }
+
+        for s in ["", "(", ")", "(\"a)", "(a)b", "(a\\)", "a,b"] {
+            assert!(parse_composite_literal(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn parse_range()
+    {
+        let range = |lower: Bound<&str>, upper: Bound<&str>| {
+            let owned = |bound: Bound<&str>| bound.map(|bound| {
+                Cow::Owned(bound.to_owned())
+            });
+            Range::NonEmpty{lower: owned(lower), upper: owned(upper)}
+        };
+        let cases = [
+            ("empty", Range::Empty),
+            (" EmPtY ", Range::Empty),
+            ("[1,5)", range(Bound::Included("1"), Bound::Excluded("5"))),
+            ("(,)", range(Bound::Unbounded, Bound::Unbounded)),
+            (r#" (-1," a"] "#, range(Bound::Excluded("-1"),
+                                     Bound::Included(" a"))),
+            (r#"["",a""b)"#, range(Bound::Included(""),
+                                   Bound::Excluded("ab"))),
+            (r#"["a""b",c\\d]"#, range(Bound::Included("a\"b"),
+                                       Bound::Included("c\\d"))),
+            (r#"["[1,2)",)"#, range(Bound::Included("[1,2)"),
+                                    Bound::Unbounded)),
+        ];
+        for (s, expected) in &cases {
+            assert_eq!(parse_range_literal(s).ok().as_ref(), Some(expected),
+                       "{s}");
+        }
+
+        let ranges = parse_multirange_literal(" { [1,5) , empty,(,)} ")
+            .unwrap();
+        assert_eq!(ranges, [cases[2].1.clone(), Range::Empty,
+                            cases[3].1.clone()]);
+        assert_eq!(parse_multirange_literal("{}").unwrap(), []);
+
+        for s in ["", "[", "[1,2", "[1)", "[1,2,3)", "{1,2}", "[1,2)x",
+                  "emptyx", "(\"a,)"] {
+            assert!(parse_range_literal(s).is_err(), "{s}");
+        }
+        for s in ["", "{", "{,}", "{[1,2),}", "{[1,2)}x", "[1,2)", "{(1)}"] {
+            assert!(parse_multirange_literal(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn write()
+    {
+        let write_array = |dimensions, elements: &[Option<&str>]| {
+            let array = Array::new(dimensions, elements.to_vec()).unwrap();
+            let mut out = String::new();
+            write_array_literal(&array, b',', &mut out);
+            assert_eq!(parse_array_literal(&out, b',').unwrap(),
+                       Array::new(array.dimensions().to_vec(),
+                                  items(elements)).unwrap());
+            out
+        };
+        let dimension = |length, lower_bound| Dimension{length, lower_bound};
+
+        assert_eq!(write_array(vec![], &[]), "{}");
+        assert_eq!(write_array(vec![dimension(5, 1)],
+                               &[Some("a"), None, Some("NULL"), Some(""),
+                                 Some("x\"y\\z")]),
+                   r#"{a,NULL,"NULL","","x\"y\\z"}"#);
+        assert_eq!(write_array(vec![dimension(2, 1), dimension(2, 1)],
+                               &[Some("{"), Some("}"), Some("a,b"),
+                                 Some(" ")]),
+                   r#"{{"{","}"},{"a,b"," "}}"#);
+        assert_eq!(write_array(vec![dimension(1, i32::MAX),
+                                    dimension(2, -1)],
+                               &[Some("1"), Some("2")]),
+                   "[2147483647:2147483647][-1:0]={{1,2}}");
+
+        let mut out = String::new();
+        let array = Array::from(vec![Some("(1,1),(0,0)"), Some("a;b")]);
+        write_array_literal(&array, b';', &mut out);
+        assert_eq!(out, r#"{(1,1),(0,0);"a;b"}"#);
+
+        let fields = [Some("1"), None, Some(""), Some("x,y"),
+                      Some("a\"b\\c")];
+        let mut out = String::new();
+        write_composite_literal(fields, &mut out);
+        assert_eq!(out, r#"(1,,"","x,y","a""b\\c")"#);
+        assert_eq!(parse_composite_literal(&out).unwrap(), items(&fields));
+
+        let ranges = [
+            Range::Empty,
+            Range::NonEmpty{lower: Bound::Included("1"),
+                            upper: Bound::Unbounded},
+            Range::NonEmpty{lower: Bound::Excluded(""),
+                            upper: Bound::Included("a]\"b\\c")},
+        ];
+        let mut out = String::new();
+        write_multirange_literal(&ranges, &mut out);
+        assert_eq!(out, r#"{empty,[1,),("","a]""b\\c"]}"#);
+        let parsed = parse_multirange_literal(&out).unwrap();
+        assert_eq!(parsed, ranges.map(|range| match range {
+            Range::Empty => Range::Empty,
+            Range::NonEmpty{lower, upper} => Range::NonEmpty{
+                lower: lower.map(Cow::Borrowed),
+                upper: upper.map(Cow::Borrowed),
+            },
+        }));
+    }
+
+    #[test]
+    fn fuzz()
+    {
+        for input in fuzz_inputs() {
+            let input = String::from_utf8_lossy(&input);
+            let _ = parse_array_literal(&input, b',');
+            let _ = parse_array_literal(&input, b';');
+            let _ = parse_composite_literal(&input);
+            let _ = parse_range_literal(&input);
+            let _ = parse_multirange_literal(&input);
+        }
+    }
+}
@@ -0,0 +1,591 @@
+//! Conversion between Rust values and PostgreSQL values.
+//!
+//! [`ToSql`] encodes Rust values as parameter values,
+//! and [`FromSql`] decodes column values into Rust values.
+//! Each implementation declares which PostgreSQL [`Type`]s it accepts.
+//! The type of each parameter, as reported by `ParameterDescription`,
+//! and the type of each column, as reported by `RowDescription`,
+//! is checked against this before any conversion takes place,
+//! so that a mismatch results in [`Error::WrongType`]
+//! rather than in misinterpreted bytes.
+//!
+//! Values are converted in the format given by the [`Context`].
+//! Typed queries, such as [`PgClient::query`], use binary format
+//! for both parameters and results.
+//!
+//! Types that are not built in, such as enums, domains, and types
+//! created by extensions, have OIDs that differ between databases.
+//! Typed queries look up such types in the catalogs of the server
+//! the first time they are encountered;
+//! see [`PgClient::lookup_type`] for details.
+//! Codecs accept these types by their [`Kind`] or by their name.
+//! Enum labels and `citext` are accepted as strings,
+//! `hstore` as a [`HashMap`], `vector` and `halfvec` as `Vec<f32>`,
+//! and domains by the codecs of their base types.
+//! Names are matched regardless of schema, so a type of the same name
+//! that was not created by the extension is accepted as well,
+//! and its values are misinterpreted or fail to decode.
+//! Other such types, for instance an enum type as a Rust enum,
+//! can be converted by implementing [`ToSql`] and [`FromSql`]
+//! for a Rust type that accepts the name of the type.
+//!
+//! [`HashMap`]: `std::collections::HashMap`
+//! [`PgClient::lookup_type`]: `crate::PgClient::lookup_type`
+//! [`PgClient::query`]: `crate::PgClient::query`
+
+pub use self::{
+    array::*,
+    bit_string::*,
+    composite::*,
+    datetime::*,
+    geometric::*,
+    literal::*,
+    money::*,
+    network::*,
+    numeric::*,
+    pg_type::*,
+    range::*,
+    system::*,
+    text_search::*,
+    uuid::*,
+    vector::*,
+};
+
+#[cfg(feature = "postgis")]
+pub use self::postgis::*;
+
+#[cfg(feature = "serde_json")]
+pub use self::json_serde_json::Json;
+
+use {
+    crate::{Error, Result, protocol::FormatCode},
+    std::{any::type_name, str},
+};
+
+mod array;
+mod bit_string;
+mod composite;
+mod datetime;
+mod datetime_text;
+mod geometric;
+mod hstore;
+mod json;
+mod literal;
+mod money;
+mod network;
+mod numeric;
+mod pg_type;
+mod range;
+mod scalar;
+mod system;
+mod text;
+mod text_search;
+mod uuid;
+mod vector;
+
+#[cfg(feature = "chrono")]
+mod datetime_chrono;
+
+#[cfg(feature = "time")]
+mod datetime_time;
+
+#[cfg(feature = "serde_json")]
+mod json_serde_json;
+
+#[cfg(feature = "bigdecimal")]
+mod numeric_bigdecimal;
+
+#[cfg(feature = "rust_decimal")]
+mod numeric_rust_decimal;
+
+#[cfg(feature = "postgis")]
+mod postgis;
+
+#[cfg(feature = "uuid")]
+mod uuid_uuid;
+
+#[cfg(test)]
+mod tests;
+
+/// Whether a value encoded by [`ToSql`] is SQL NULL.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IsNull
+{
+    #[allow(missing_docs)]
+    Yes,
+    #[allow(missing_docs)]
+    No,
+}
+
+/// Information about a value being converted.
+#[derive(Clone, Copy, Debug)]
+pub struct Context<'a>
+{
+    ty: &'a Type,
+    format_code: FormatCode,
+    session_parameters: &'a SessionParameters,
+}
+
+impl<'a> Context<'a>
+{
+    /// Create a context for a value of the given type and format.
+    ///
+    /// The session parameters are [`SessionParameters::DEFAULT`].
+    pub fn new(ty: &'a Type, format_code: FormatCode) -> Self
+    {
+        let session_parameters = &SessionParameters::DEFAULT;
+        Self{ty, format_code, session_parameters}
+    }
+
+    /// Replace the session parameters.
+    pub fn with_session_parameters(
+        self,
+        session_parameters: &'a SessionParameters,
+    ) -> Self
+    {
+        Self{session_parameters, ..self}
+    }
+
+    /// The PostgreSQL type of the value.
+    pub fn ty(&self) -> &'a Type
+    {
+        self.ty
+    }
+
+    /// The format in which the value is represented.
+    pub fn format_code(&self) -> FormatCode
+    {
+        self.format_code
+    }
+
+    /// The session parameters in effect.
+    pub fn session_parameters(&self) -> &'a SessionParameters
+    {
+        self.session_parameters
+    }
+}
+
+/// Session parameters that affect the representation of values.
+///
+/// The server reports these using `ParameterStatus` messages.
+/// [`PgClient`] keeps track of them, and attaches them to query results,
+/// so that values are converted according to the settings
+/// that were in effect when they were received.
+///
+/// [`PgClient`]: `crate::PgClient`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionParameters
+{
+    integer_datetimes: bool,
+    date_style: DateStyle,
+    date_order: DateOrder,
+    interval_style: IntervalStyle,
+}
+
+/// The output format of `DateStyle`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DateStyle
+{
+    /// `2024-02-09 07:08:09-08`.
+    Iso,
+    /// `02/09/2024 07:08:09 PST`.
+    Sql,
+    /// `Fri Feb 09 07:08:09 2024 PST`.
+    Postgres,
+    /// `09.02.2024 07:08:09 PST`.
+    German,
+}
+
+/// The field order of `DateStyle`, which determines whether
+/// the month or the day comes first in dates in SQL
+/// and Postgres formats.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DateOrder
+{
+    /// Month, day, year.
+    Mdy,
+    /// Day, month, year.
+    Dmy,
+    /// Year, month, day.
+    /// This is output like [`Mdy`][`Self::Mdy`].
+    Ymd,
+}
+
+/// The output format of `IntervalStyle`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum IntervalStyle
+{
+    /// `1 year 2 mons -3 days +04:05:06`.
+    Postgres,
+    /// `@ 1 year 2 mons -3 days 4 hours 5 mins 6 secs`.
+    PostgresVerbose,
+    /// `+1-2 -3 +4:05:06`.
+    SqlStandard,
+    /// `P1Y2M-3DT4H5M6S`.
+    Iso8601,
+}
+
+impl SessionParameters
+{
+    /// The parameters of a server with the default configuration.
+    pub const DEFAULT: Self = Self{
+        integer_datetimes: true,
+        date_style: DateStyle::Iso,
+        date_order: DateOrder::Mdy,
+        interval_style: IntervalStyle::Postgres,
+    };
+
+    /// Update a parameter given its name and value
+    /// as reported by `ParameterStatus`.
+    ///
+    /// Parameters that do not affect the representation
+    /// of values are ignored, as are values that are not recognized.
+    pub fn set(&mut self, name: &[u8], value: &[u8])
+    {
+        match name {
+            b"integer_datetimes" =>
+                self.integer_datetimes = value == b"on",
+            b"DateStyle" =>
+                for part in value.split(|&b| b == b',') {
+                    match part.trim_ascii() {
+                        b"ISO" => self.date_style = DateStyle::Iso,
+                        b"SQL" => self.date_style = DateStyle::Sql,
+                        b"Postgres" => self.date_style = DateStyle::Postgres,
+                        b"German" => self.date_style = DateStyle::German,
+                        b"MDY" => self.date_order = DateOrder::Mdy,
+                        b"DMY" => self.date_order = DateOrder::Dmy,
+                        b"YMD" => self.date_order = DateOrder::Ymd,
+                        _ => (),
+                    }
+                },
+            b"IntervalStyle" =>
+                self.interval_style = match value {
+                    b"postgres" => IntervalStyle::Postgres,
+                    b"postgres_verbose" => IntervalStyle::PostgresVerbose,
+                    b"sql_standard" => IntervalStyle::SqlStandard,
+                    b"iso_8601" => IntervalStyle::Iso8601,
+                    _ => self.interval_style,
+                },
+            _ => (),
+        }
+    }
+
+    /// The value of `integer_datetimes`, which is whether the server
+    /// represents date and time values as integers rather than as
+    /// floating-point numbers. Only integers are supported,
+    /// which all servers since PostgreSQL 10 use.
+    pub fn integer_datetimes(&self) -> bool
+    {
+        self.integer_datetimes
+    }
+
+    /// The output format of `DateStyle`, which determines
+    /// the text format of dates and timestamps.
+    pub fn date_style(&self) -> DateStyle
+    {
+        self.date_style
+    }
+
+    /// The field order of `DateStyle`.
+    pub fn date_order(&self) -> DateOrder
+    {
+        self.date_order
+    }
+
+    /// The value of `IntervalStyle`,
+    /// which determines the text format of intervals.
+    pub fn interval_style(&self) -> IntervalStyle
+    {
+        self.interval_style
+    }
+}
+
+impl Default for SessionParameters
+{
+    fn default() -> Self
+    {
+        Self::DEFAULT
+    }
+}
+
+/// Rust type that can be encoded as a PostgreSQL value.
+pub trait ToSql
+{
+    /// Whether values can be encoded as values of the given type.
+    fn accepts(ty: &Type) -> bool
+        where Self: Sized;
+
+    /// Encode the value into a buffer.
+    ///
+    /// The value must be encoded in the format given by the context.
+    /// If [`IsNull::Yes`] is returned, anything written is discarded.
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>;
+}
+
+/// Type-erased [`ToSql`], for passing parameters of different types.
+///
+/// This is implemented for every type that implements [`ToSql`],
+/// so parameters can be given as `&[&1i32, &"text"]`.
+pub trait Parameter
+{
+    /// See [`ToSql::accepts`].
+    fn accepts_type(&self, ty: &Type) -> bool;
+
+    /// See [`ToSql::to_sql`].
+    fn to_sql_parameter(&self, cx: &Context, buf: &mut Vec<u8>)
+        -> Result<IsNull>;
+
+    /// The name of the Rust type, for use in error messages.
+    fn rust_type_name(&self) -> &'static str;
+}
+
+impl<T> Parameter for T
+    where T: ToSql
+{
+    fn accepts_type(&self, ty: &Type) -> bool
+    {
+        T::accepts(ty)
+    }
+
+    fn to_sql_parameter(&self, cx: &Context, buf: &mut Vec<u8>)
+        -> Result<IsNull>
+    {
+        self.to_sql(cx, buf)
+    }
+
+    fn rust_type_name(&self) -> &'static str
+    {
+        type_name::<T>()
+    }
+}
+
+/// Rust type that can be decoded from a PostgreSQL value.
+///
+/// The lifetime `'a` allows borrowing from the value,
+/// for instance when decoding `text` into `&'a str`.
+pub trait FromSql<'a>: Sized
+{
+    /// Whether values of the given type can be decoded.
+    fn accepts(ty: &Type) -> bool;
+
+    /// Decode a value.
+    ///
+    /// The value is in the format given by the context.
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>;
+
+    /// Decode SQL NULL.
+    ///
+    /// The default implementation returns [`Error::UnexpectedNull`].
+    fn from_sql_null(cx: &Context) -> Result<Self>
+    {
+        let _ = cx;
+        Err(Error::UnexpectedNull)
+    }
+
+    /// Decode a value from a buffer that does not live for `'a`.
+    ///
+    /// This is used for values that must be unescaped before they
+    /// can be decoded, such as quoted elements of arrays in text format.
+    /// Implementations for types that do not borrow from the value
+    /// must forward to [`from_sql`][`Self::from_sql`], and those for
+    /// containers must decode their elements with this method.
+    /// The default implementation returns [`Error::Conversion`],
+    /// as it cannot know whether `Self` borrows from the value.
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        let _ = raw;
+        let message = format!("{} value cannot be borrowed as {}, \
+                               as it must be unescaped",
+                              cx.ty(), type_name::<Self>());
+        Err(Error::Conversion(message.into()))
+    }
+}
+
+/// Error for a value that could not be decoded.
+pub (crate) fn invalid_value(cx: &Context, reason: &str) -> Error
+{
+    let message = format!("invalid {} value: {reason}", cx.ty());
+    Error::Conversion(message.into())
+}
+
+/// Error for a value that the Rust type cannot represent.
+#[cfg_attr(
+    not(any(
+        feature = "bigdecimal",
+        feature = "chrono",
+        feature = "rust_decimal",
+        feature = "time",
+    )),
+    allow(dead_code),
+)]
+pub (crate) fn out_of_range<T>(cx: &Context) -> Error
+{
+    let message = format!("{} value out of range for {}",
+                          cx.ty(), type_name::<T>());
+    Error::Conversion(message.into())
+}
+
+/// Decode a binary value that must have a length of `N` bytes.
+pub (crate) fn fixed_length<const N: usize>(cx: &Context, raw: &[u8])
+    -> Result<[u8; N]>
+{
+    raw.try_into().map_err(|_| {
+        let message = format!("invalid length for {}: \
+                               expected {N} bytes, got {}",
+                              cx.ty(), raw.len());
+        Error::Conversion(message.into())
+    })
+}
+
+/// Decode a value in text format, which must be valid UTF-8.
+///
+/// This assumes that `client_encoding` is `UTF8`.
+pub (crate) fn text_value<'a>(cx: &Context, raw: &'a [u8]) -> Result<&'a str>
+{
+    str::from_utf8(raw).map_err(|_| invalid_value(cx, "not UTF-8"))
+}
+
+/// The context for the type if it is accepted,
+/// or else for the nearest base type of the domain that is accepted.
+///
+/// Values of a domain are in the format of its base type,
+/// so codecs for the base type can be used for the domain,
+/// unless there is a codec for the domain itself.
+fn accepted_context<'a, F>(cx: &Context<'a>, accepts: F) -> Option<Context<'a>>
+    where F: Fn(&Type) -> bool
+{
+    let ty = accepted_type(cx.ty(), accepts)?;
+    Some(Context{ty, ..*cx})
+}
+
+/// Whether the type or any base type of the domain is accepted.
+///
+/// Codecs for container types use this for their element types,
+/// so that containers of domains are accepted like the domains are.
+pub (crate) fn is_accepted<F>(ty: &Type, accepts: F) -> bool
+    where F: Fn(&Type) -> bool
+{
+    accepted_type(ty, accepts).is_some()
+}
+
+/// The type if it is accepted,
+/// or else the nearest base type of the domain that is accepted.
+fn accepted_type<F>(mut ty: &Type, accepts: F) -> Option<&Type>
+    where F: Fn(&Type) -> bool
+{
+    while !accepts(ty) {
+        match ty.kind() {
+            Kind::Domain(base_type) => ty = base_type,
+            _ => return None,
+        }
+    }
+    Some(ty)
+}
+
+/// Function that decodes an element of a container type,
+/// which is either [`FromSql::from_sql`]
+/// or [`FromSql::from_sql_temporary`].
+pub (crate) type Decode<'b, T> = fn(&Context, &'b [u8]) -> Result<T>;
+
+/// Decode a value or SQL NULL, checking that the type is accepted.
+pub (crate) fn from_sql_checked<'a, T>(cx: &Context, raw: Option<&'a [u8]>)
+    -> Result<T>
+    where T: FromSql<'a>
+{
+    from_sql_checked_with(cx, raw, T::from_sql)
+}
+
+/// Like [`from_sql_checked`], but decode the value
+/// with the given function.
+pub (crate) fn from_sql_checked_with<'a, 'b, T>(
+    cx: &Context,
+    raw: Option<&'b [u8]>,
+    decode: Decode<'b, T>,
+) -> Result<T>
+    where T: FromSql<'a>
+{
+    let Some(cx) = &accepted_context(cx, T::accepts) else {
+        return Err(Error::WrongType{
+            rust_type: type_name::<T>(),
+            pg_type: cx.ty().clone(),
+        });
+    };
+    match raw {
+        Some(raw) => decode(cx, raw),
+        None => T::from_sql_null(cx),
+    }
+}
+
+/// Encode a parameter, checking that the type is accepted.
+pub (crate) fn to_sql_checked(
+    parameter: &dyn Parameter,
+    cx: &Context,
+    buf: &mut Vec<u8>,
+) -> Result<IsNull>
+{
+    let accepts = |ty: &Type| parameter.accepts_type(ty);
+    let Some(cx) = &accepted_context(cx, accepts) else {
+        return Err(Error::WrongType{
+            rust_type: parameter.rust_type_name(),
+            pg_type: cx.ty().clone(),
+        });
+    };
+    parameter.to_sql_parameter(cx, buf)
+}
+
+impl<T> ToSql for &T
+    where T: ToSql
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        T::accepts(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        T::to_sql(self, cx, buf)
+    }
+}
+
+/// [`None`] is encoded as SQL NULL.
+impl<T> ToSql for Option<T>
+    where T: ToSql
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        T::accepts(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        match self {
+            Some(value) => value.to_sql(cx, buf),
+            None => Ok(IsNull::Yes),
+        }
+    }
+}
+
+/// SQL NULL is decoded as [`None`].
+impl<'a, T> FromSql<'a> for Option<T>
+    where T: FromSql<'a>
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        T::accepts(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        T::from_sql(cx, raw).map(Some)
+    }
+
+    fn from_sql_null(_cx: &Context) -> Result<Self>
+    {
+        Ok(None)
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        T::from_sql_temporary(cx, raw).map(Some)
+    }
+}
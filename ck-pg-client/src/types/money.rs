@@ -0,0 +1,137 @@
+//! Codec for `money`.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{
+        Context,
+        FromSql,
+        IsNull,
+        ToSql,
+        Type,
+        fixed_length,
+        invalid_value,
+        text_value,
+    },
+};
+
+/// Value of type `money`, which is an amount of currency.
+///
+/// The amount is in the smallest unit of the currency, such as cents.
+/// Which unit that is depends on the `lc_monetary` setting of the server:
+/// with two fraction digits, `Money(1234)` is displayed as `$12.34`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Money(pub i64);
+
+/// In binary format, the amount is sent as is.
+///
+/// In text format, values are formatted according to `lc_monetary`,
+/// as in `-$1,234.56` or `1.234,56 €`. Decoding ignores everything
+/// but the digits, which are the amount in the smallest unit,
+/// and the sign, which is a minus sign or enclosing parentheses.
+/// Encoding in text format is not supported,
+/// because the server would scale the amount by the fraction digits.
+impl ToSql for Money
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::MONEY
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        match cx.format_code() {
+            FormatCode::Binary => buf.extend_from_slice(&self.0.to_be_bytes()),
+            FormatCode::Text =>
+                return Err(invalid_value(cx, "text format not supported")),
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl<'a> FromSql<'a> for Money
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::MONEY
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        match cx.format_code() {
+            FormatCode::Binary =>
+                Ok(Self(i64::from_be_bytes(fixed_length(cx, raw)?))),
+            FormatCode::Text => parse_money(text_value(cx, raw)?)
+                .ok_or_else(|| invalid_value(cx, "malformed text")),
+        }
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+/// Parse the text format of `money`, regardless of `lc_monetary`.
+fn parse_money(s: &str) -> Option<Money>
+{
+    let negative = s.contains(['-', '(']);
+    let mut digits = s.chars().filter_map(|c| c.to_digit(10)).peekable();
+    digits.peek()?;
+    let amount = digits.try_fold(0u64, |amount, digit| {
+        amount.checked_mul(10)?.checked_add(u64::from(digit))
+    })?;
+    if negative {
+        0i64.checked_sub_unsigned(amount).map(Money)
+    } else {
+        i64::try_from(amount).ok().map(Money)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::tests::{fuzz_from_sql, round_trip},
+        super::*,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<Money>(&Type::MONEY);
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        for value in [0, -1, 1234, i64::MIN, i64::MAX] {
+            round_trip(&Type::MONEY, FormatCode::Binary, &Money(value));
+        }
+    }
+
+    #[test]
+    fn text()
+    {
+        let cx = Context::new(&Type::MONEY, FormatCode::Text);
+        assert!(Money(1).to_sql(&cx, &mut Vec::new()).is_err());
+
+        for (text, expected) in [
+            ("$0.00", 0),
+            ("$1,234.56", 123456),
+            ("-$0.01", -1),
+            ("($5.00)", -500),
+            ("1.234,56 €", 123456),
+            ("-1 234", -1234),
+            ("$92,233,720,368,547,758.07", i64::MAX),
+            ("-$92,233,720,368,547,758.08", i64::MIN),
+        ] {
+            let actual = Money::from_sql(&cx, text.as_bytes()).unwrap();
+            assert_eq!(actual, Money(expected), "{text}");
+        }
+
+        for text in ["", "$", "$92,233,720,368,547,758.08",
+                     "-$184,467,440,737,095,516.16"] {
+            assert!(Money::from_sql(&cx, text.as_bytes()).is_err(), "{text}");
+        }
+    }
+}
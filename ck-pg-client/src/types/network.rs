@@ -0,0 +1,532 @@
+//! Codecs for `inet`, `cidr`, `macaddr`, and `macaddr8`.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{
+        Context,
+        FromSql,
+        IsNull,
+        ToSql,
+        Type,
+        fixed_length,
+        invalid_value,
+        text_value,
+    },
+    std::{
+        fmt,
+        io::Write,
+        net::{IpAddr, Ipv4Addr, Ipv6Addr},
+        str::FromStr,
+    },
+    thiserror::Error,
+};
+
+/// Address family of IPv4 addresses in the binary format.
+const PGSQL_AF_INET: u8 = 2;
+
+/// Address family of IPv6 addresses in the binary format.
+const PGSQL_AF_INET6: u8 = 3;
+
+/// Value of type `inet`, which is a host address with an optional netmask.
+///
+/// The netmask is given as a prefix length, such as 24 in
+/// `192.168.0.1/24`. Unlike [`Cidr`], the address may have bits set
+/// to the right of the netmask. The address is a host address
+/// if the prefix length is the length of the address.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Inet
+{
+    address: IpAddr,
+    prefix_length: u8,
+}
+
+/// Value of type `cidr`, which is a network address.
+///
+/// Unlike [`Inet`], the address has no bits set
+/// to the right of the netmask.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Cidr
+{
+    address: IpAddr,
+    prefix_length: u8,
+}
+
+/// Value of type `macaddr`, which is a MAC address in EUI-48 format.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MacAddr(pub [u8; 6]);
+
+/// Value of type `macaddr8`, which is a MAC address in EUI-64 format.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MacAddr8(pub [u8; 8]);
+
+/// Error returned when parsing an [`Inet`], [`Cidr`],
+/// [`MacAddr`], or [`MacAddr8`] fails.
+#[derive(Clone, Debug, Error)]
+#[error("invalid network address literal")]
+pub struct ParseNetworkError(());
+
+impl Inet
+{
+    /// Create an address with a netmask of the given prefix length.
+    ///
+    /// Returns [`None`] if the prefix length exceeds
+    /// the length of the address.
+    pub fn new(address: IpAddr, prefix_length: u8) -> Option<Self>
+    {
+        (prefix_length <= max_prefix_length(address))
+            .then_some(Self{address, prefix_length})
+    }
+
+    /// The address.
+    pub fn address(self) -> IpAddr
+    {
+        self.address
+    }
+
+    /// The length of the netmask in bits.
+    pub fn prefix_length(self) -> u8
+    {
+        self.prefix_length
+    }
+}
+
+impl From<IpAddr> for Inet
+{
+    /// Create a host address, whose netmask covers the entire address.
+    fn from(address: IpAddr) -> Self
+    {
+        Self{address, prefix_length: max_prefix_length(address)}
+    }
+}
+
+impl Cidr
+{
+    /// Create a network address with the given prefix length.
+    ///
+    /// Returns [`None`] if the prefix length exceeds the length
+    /// of the address, or if the address has bits set
+    /// to the right of the netmask.
+    pub fn new(address: IpAddr, prefix_length: u8) -> Option<Self>
+    {
+        Inet::new(address, prefix_length)?;
+        (host_bits(address, prefix_length) == 0)
+            .then_some(Self{address, prefix_length})
+    }
+
+    /// The network address.
+    pub fn address(self) -> IpAddr
+    {
+        self.address
+    }
+
+    /// The length of the netmask in bits.
+    pub fn prefix_length(self) -> u8
+    {
+        self.prefix_length
+    }
+}
+
+/// The length of the address in bits.
+fn max_prefix_length(address: IpAddr) -> u8
+{
+    match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+/// The bits of the address to the right of the netmask.
+fn host_bits(address: IpAddr, prefix_length: u8) -> u128
+{
+    let bits = match address {
+        IpAddr::V4(address) => u128::from(u32::from(address)),
+        IpAddr::V6(address) => u128::from(address),
+    };
+    let host_length =
+        u32::from(max_prefix_length(address).saturating_sub(prefix_length));
+    bits & u128::MAX.checked_shr(128 - host_length).unwrap_or(0)
+}
+
+impl fmt::Display for Inet
+{
+    /// Format the address the way PostgreSQL does,
+    /// omitting the prefix length of host addresses.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        if self.prefix_length == max_prefix_length(self.address) {
+            write!(f, "{}", self.address)
+        } else {
+            write!(f, "{}/{}", self.address, self.prefix_length)
+        }
+    }
+}
+
+impl fmt::Display for Cidr
+{
+    /// Format the address the way PostgreSQL does.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}/{}", self.address, self.prefix_length)
+    }
+}
+
+impl FromStr for Inet
+{
+    type Err = ParseNetworkError;
+
+    /// Parse an address with an optional prefix length,
+    /// such as `192.168.0.1/24` or `::1`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        let (address, prefix_length) = match s.split_once('/') {
+            Some((address, prefix_length)) =>
+                (address, Some(parse_prefix_length(prefix_length)?)),
+            None => (s, None),
+        };
+        let address = address.parse().map_err(|_| ParseNetworkError(()))?;
+        match prefix_length {
+            Some(prefix_length) => Self::new(address, prefix_length)
+                .ok_or(ParseNetworkError(())),
+            None => Ok(Self::from(address)),
+        }
+    }
+}
+
+impl FromStr for Cidr
+{
+    type Err = ParseNetworkError;
+
+    /// Parse an address with a prefix length, such as `10.0.0.0/8`.
+    ///
+    /// Unlike PostgreSQL, this does not infer
+    /// a missing prefix length from the address.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        let (address, prefix_length) =
+            s.split_once('/').ok_or(ParseNetworkError(()))?;
+        let address = address.parse().map_err(|_| ParseNetworkError(()))?;
+        Self::new(address, parse_prefix_length(prefix_length)?)
+            .ok_or(ParseNetworkError(()))
+    }
+}
+
+fn parse_prefix_length(s: &str)
+    -> std::result::Result<u8, ParseNetworkError>
+{
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseNetworkError(()));
+    }
+    s.parse().map_err(|_| ParseNetworkError(()))
+}
+
+impl fmt::Display for MacAddr
+{
+    /// Format the address the way PostgreSQL does, as six
+    /// colon-separated pairs of lowercase hexadecimal digits.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write_hex_bytes(f, &self.0)
+    }
+}
+
+impl fmt::Display for MacAddr8
+{
+    /// Format the address the way PostgreSQL does, as eight
+    /// colon-separated pairs of lowercase hexadecimal digits.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write_hex_bytes(f, &self.0)
+    }
+}
+
+impl FromStr for MacAddr
+{
+    type Err = ParseNetworkError;
+
+    /// Parse colon-separated pairs of hexadecimal digits,
+    /// such as `08:00:2b:01:02:03`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        parse_hex_bytes(s).map(Self)
+    }
+}
+
+impl FromStr for MacAddr8
+{
+    type Err = ParseNetworkError;
+
+    /// Parse colon-separated pairs of hexadecimal digits,
+    /// such as `08:00:2b:01:02:03:04:05`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        parse_hex_bytes(s).map(Self)
+    }
+}
+
+fn write_hex_bytes(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result
+{
+    for (i, byte) in bytes.iter().enumerate() {
+        if i != 0 {
+            f.write_str(":")?;
+        }
+        write!(f, "{byte:02x}")?;
+    }
+    Ok(())
+}
+
+fn parse_hex_bytes<const N: usize>(s: &str)
+    -> std::result::Result<[u8; N], ParseNetworkError>
+{
+    let mut bytes = [0; N];
+    let mut pairs = s.split(':');
+    for byte in &mut bytes {
+        let pair = pairs.next().ok_or(ParseNetworkError(()))?;
+        if pair.len() != 2 || !pair.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseNetworkError(()));
+        }
+        *byte = u8::from_str_radix(pair, 16)
+            .map_err(|_| ParseNetworkError(()))?;
+    }
+    match pairs.next() {
+        Some(_) => Err(ParseNetworkError(())),
+        None => Ok(bytes),
+    }
+}
+
+/// Write an address in the binary format of `inet` and `cidr`,
+/// which consists of the address family, the prefix length,
+/// whether the value is a `cidr`, the length of the address,
+/// and the address.
+fn write_network(buf: &mut Vec<u8>, address: IpAddr, prefix_length: u8,
+                 is_cidr: bool)
+{
+    match address {
+        IpAddr::V4(address) => {
+            buf.extend_from_slice(&[PGSQL_AF_INET, prefix_length,
+                                    u8::from(is_cidr), 4]);
+            buf.extend_from_slice(&address.octets());
+        },
+        IpAddr::V6(address) => {
+            buf.extend_from_slice(&[PGSQL_AF_INET6, prefix_length,
+                                    u8::from(is_cidr), 16]);
+            buf.extend_from_slice(&address.octets());
+        },
+    }
+}
+
+/// Read an address in the binary format of `inet` and `cidr`.
+fn read_network(cx: &Context, raw: &[u8]) -> Result<Inet>
+{
+    let [family, prefix_length, _is_cidr, length, address @ ..] = raw
+        else { return Err(invalid_value(cx, "malformed binary value")) };
+    let address = match (*family, *length) {
+        (PGSQL_AF_INET, 4) =>
+            IpAddr::V4(Ipv4Addr::from(fixed_length::<4>(cx, address)?)),
+        (PGSQL_AF_INET6, 16) =>
+            IpAddr::V6(Ipv6Addr::from(fixed_length::<16>(cx, address)?)),
+        _ => return Err(invalid_value(cx, "unknown address family")),
+    };
+    Inet::new(address, *prefix_length)
+        .ok_or_else(|| invalid_value(cx, "prefix length out of range"))
+}
+
+/// Implement [`ToSql`] and [`FromSql`] for a network address type.
+///
+/// The text format is what `Display` produces and `FromStr` parses.
+macro_rules! network_codec
+{
+    ($rust:ty, $pg:ident, $encode:expr, $decode:expr) => {
+        impl ToSql for $rust
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                *ty == Type::$pg
+            }
+
+            fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>)
+                -> Result<IsNull>
+            {
+                match cx.format_code() {
+                    FormatCode::Binary => $encode(self, buf),
+                    FormatCode::Text => write!(buf, "{self}")?,
+                }
+                Ok(IsNull::No)
+            }
+        }
+
+        impl<'a> FromSql<'a> for $rust
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                *ty == Type::$pg
+            }
+
+            fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+            {
+                match cx.format_code() {
+                    FormatCode::Binary => $decode(cx, raw),
+                    FormatCode::Text => text_value(cx, raw)?.parse()
+                        .map_err(|_| invalid_value(cx, "malformed text")),
+                }
+            }
+
+            fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+            {
+                <Self as FromSql>::from_sql(cx, raw)
+            }
+        }
+    };
+}
+
+network_codec!(
+    Inet, INET,
+    |value: &Inet, buf| {
+        write_network(buf, value.address, value.prefix_length, false);
+    },
+    read_network
+);
+
+network_codec!(
+    Cidr, CIDR,
+    |value: &Cidr, buf| {
+        write_network(buf, value.address, value.prefix_length, true);
+    },
+    |cx, raw| {
+        let inet = read_network(cx, raw)?;
+        Cidr::new(inet.address, inet.prefix_length)
+            .ok_or_else(|| invalid_value(cx, "bits set right of netmask"))
+    }
+);
+
+network_codec!(
+    MacAddr, MACADDR,
+    |value: &MacAddr, buf: &mut Vec<u8>| buf.extend_from_slice(&value.0),
+    |cx, raw| fixed_length(cx, raw).map(MacAddr)
+);
+
+network_codec!(
+    MacAddr8, MACADDR8,
+    |value: &MacAddr8, buf: &mut Vec<u8>| buf.extend_from_slice(&value.0),
+    |cx, raw| fixed_length(cx, raw).map(MacAddr8)
+);
+
+/// A host address as an `inet`, or as a `cidr` with a netmask
+/// that covers the entire address.
+impl ToSql for IpAddr
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::INET || *ty == Type::CIDR
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        let inet = Inet::from(*self);
+        match cx.format_code() {
+            FormatCode::Binary => write_network(buf, inet.address,
+                                                inet.prefix_length,
+                                                *cx.ty() == Type::CIDR),
+            FormatCode::Text => write!(buf, "{self}")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+/// Only host addresses can be decoded; decode values with
+/// a netmask as [`Inet`] or [`Cidr`] instead.
+impl<'a> FromSql<'a> for IpAddr
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::INET || *ty == Type::CIDR
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        let inet = match cx.format_code() {
+            FormatCode::Binary => read_network(cx, raw)?,
+            FormatCode::Text => text_value(cx, raw)?
+                .parse::<Inet>()
+                .map_err(|_| invalid_value(cx, "malformed text"))?,
+        };
+        if inet.prefix_length != max_prefix_length(inet.address) {
+            return Err(invalid_value(cx, "not a host address"));
+        }
+        Ok(inet.address)
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::tests::{fuzz_from_sql, round_trip},
+        super::*,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<Inet>(&Type::INET);
+        fuzz_from_sql::<Cidr>(&Type::CIDR);
+        fuzz_from_sql::<IpAddr>(&Type::INET);
+        fuzz_from_sql::<MacAddr>(&Type::MACADDR);
+        fuzz_from_sql::<MacAddr8>(&Type::MACADDR8);
+    }
+
+    #[test]
+    fn parse()
+    {
+        let inet = |s: &str| s.parse::<Inet>().ok().map(|i| i.to_string());
+        assert_eq!(inet("192.168.0.1/24").as_deref(), Some("192.168.0.1/24"));
+        assert_eq!(inet("192.168.0.1/32").as_deref(), Some("192.168.0.1"));
+        assert_eq!(inet("::1").as_deref(), Some("::1"));
+        assert_eq!(inet("::1/129"), None);
+        assert_eq!(inet("::1/+1"), None);
+        assert_eq!(inet("::1/"), None);
+
+        let cidr = |s: &str| s.parse::<Cidr>().ok().map(|c| c.to_string());
+        assert_eq!(cidr("10.0.0.0/8").as_deref(), Some("10.0.0.0/8"));
+        assert_eq!(cidr("10.0.0.1/32").as_deref(), Some("10.0.0.1/32"));
+        assert_eq!(cidr("2001:db8::/32").as_deref(), Some("2001:db8::/32"));
+        assert_eq!(cidr("::/0").as_deref(), Some("::/0"));
+        assert_eq!(cidr("10.0.0.1/8"), None);
+        assert_eq!(cidr("10.0.0.0"), None);
+
+        let mac = "08:00:2b:01:02:0A".parse::<MacAddr>().unwrap();
+        assert_eq!(mac, MacAddr([8, 0, 0x2b, 1, 2, 10]));
+        assert_eq!(mac.to_string(), "08:00:2b:01:02:0a");
+        assert!("08:00:2b:01:02".parse::<MacAddr>().is_err());
+        assert!("08:00:2b:01:02:03:04".parse::<MacAddr>().is_err());
+        assert!("08:00:2b:01:02:+3".parse::<MacAddr>().is_err());
+        let mac8 = "08:00:2b:01:02:03:04:05".parse::<MacAddr8>().unwrap();
+        assert_eq!(mac8.to_string(), "08:00:2b:01:02:03:04:05");
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        let v4 = IpAddr::from([192, 168, 0, 1]);
+        let v6 = IpAddr::from([0x2001, 0xdb8, 0, 0, 0, 0, 0, 1]);
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for (address, prefix_length) in [(v4, 24), (v4, 32), (v6, 64),
+                                             (v6, 128), (v6, 0)] {
+                let inet = Inet::new(address, prefix_length).unwrap();
+                round_trip(&Type::INET, format_code, &inet);
+            }
+            round_trip(&Type::INET, format_code, &v4);
+            round_trip(&Type::CIDR, format_code, &v6);
+            let cidr = Cidr::new(IpAddr::from([10, 0, 0, 0]), 8).unwrap();
+            round_trip(&Type::CIDR, format_code, &cidr);
+            let mac = MacAddr([8, 0, 0x2b, 1, 2, 3]);
+            round_trip(&Type::MACADDR, format_code, &mac);
+            let mac8 = MacAddr8([8, 0, 0x2b, 1, 2, 3, 4, 0xff]);
+            round_trip(&Type::MACADDR8, format_code, &mac8);
+        }
+    }
+}
@@ -0,0 +1,507 @@
+//! Codec for `numeric`.
+
+use {
+    crate::{Error, Result, protocol::FormatCode},
+    super::{Context, FromSql, IsNull, ToSql, Type, invalid_value, text_value},
+    std::{fmt, io::Write, str::FromStr},
+    thiserror::Error,
+};
+
+/// Value of type `numeric`.
+///
+/// Unlike floating-point numbers, `numeric` values are exact,
+/// and they remember their display scale: `1.5` and `1.50` are
+/// different values, even though they compare equal in PostgreSQL.
+/// Hence this type is compared structurally rather than numerically.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Numeric
+{
+    /// Not-a-number, which PostgreSQL considers equal to itself.
+    NaN,
+
+    /// Positive infinity.
+    Infinity,
+
+    /// Negative infinity.
+    NegativeInfinity,
+
+    /// Finite value.
+    Finite(Decimal),
+}
+
+/// Finite value of type `numeric`.
+///
+/// The value is represented as a sequence of decimal digits
+/// without leading zeros, and a scale, which is the number of
+/// those digits that are after the decimal point. For instance,
+/// `-12.50` has the digits `[1, 2, 5, 0]` and the scale 2.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Decimal
+{
+    negative: bool,
+    digits: Vec<u8>,
+    scale: u16,
+}
+
+/// Error returned when parsing a [`Numeric`] fails.
+#[derive(Clone, Debug, Error)]
+#[error("invalid numeric literal")]
+pub struct ParseNumericError(());
+
+impl Decimal
+{
+    /// The greatest scale that PostgreSQL supports.
+    pub const MAX_SCALE: u16 = 0x3FFF;
+
+    /// The greatest number of digits before the decimal point
+    /// that PostgreSQL supports.
+    pub const MAX_INTEGER_DIGITS: usize = 131072;
+
+    /// Create a decimal from its sign, digits, and scale.
+    ///
+    /// Each digit must be in the range `0 ..= 9`,
+    /// and the scale must not exceed [`MAX_SCALE`].
+    /// Leading zeros are removed, and zero is never negative.
+    ///
+    /// [`MAX_SCALE`]: `Self::MAX_SCALE`
+    pub fn new(negative: bool, mut digits: Vec<u8>, scale: u16)
+        -> Option<Self>
+    {
+        if scale > Self::MAX_SCALE || digits.iter().any(|&d| d > 9) {
+            return None;
+        }
+        let leading_zeros = digits.iter().take_while(|&&d| d == 0).count();
+        digits.drain(.. leading_zeros);
+        let negative = negative && !digits.is_empty();
+        Some(Self{negative, digits, scale})
+    }
+
+    /// Whether the value is less than zero.
+    pub fn is_negative(&self) -> bool
+    {
+        self.negative
+    }
+
+    /// The digits, without leading zeros; empty for zero.
+    pub fn digits(&self) -> &[u8]
+    {
+        &self.digits
+    }
+
+    /// The number of digits after the decimal point.
+    pub fn scale(&self) -> u16
+    {
+        self.scale
+    }
+}
+
+impl From<Decimal> for Numeric
+{
+    fn from(other: Decimal) -> Self
+    {
+        Self::Finite(other)
+    }
+}
+
+impl fmt::Display for Decimal
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        let scale = usize::from(self.scale);
+
+        if self.negative {
+            f.write_str("-")?;
+        }
+
+        if self.digits.len() > scale {
+            let (integer, fraction) =
+                self.digits.split_at(self.digits.len() - scale);
+            integer.iter().try_for_each(|d| write!(f, "{d}"))?;
+            if !fraction.is_empty() {
+                f.write_str(".")?;
+                fraction.iter().try_for_each(|d| write!(f, "{d}"))?;
+            }
+        } else {
+            f.write_str("0")?;
+            if scale != 0 {
+                f.write_str(".")?;
+                for _ in self.digits.len() .. scale {
+                    f.write_str("0")?;
+                }
+                self.digits.iter().try_for_each(|d| write!(f, "{d}"))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Numeric
+{
+    /// Format the value the way PostgreSQL does.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self {
+            Self::NaN => f.write_str("NaN"),
+            Self::Infinity => f.write_str("Infinity"),
+            Self::NegativeInfinity => f.write_str("-Infinity"),
+            Self::Finite(decimal) => decimal.fmt(f),
+        }
+    }
+}
+
+impl FromStr for Numeric
+{
+    type Err = ParseNumericError;
+
+    /// Parse a value the way PostgreSQL does.
+    ///
+    /// Besides the output of [`Display`][`fmt::Display`], this accepts
+    /// exponents (`1.5e3`), explicit plus signs, surrounding whitespace,
+    /// and `inf` as an abbreviation of `Infinity`, ignoring case.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        let s = s.trim();
+        for (name, value) in [
+            ("nan", Self::NaN),
+            ("infinity", Self::Infinity),
+            ("+infinity", Self::Infinity),
+            ("inf", Self::Infinity),
+            ("+inf", Self::Infinity),
+            ("-infinity", Self::NegativeInfinity),
+            ("-inf", Self::NegativeInfinity),
+        ] {
+            if s.eq_ignore_ascii_case(name) {
+                return Ok(value);
+            }
+        }
+        Decimal::from_str(s).map(Self::Finite)
+    }
+}
+
+impl FromStr for Decimal
+{
+    type Err = ParseNumericError;
+
+    /// Parse a finite value. See [`Numeric::from_str`].
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        let s = s.trim();
+
+        let (negative, s) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1 ..]),
+            Some(b'+') => (false, &s[1 ..]),
+            _ => (false, s),
+        };
+
+        let (mantissa, exponent) = match s.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) =>
+                (mantissa, exponent.parse::<i64>().ok()
+                    .ok_or(ParseNumericError(()))?),
+            None => (s, 0),
+        };
+
+        let (integer, fraction) =
+            mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if integer.is_empty() && fraction.is_empty()
+            || !is_digits(integer) || !is_digits(fraction) {
+            return Err(ParseNumericError(()));
+        }
+
+        let mut digits: Vec<u8> = integer.bytes().chain(fraction.bytes())
+            .map(|b| b - b'0')
+            .collect();
+
+        // The exponent moves the decimal point; trailing zeros are
+        // added if it moves past the last digit, like PostgreSQL does.
+        let max_integer_digits = Decimal::MAX_INTEGER_DIGITS as i64;
+        let scale = (fraction.len() as i64).checked_sub(exponent)
+            .filter(|scale| {
+                let max_scale = i64::from(Decimal::MAX_SCALE);
+                (-max_integer_digits ..= max_scale).contains(scale)
+            })
+            .ok_or(ParseNumericError(()))?;
+        if scale < 0 {
+            digits.resize(digits.len() + scale.unsigned_abs() as usize, 0);
+        }
+        let scale = u16::try_from(scale.max(0)).unwrap_or(u16::MAX);
+
+        Decimal::new(negative, digits, scale).ok_or(ParseNumericError(()))
+    }
+}
+
+/// Values of the `sign` field of the binary format.
+const NUMERIC_POS: u16 = 0x0000;
+const NUMERIC_NEG: u16 = 0x4000;
+const NUMERIC_NAN: u16 = 0xC000;
+const NUMERIC_PINF: u16 = 0xD000;
+const NUMERIC_NINF: u16 = 0xF000;
+
+/// The base of the digits of the binary format.
+const NBASE: u16 = 10000;
+
+/// The number of decimal digits in each digit of the binary format.
+const DEC_DIGITS: usize = 4;
+
+impl ToSql for Numeric
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::NUMERIC
+    }
+
+    /// The binary format consists of the number of base-10000 digits,
+    /// the weight of the first digit, the sign, the display scale,
+    /// and the base-10000 digits, all as 16-bit integers.
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        if cx.format_code() == FormatCode::Text {
+            write!(buf, "{self}")?;
+            return Ok(IsNull::No);
+        }
+
+        let decimal = match self {
+            Self::NaN => {
+                write_header(buf, 0, 0, NUMERIC_NAN, 0);
+                return Ok(IsNull::No);
+            },
+            Self::Infinity => {
+                write_header(buf, 0, 0, NUMERIC_PINF, 0);
+                return Ok(IsNull::No);
+            },
+            Self::NegativeInfinity => {
+                write_header(buf, 0, 0, NUMERIC_NINF, 0);
+                return Ok(IsNull::No);
+            },
+            Self::Finite(decimal) => decimal,
+        };
+
+        // Pad the decimal digits with zeros on the left, so that the
+        // decimal point falls on a boundary between base-10000 digits.
+        let digits = &decimal.digits;
+        let integer_digits = digits.len() as i64 - i64::from(decimal.scale);
+        let padding = (-integer_digits).rem_euclid(DEC_DIGITS as i64) as usize;
+        let decimal_digit = |i: usize| {
+            i.checked_sub(padding)
+                .and_then(|i| digits.get(i))
+                .map_or(0, |&d| u16::from(d))
+        };
+
+        let ndigits = (padding + digits.len()).div_ceil(DEC_DIGITS);
+        let mut nbase_digits: Vec<u16> = (0 .. ndigits)
+            .map(|i| (0 .. DEC_DIGITS)
+                .fold(0, |acc, j| acc * 10 + decimal_digit(i * DEC_DIGITS + j)))
+            .collect();
+        while nbase_digits.last() == Some(&0) {
+            nbase_digits.pop();
+        }
+
+        let weight = if nbase_digits.is_empty() { 0 } else {
+            (integer_digits + padding as i64) / DEC_DIGITS as i64 - 1
+        };
+        let (Ok(ndigits), Ok(weight)) =
+            (u16::try_from(nbase_digits.len()), i16::try_from(weight))
+            else { return Err(invalid_value(cx, "too many digits")) };
+
+        let sign = if decimal.negative { NUMERIC_NEG } else { NUMERIC_POS };
+        write_header(buf, ndigits, weight, sign, decimal.scale);
+        for nbase_digit in nbase_digits {
+            buf.extend_from_slice(&nbase_digit.to_be_bytes());
+        }
+
+        Ok(IsNull::No)
+    }
+}
+
+impl<'a> FromSql<'a> for Numeric
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::NUMERIC
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        if cx.format_code() == FormatCode::Text {
+            return text_value(cx, raw)?.parse()
+                .map_err(|err| Error::Conversion(Box::new(err)));
+        }
+
+        let invalid = || invalid_value(cx, "malformed binary value");
+
+        let (header, body) = raw.split_first_chunk::<8>().ok_or_else(invalid)?;
+        let field = |i: usize| u16::from_be_bytes([header[i], header[i + 1]]);
+        let ndigits = usize::from(field(0));
+        let weight = field(2) as i16;
+        let sign = field(4);
+        let dscale = field(6);
+
+        if body.len() != ndigits * 2 {
+            return Err(invalid());
+        }
+
+        let negative = match sign {
+            NUMERIC_POS => false,
+            NUMERIC_NEG => true,
+            NUMERIC_NAN | NUMERIC_PINF | NUMERIC_NINF if ndigits != 0 =>
+                return Err(invalid()),
+            NUMERIC_NAN => return Ok(Self::NaN),
+            NUMERIC_PINF => return Ok(Self::Infinity),
+            NUMERIC_NINF => return Ok(Self::NegativeInfinity),
+            _ => return Err(invalid()),
+        };
+
+        if dscale > Decimal::MAX_SCALE {
+            return Err(invalid());
+        }
+
+        // The decimal digits from the first base-10000 digit
+        // down to the last digit allowed by the display scale.
+        let top = DEC_DIGITS as i64 * (i64::from(weight) + 1);
+        let bottom = -i64::from(dscale);
+        let mut digits = vec![0; (top - bottom).max(0) as usize];
+
+        let nbase_digits = body.chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+        for (i, mut nbase_digit) in nbase_digits.enumerate() {
+            if nbase_digit >= NBASE {
+                return Err(invalid());
+            }
+            for j in (0 .. DEC_DIGITS).rev() {
+                let digit = (nbase_digit % 10) as u8;
+                nbase_digit /= 10;
+                match digits.get_mut(i * DEC_DIGITS + j) {
+                    Some(slot) => *slot = digit,
+                    // Digits beyond the display scale cannot be
+                    // represented, unless they are zero.
+                    None if digit == 0 => (),
+                    None => return Err(invalid()),
+                }
+            }
+        }
+
+        Decimal::new(negative, digits, dscale)
+            .map(Self::Finite)
+            .ok_or_else(invalid)
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+/// Write the header of the binary format.
+///
+/// The number of digits is unsigned, as PostgreSQL treats it as such,
+/// even though it is documented as signed.
+fn write_header(buf: &mut Vec<u8>, ndigits: u16, weight: i16, sign: u16,
+                dscale: u16)
+{
+    buf.extend_from_slice(&ndigits.to_be_bytes());
+    buf.extend_from_slice(&weight.to_be_bytes());
+    buf.extend_from_slice(&sign.to_be_bytes());
+    buf.extend_from_slice(&dscale.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::tests::{fuzz_from_sql, round_trip},
+        super::*,
+    };
+
+    fn numeric(s: &str) -> Numeric
+    {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<Numeric>(&Type::NUMERIC);
+    }
+
+    #[test]
+    fn parse()
+    {
+        let decimal = |negative, digits: &[u8], scale| {
+            Numeric::Finite(Decimal::new(negative, digits.to_vec(), scale)
+                .unwrap())
+        };
+        assert_eq!(numeric("0"), decimal(false, &[], 0));
+        assert_eq!(numeric("-0.00"), decimal(false, &[], 2));
+        assert_eq!(numeric(" +007.50 "), decimal(false, &[7, 5, 0], 2));
+        assert_eq!(numeric("-.5"), decimal(true, &[5], 1));
+        assert_eq!(numeric("5."), decimal(false, &[5], 0));
+        assert_eq!(numeric("1.5e3"), decimal(false, &[1, 5, 0, 0], 0));
+        assert_eq!(numeric("1.5E-3"), decimal(false, &[1, 5], 4));
+        assert_eq!(numeric("nan"), Numeric::NaN);
+        assert_eq!(numeric("-Inf"), Numeric::NegativeInfinity);
+
+        for invalid in ["", ".", "-", "1e", "1.2.3", "1e99999999999999999999",
+                        "1e-20000", "1e200000", "١", "0x10", "1 2"] {
+            assert!(invalid.parse::<Numeric>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn display()
+    {
+        for s in ["0", "0.000", "-1", "12.50", "-0.0012", "100", "NaN",
+                  "Infinity", "-Infinity", "123456789.123456789"] {
+            assert_eq!(numeric(s).to_string(), s);
+        }
+        assert_eq!(numeric("1e3").to_string(), "1000");
+        assert_eq!(numeric("-00.10").to_string(), "-0.10");
+    }
+
+    #[test]
+    fn binary()
+    {
+        let encode = |s: &str| {
+            let mut buf = Vec::new();
+            let cx = Context::new(&Type::NUMERIC, FormatCode::Binary);
+            numeric(s).to_sql(&cx, &mut buf).unwrap();
+            buf
+        };
+        // Examples taken from the output of numeric_send.
+        assert_eq!(encode("0"), [0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(encode("-12345.678"),
+                   [0, 3, 0, 1, 0x40, 0, 0, 3, 0, 1, 0x09, 0x29, 0x1A, 0x7C]);
+        assert_eq!(encode("0.00010"), [0, 1, 0xFF, 0xFF, 0, 0, 0, 5, 0, 1]);
+        assert_eq!(encode("10000"), [0, 1, 0, 1, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(encode("NaN"), [0, 0, 0, 0, 0xC0, 0, 0, 0]);
+
+        let cx = Context::new(&Type::NUMERIC, FormatCode::Binary);
+        let decode = |raw: &[u8]| Numeric::from_sql(&cx, raw);
+        // Digit of 10000 or more.
+        assert!(decode(&[0, 1, 0, 0, 0, 0, 0, 0, 0x27, 0x10]).is_err());
+        // Digit beyond the display scale.
+        assert!(decode(&[0, 1, 0xFF, 0xFF, 0, 0, 0, 3, 0, 1]).is_err());
+        // Trailing zero digit beyond the display scale.
+        assert_eq!(decode(&[0, 2, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0]).unwrap(),
+                   numeric("1"));
+        // Special value with digits.
+        assert!(decode(&[0, 1, 0, 0, 0xD0, 0, 0, 0, 0, 1]).is_err());
+        // Invalid sign.
+        assert!(decode(&[0, 0, 0, 0, 0x80, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for s in ["0", "0.0000", "1", "-1", "9999", "10000", "-10001",
+                      "0.1", "0.0001", "0.00001", "12345678.87654321",
+                      "1e100", "-1e-100", "1.00000000000000000000000000001",
+                      "NaN", "Infinity", "-Infinity"] {
+                round_trip(&Type::NUMERIC, format_code, &numeric(s));
+            }
+            let huge = format!("{}.{}", "9".repeat(131072), "9".repeat(16383));
+            round_trip(&Type::NUMERIC, format_code, &numeric(&huge));
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Codec for `numeric` using the **bigdecimal** crate.
+
+use {
+    crate::Result,
+    super::{
+        Context,
+        Decimal,
+        FromSql,
+        IsNull,
+        Numeric,
+        ToSql,
+        Type,
+        out_of_range,
+    },
+    bigdecimal::{BigDecimal, num_bigint::{BigInt, Sign}},
+};
+
+/// Values with more digits than PostgreSQL supports result in an error,
+/// rather than being rounded. Zeros at the end of the fraction
+/// are removed if needed to fit.
+impl ToSql for BigDecimal
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <Numeric as ToSql>::accepts(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        let (integer, mut scale) = self.as_bigint_and_exponent();
+        let (sign, mut digits) = integer.to_radix_be(10);
+
+        // A negative scale means that there are zeros after the digits.
+        if scale < 0 {
+            let zeros = usize::try_from(scale.unsigned_abs()).ok()
+                .filter(|&zeros| zeros <= Decimal::MAX_INTEGER_DIGITS)
+                .ok_or_else(|| out_of_range::<Numeric>(cx))?;
+            digits.resize(digits.len() + zeros, 0);
+            scale = 0;
+        }
+
+        let max_scale = i64::from(Decimal::MAX_SCALE);
+        while scale > max_scale && digits.last() == Some(&0) {
+            digits.pop();
+            scale -= 1;
+        }
+
+        let decimal = u16::try_from(scale).ok()
+            .and_then(|scale| Decimal::new(sign == Sign::Minus, digits, scale))
+            .ok_or_else(|| out_of_range::<Numeric>(cx))?;
+        Numeric::Finite(decimal).to_sql(cx, buf)
+    }
+}
+
+/// NaN and the infinities result in an error.
+impl<'a> FromSql<'a> for BigDecimal
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <Numeric as FromSql>::accepts(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        let Numeric::Finite(decimal) = Numeric::from_sql(cx, raw)?
+            else { return Err(out_of_range::<Self>(cx)) };
+        let sign = if decimal.is_negative() { Sign::Minus } else { Sign::Plus };
+        let integer = BigInt::from_radix_be(sign, decimal.digits(), 10)
+            .ok_or_else(|| out_of_range::<Self>(cx))?;
+        Ok(Self::new(integer, i64::from(decimal.scale())))
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::{protocol::FormatCode, types::tests::round_trip},
+        super::*,
+        std::str::FromStr,
+    };
+
+    #[test]
+    fn conversions()
+    {
+        let cx = Context::new(&Type::NUMERIC, FormatCode::Binary);
+        let encode = |value: &BigDecimal| {
+            let mut buf = Vec::new();
+            value.to_sql(&cx, &mut buf).ok()?;
+            Numeric::from_sql(&cx, &buf).ok().map(|n| n.to_string())
+        };
+        let decimal = |s| BigDecimal::from_str(s).unwrap();
+
+        assert_eq!(encode(&decimal("-12.50")).unwrap(), "-12.50");
+        assert_eq!(encode(&decimal("1e5")).unwrap(), "100000");
+        assert_eq!(encode(&decimal("1e200000")), None);
+        assert_eq!(encode(&decimal("1e-20000")), None);
+        assert_eq!(encode(&BigDecimal::new(BigInt::from(10).pow(20000), 20000))
+                   .unwrap(), "1.".to_owned() + &"0".repeat(16383));
+
+        let nan = {
+            let mut buf = Vec::new();
+            Numeric::NaN.to_sql(&cx, &mut buf).unwrap();
+            buf
+        };
+        assert!(BigDecimal::from_sql(&cx, &nan).is_err());
+
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for s in ["0", "-0.001", "123456789.987654321",
+                      "-79228162514264337593543950335e-1000"] {
+                round_trip(&Type::NUMERIC, format_code, &decimal(s));
+            }
+        }
+    }
+}
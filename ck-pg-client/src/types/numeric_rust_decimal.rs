@@ -0,0 +1,122 @@
+//! Codec for `numeric` using the **rust_decimal** crate.
+
+use {
+    crate::Result,
+    super::{
+        Context,
+        Decimal,
+        FromSql,
+        IsNull,
+        Numeric,
+        ToSql,
+        Type,
+        out_of_range,
+    },
+};
+
+/// The greatest scale that [`rust_decimal::Decimal`] supports.
+const MAX_SCALE: u32 = 28;
+
+impl ToSql for rust_decimal::Decimal
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <Numeric as ToSql>::accepts(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        let digits = self.mantissa().unsigned_abs().to_string().bytes()
+            .map(|b| b - b'0')
+            .collect();
+        let scale = u16::try_from(self.scale())
+            .map_err(|_| out_of_range::<Numeric>(cx))?;
+        let decimal = Decimal::new(self.is_sign_negative(), digits, scale)
+            .ok_or_else(|| out_of_range::<Numeric>(cx))?;
+        Numeric::Finite(decimal).to_sql(cx, buf)
+    }
+}
+
+/// Values that do not fit in [`rust_decimal::Decimal`] result in an
+/// error rather than being rounded, as do NaN and the infinities.
+/// Zeros at the end of the fraction are removed if needed to fit.
+impl<'a> FromSql<'a> for rust_decimal::Decimal
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <Numeric as FromSql>::accepts(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        let Numeric::Finite(decimal) = Numeric::from_sql(cx, raw)?
+            else { return Err(out_of_range::<Self>(cx)) };
+
+        let mut digits = decimal.digits();
+        let mut scale = u32::from(decimal.scale());
+        while scale > MAX_SCALE && digits.last() == Some(&0) {
+            digits = &digits[.. digits.len() - 1];
+            scale -= 1;
+        }
+
+        let mantissa = digits.iter().try_fold(0i128, |acc, &digit| {
+            acc.checked_mul(10)?.checked_add(i128::from(digit))
+        });
+        let mantissa = mantissa.map(|mantissa| {
+            if decimal.is_negative() { -mantissa } else { mantissa }
+        });
+
+        mantissa
+            .and_then(|mantissa| {
+                Self::try_from_i128_with_scale(mantissa, scale).ok()
+            })
+            .ok_or_else(|| out_of_range::<Self>(cx))
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::{protocol::FormatCode, types::tests::round_trip},
+        super::*,
+        std::str::FromStr,
+    };
+
+    #[test]
+    fn conversions()
+    {
+        let cx = Context::new(&Type::NUMERIC, FormatCode::Binary);
+        let decode = |s: &str| {
+            let mut buf = Vec::new();
+            s.parse::<Numeric>().unwrap().to_sql(&cx, &mut buf).unwrap();
+            rust_decimal::Decimal::from_sql(&cx, &buf).ok()
+        };
+        let decimal = |s| rust_decimal::Decimal::from_str(s).ok();
+
+        assert_eq!(decode("-12.50"), decimal("-12.50"));
+        assert_eq!(decode("79228162514264337593543950335"),
+                   decimal("79228162514264337593543950335"));
+        assert_eq!(decode("79228162514264337593543950336"), None);
+        assert_eq!(decode("1e100"), None);
+        assert_eq!(decode("0.0000000000000000000000000001"),
+                   decimal("0.0000000000000000000000000001"));
+        assert_eq!(decode("0.00000000000000000000000000001"), None);
+        assert_eq!(decode("1.00000000000000000000000000000000"),
+                   decimal("1.0000000000000000000000000000"));
+        assert_eq!(decode("NaN"), None);
+        assert_eq!(decode("-Infinity"), None);
+
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for s in ["0", "-0.001", "123456789.987654321",
+                      "-79228162514264337593543950335"] {
+                round_trip(&Type::NUMERIC, format_code, &decimal(s).unwrap());
+            }
+        }
+    }
+}
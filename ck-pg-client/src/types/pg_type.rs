@@ -0,0 +1,390 @@
+use std::{borrow::Cow, fmt, hash::{Hash, Hasher}, sync::Arc};
+
+/// PostgreSQL data type.
+///
+/// A type is identified by its OID. The built-in types are available as
+/// associated constants, such as [`Type::INT4`]; their OIDs are fixed.
+/// Other types, such as those created with `CREATE TYPE`
+/// or by extensions, have OIDs that differ between databases.
+/// Cloning a type is cheap.
+#[derive(Clone)]
+pub struct Type(Repr);
+
+#[derive(Clone)]
+enum Repr
+{
+    Builtin(&'static TypeInfo),
+    Other(Arc<TypeInfo>),
+}
+
+struct TypeInfo
+{
+    oid: u32,
+    name: Cow<'static, str>,
+    kind: Kind,
+}
+
+/// What kind of type a type is.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Kind
+{
+    /// A base type, or a type whose kind is not known.
+    Simple,
+
+    /// A pseudo-type, such as `record` or `void`.
+    Pseudo,
+
+    /// An array type, with the given element type.
+    Array(Type),
+
+    /// A range type, with the given element type.
+    Range(Type),
+
+    /// A multirange type, with the given range type.
+    Multirange(Type),
+
+    /// A composite type, with the given fields.
+    Composite(Vec<Field>),
+
+    /// An enum type, with the given labels in sort order.
+    Enum(Vec<String>),
+
+    /// A domain, with the given base type.
+    Domain(Type),
+}
+
+/// Field of a composite type.
+#[derive(Clone, Debug)]
+pub struct Field
+{
+    name: String,
+    ty: Type,
+}
+
+impl Field
+{
+    /// Create a field with the given name and type.
+    pub fn new(name: String, ty: Type) -> Self
+    {
+        Self{name, ty}
+    }
+
+    /// The name of the field, as in `pg_attribute.attname`.
+    pub fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+    /// The type of the field.
+    pub fn ty(&self) -> &Type
+    {
+        &self.ty
+    }
+}
+
+impl Type
+{
+    /// Create a type that is not built in.
+    pub fn new(oid: u32, name: String, kind: Kind) -> Self
+    {
+        let name = Cow::Owned(name);
+        Self(Repr::Other(Arc::new(TypeInfo{oid, name, kind})))
+    }
+
+    /// The built-in type with the given OID, if any.
+    pub fn from_oid(oid: u32) -> Option<Self>
+    {
+        builtin_from_oid(oid)
+    }
+
+    /// The type with the given OID, if built in,
+    /// or else a [`Kind::Simple`] type without a name.
+    pub fn from_oid_or_unknown(oid: u32) -> Self
+    {
+        Self::from_oid(oid)
+            .unwrap_or_else(|| Self::new(oid, String::new(), Kind::Simple))
+    }
+
+    /// The OID of the type.
+    pub fn oid(&self) -> u32
+    {
+        self.info().oid
+    }
+
+    /// The name of the type, as in `pg_type.typname`.
+    ///
+    /// This is empty if the name is not known.
+    pub fn name(&self) -> &str
+    {
+        &self.info().name
+    }
+
+    /// What kind of type the type is.
+    pub fn kind(&self) -> &Kind
+    {
+        &self.info().kind
+    }
+
+    fn info(&self) -> &TypeInfo
+    {
+        match &self.0 {
+            Repr::Builtin(info) => info,
+            Repr::Other(info) => info,
+        }
+    }
+}
+
+/// Types are equal if their OIDs are equal.
+impl PartialEq for Type
+{
+    fn eq(&self, other: &Self) -> bool
+    {
+        self.oid() == other.oid()
+    }
+}
+
+impl Eq for Type
+{
+}
+
+impl Hash for Type
+{
+    fn hash<H>(&self, state: &mut H)
+        where H: Hasher
+    {
+        self.oid().hash(state);
+    }
+}
+
+impl fmt::Debug for Type
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        f.debug_struct("Type")
+            .field("oid", &self.oid())
+            .field("name", &self.name())
+            .field("kind", self.kind())
+            .finish()
+    }
+}
+
+/// Displays the name of the type, or its OID if the name is not known.
+impl fmt::Display for Type
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        if self.name().is_empty() {
+            write!(f, "OID {}", self.oid())
+        } else {
+            write!(f, "{}", self.name())
+        }
+    }
+}
+
+macro_rules! builtin_types
+{
+    ($($ident:ident = $oid:literal, $name:literal, $kind:expr;)*) => {
+        #[allow(missing_docs)]
+        impl Type
+        {
+            $(
+                pub const $ident: Self = Self(Repr::Builtin(&TypeInfo{
+                    oid: $oid,
+                    name: Cow::Borrowed($name),
+                    kind: {
+                        #[allow(unused_imports)]
+                        use Kind::*;
+                        $kind
+                    },
+                }));
+            )*
+        }
+
+        fn builtin_from_oid(oid: u32) -> Option<Type>
+        {
+            match oid {
+                $($oid => Some(Type::$ident),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+// The built-in types that client applications may encounter,
+// as listed in the pg_type catalog of PostgreSQL 15.
+// Types that are only used internally by the server are not listed.
+builtin_types!
+{
+    BOOL = 16, "bool", Simple;
+    BYTEA = 17, "bytea", Simple;
+    CHAR = 18, "char", Simple;
+    NAME = 19, "name", Simple;
+    INT8 = 20, "int8", Simple;
+    INT2 = 21, "int2", Simple;
+    INT2VECTOR = 22, "int2vector", Array(Type::INT2);
+    INT4 = 23, "int4", Simple;
+    REGPROC = 24, "regproc", Simple;
+    TEXT = 25, "text", Simple;
+    OID = 26, "oid", Simple;
+    TID = 27, "tid", Simple;
+    XID = 28, "xid", Simple;
+    CID = 29, "cid", Simple;
+    OIDVECTOR = 30, "oidvector", Array(Type::OID);
+    JSON = 114, "json", Simple;
+    XML = 142, "xml", Simple;
+    XML_ARRAY = 143, "_xml", Array(Type::XML);
+    JSON_ARRAY = 199, "_json", Array(Type::JSON);
+    XID8_ARRAY = 271, "_xid8", Array(Type::XID8);
+    POINT = 600, "point", Simple;
+    LSEG = 601, "lseg", Simple;
+    PATH = 602, "path", Simple;
+    BOX = 603, "box", Simple;
+    POLYGON = 604, "polygon", Simple;
+    LINE = 628, "line", Simple;
+    LINE_ARRAY = 629, "_line", Array(Type::LINE);
+    CIDR = 650, "cidr", Simple;
+    CIDR_ARRAY = 651, "_cidr", Array(Type::CIDR);
+    FLOAT4 = 700, "float4", Simple;
+    FLOAT8 = 701, "float8", Simple;
+    UNKNOWN = 705, "unknown", Pseudo;
+    CIRCLE = 718, "circle", Simple;
+    CIRCLE_ARRAY = 719, "_circle", Array(Type::CIRCLE);
+    MACADDR8 = 774, "macaddr8", Simple;
+    MACADDR8_ARRAY = 775, "_macaddr8", Array(Type::MACADDR8);
+    MONEY = 790, "money", Simple;
+    MONEY_ARRAY = 791, "_money", Array(Type::MONEY);
+    MACADDR = 829, "macaddr", Simple;
+    INET = 869, "inet", Simple;
+    BOOL_ARRAY = 1000, "_bool", Array(Type::BOOL);
+    BYTEA_ARRAY = 1001, "_bytea", Array(Type::BYTEA);
+    CHAR_ARRAY = 1002, "_char", Array(Type::CHAR);
+    NAME_ARRAY = 1003, "_name", Array(Type::NAME);
+    INT2_ARRAY = 1005, "_int2", Array(Type::INT2);
+    INT4_ARRAY = 1007, "_int4", Array(Type::INT4);
+    REGPROC_ARRAY = 1008, "_regproc", Array(Type::REGPROC);
+    TEXT_ARRAY = 1009, "_text", Array(Type::TEXT);
+    TID_ARRAY = 1010, "_tid", Array(Type::TID);
+    XID_ARRAY = 1011, "_xid", Array(Type::XID);
+    CID_ARRAY = 1012, "_cid", Array(Type::CID);
+    BPCHAR_ARRAY = 1014, "_bpchar", Array(Type::BPCHAR);
+    VARCHAR_ARRAY = 1015, "_varchar", Array(Type::VARCHAR);
+    INT8_ARRAY = 1016, "_int8", Array(Type::INT8);
+    POINT_ARRAY = 1017, "_point", Array(Type::POINT);
+    LSEG_ARRAY = 1018, "_lseg", Array(Type::LSEG);
+    PATH_ARRAY = 1019, "_path", Array(Type::PATH);
+    BOX_ARRAY = 1020, "_box", Array(Type::BOX);
+    FLOAT4_ARRAY = 1021, "_float4", Array(Type::FLOAT4);
+    FLOAT8_ARRAY = 1022, "_float8", Array(Type::FLOAT8);
+    POLYGON_ARRAY = 1027, "_polygon", Array(Type::POLYGON);
+    OID_ARRAY = 1028, "_oid", Array(Type::OID);
+    MACADDR_ARRAY = 1040, "_macaddr", Array(Type::MACADDR);
+    INET_ARRAY = 1041, "_inet", Array(Type::INET);
+    BPCHAR = 1042, "bpchar", Simple;
+    VARCHAR = 1043, "varchar", Simple;
+    DATE = 1082, "date", Simple;
+    TIME = 1083, "time", Simple;
+    TIMESTAMP = 1114, "timestamp", Simple;
+    TIMESTAMP_ARRAY = 1115, "_timestamp", Array(Type::TIMESTAMP);
+    DATE_ARRAY = 1182, "_date", Array(Type::DATE);
+    TIME_ARRAY = 1183, "_time", Array(Type::TIME);
+    TIMESTAMPTZ = 1184, "timestamptz", Simple;
+    TIMESTAMPTZ_ARRAY = 1185, "_timestamptz", Array(Type::TIMESTAMPTZ);
+    INTERVAL = 1186, "interval", Simple;
+    INTERVAL_ARRAY = 1187, "_interval", Array(Type::INTERVAL);
+    NUMERIC_ARRAY = 1231, "_numeric", Array(Type::NUMERIC);
+    TIMETZ = 1266, "timetz", Simple;
+    TIMETZ_ARRAY = 1270, "_timetz", Array(Type::TIMETZ);
+    BIT = 1560, "bit", Simple;
+    BIT_ARRAY = 1561, "_bit", Array(Type::BIT);
+    VARBIT = 1562, "varbit", Simple;
+    VARBIT_ARRAY = 1563, "_varbit", Array(Type::VARBIT);
+    NUMERIC = 1700, "numeric", Simple;
+    REFCURSOR = 1790, "refcursor", Simple;
+    REFCURSOR_ARRAY = 2201, "_refcursor", Array(Type::REFCURSOR);
+    REGPROCEDURE = 2202, "regprocedure", Simple;
+    REGOPER = 2203, "regoper", Simple;
+    REGOPERATOR = 2204, "regoperator", Simple;
+    REGCLASS = 2205, "regclass", Simple;
+    REGTYPE = 2206, "regtype", Simple;
+    REGPROCEDURE_ARRAY = 2207, "_regprocedure", Array(Type::REGPROCEDURE);
+    REGOPER_ARRAY = 2208, "_regoper", Array(Type::REGOPER);
+    REGOPERATOR_ARRAY = 2209, "_regoperator", Array(Type::REGOPERATOR);
+    REGCLASS_ARRAY = 2210, "_regclass", Array(Type::REGCLASS);
+    REGTYPE_ARRAY = 2211, "_regtype", Array(Type::REGTYPE);
+    RECORD = 2249, "record", Pseudo;
+    VOID = 2278, "void", Pseudo;
+    RECORD_ARRAY = 2287, "_record", Array(Type::RECORD);
+    TXID_SNAPSHOT_ARRAY = 2949, "_txid_snapshot", Array(Type::TXID_SNAPSHOT);
+    UUID = 2950, "uuid", Simple;
+    UUID_ARRAY = 2951, "_uuid", Array(Type::UUID);
+    TXID_SNAPSHOT = 2970, "txid_snapshot", Simple;
+    PG_LSN = 3220, "pg_lsn", Simple;
+    PG_LSN_ARRAY = 3221, "_pg_lsn", Array(Type::PG_LSN);
+    TSVECTOR = 3614, "tsvector", Simple;
+    TSQUERY = 3615, "tsquery", Simple;
+    TSVECTOR_ARRAY = 3643, "_tsvector", Array(Type::TSVECTOR);
+    TSQUERY_ARRAY = 3645, "_tsquery", Array(Type::TSQUERY);
+    REGCONFIG = 3734, "regconfig", Simple;
+    REGCONFIG_ARRAY = 3735, "_regconfig", Array(Type::REGCONFIG);
+    REGDICTIONARY = 3769, "regdictionary", Simple;
+    REGDICTIONARY_ARRAY = 3770, "_regdictionary", Array(Type::REGDICTIONARY);
+    JSONB = 3802, "jsonb", Simple;
+    JSONB_ARRAY = 3807, "_jsonb", Array(Type::JSONB);
+    INT4RANGE = 3904, "int4range", Range(Type::INT4);
+    INT4RANGE_ARRAY = 3905, "_int4range", Array(Type::INT4RANGE);
+    NUMRANGE = 3906, "numrange", Range(Type::NUMERIC);
+    NUMRANGE_ARRAY = 3907, "_numrange", Array(Type::NUMRANGE);
+    TSRANGE = 3908, "tsrange", Range(Type::TIMESTAMP);
+    TSRANGE_ARRAY = 3909, "_tsrange", Array(Type::TSRANGE);
+    TSTZRANGE = 3910, "tstzrange", Range(Type::TIMESTAMPTZ);
+    TSTZRANGE_ARRAY = 3911, "_tstzrange", Array(Type::TSTZRANGE);
+    DATERANGE = 3912, "daterange", Range(Type::DATE);
+    DATERANGE_ARRAY = 3913, "_daterange", Array(Type::DATERANGE);
+    INT8RANGE = 3926, "int8range", Range(Type::INT8);
+    INT8RANGE_ARRAY = 3927, "_int8range", Array(Type::INT8RANGE);
+    JSONPATH = 4072, "jsonpath", Simple;
+    JSONPATH_ARRAY = 4073, "_jsonpath", Array(Type::JSONPATH);
+    REGNAMESPACE = 4089, "regnamespace", Simple;
+    REGNAMESPACE_ARRAY = 4090, "_regnamespace", Array(Type::REGNAMESPACE);
+    REGROLE = 4096, "regrole", Simple;
+    REGROLE_ARRAY = 4097, "_regrole", Array(Type::REGROLE);
+    REGCOLLATION = 4191, "regcollation", Simple;
+    REGCOLLATION_ARRAY = 4192, "_regcollation", Array(Type::REGCOLLATION);
+    INT4MULTIRANGE = 4451, "int4multirange", Multirange(Type::INT4RANGE);
+    NUMMULTIRANGE = 4532, "nummultirange", Multirange(Type::NUMRANGE);
+    TSMULTIRANGE = 4533, "tsmultirange", Multirange(Type::TSRANGE);
+    TSTZMULTIRANGE = 4534, "tstzmultirange", Multirange(Type::TSTZRANGE);
+    DATEMULTIRANGE = 4535, "datemultirange", Multirange(Type::DATERANGE);
+    INT8MULTIRANGE = 4536, "int8multirange", Multirange(Type::INT8RANGE);
+    PG_SNAPSHOT = 5038, "pg_snapshot", Simple;
+    PG_SNAPSHOT_ARRAY = 5039, "_pg_snapshot", Array(Type::PG_SNAPSHOT);
+    XID8 = 5069, "xid8", Simple;
+    INT4MULTIRANGE_ARRAY = 6150, "_int4multirange", Array(Type::INT4MULTIRANGE);
+    NUMMULTIRANGE_ARRAY = 6151, "_nummultirange", Array(Type::NUMMULTIRANGE);
+    TSMULTIRANGE_ARRAY = 6152, "_tsmultirange", Array(Type::TSMULTIRANGE);
+    TSTZMULTIRANGE_ARRAY = 6153, "_tstzmultirange", Array(Type::TSTZMULTIRANGE);
+    DATEMULTIRANGE_ARRAY = 6155, "_datemultirange", Array(Type::DATEMULTIRANGE);
+    INT8MULTIRANGE_ARRAY = 6157, "_int8multirange", Array(Type::INT8MULTIRANGE);
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn builtin()
+    {
+        let ty = Type::from_oid(1007).unwrap();
+        assert_eq!(ty, Type::INT4_ARRAY);
+        assert_eq!(ty.name(), "_int4");
+        assert!(matches!(ty.kind(), Kind::Array(e) if *e == Type::INT4));
+        assert!(Type::from_oid(0).is_none());
+
+        let ty = Type::from_oid_or_unknown(123456);
+        assert_eq!(ty.oid(), 123456);
+        assert_eq!(ty.to_string(), "OID 123456");
+        assert_eq!(Type::NUMMULTIRANGE.to_string(), "nummultirange");
+    }
+}
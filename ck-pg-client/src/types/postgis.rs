@@ -0,0 +1,704 @@
+//! Codecs for `geometry` and `geography` from the **postgis** extension.
+//!
+//! The OIDs of these types differ between databases,
+//! so the types are accepted by their names.
+//! Values are sent as extended well-known binary (EWKB),
+//! which in text format is written in hexadecimal.
+//! Both byte orders and the ISO type codes for Z and M dimensions
+//! are understood; values are encoded in little-endian EWKB,
+//! as PostGIS does.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{Context, FromSql, IsNull, ToSql, Type, invalid_value, text_value},
+    std::io::Write,
+};
+
+/// Value of type `geometry` or `geography`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Geometry
+{
+    /// The spatial reference system identifier.
+    ///
+    /// PostGIS omits the identifier 0, which means unknown,
+    /// and defaults to 4326 for values of type `geography`.
+    pub srid: Option<i32>,
+
+    /// Whether each coordinate has a Z value.
+    pub has_z: bool,
+
+    /// Whether each coordinate has an M value.
+    pub has_m: bool,
+
+    #[allow(missing_docs)]
+    pub shape: Shape,
+}
+
+/// Coordinate of a geometry.
+///
+/// Whether Z and M are present must agree with
+/// [`Geometry::has_z`] and [`Geometry::has_m`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Coordinate
+{
+    #[allow(missing_docs)]
+    pub x: f64,
+    #[allow(missing_docs)]
+    pub y: f64,
+    #[allow(missing_docs)]
+    pub z: Option<f64>,
+    #[allow(missing_docs)]
+    pub m: Option<f64>,
+}
+
+/// Shape of a geometry.
+///
+/// Rings are lists of coordinates of which the first and last are
+/// equal. Members of [`GeometryCollection`] and of the curved
+/// shapes may be any shape, but PostGIS only accepts certain shapes
+/// as members of each.
+///
+/// [`GeometryCollection`]: `Self::GeometryCollection`
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape
+{
+    /// Point, which is `None` if empty.
+    Point(Option<Coordinate>),
+    LineString(Vec<Coordinate>),
+
+    /// Polygon given by its exterior ring and its interior rings.
+    Polygon(Vec<Vec<Coordinate>>),
+
+    MultiPoint(Vec<Option<Coordinate>>),
+    MultiLineString(Vec<Vec<Coordinate>>),
+    MultiPolygon(Vec<Vec<Vec<Coordinate>>>),
+    GeometryCollection(Vec<Shape>),
+    CircularString(Vec<Coordinate>),
+    CompoundCurve(Vec<Shape>),
+    CurvePolygon(Vec<Shape>),
+    MultiCurve(Vec<Shape>),
+    MultiSurface(Vec<Shape>),
+    PolyhedralSurface(Vec<Vec<Vec<Coordinate>>>),
+
+    /// Triangle given by its ring.
+    Triangle(Vec<Vec<Coordinate>>),
+
+    Tin(Vec<Vec<Vec<Coordinate>>>),
+}
+
+impl Coordinate
+{
+    /// Create a coordinate without Z and M values.
+    pub const fn xy(x: f64, y: f64) -> Self
+    {
+        Self{x, y, z: None, m: None}
+    }
+
+    /// Create a coordinate with a Z value but no M value.
+    pub const fn xyz(x: f64, y: f64, z: f64) -> Self
+    {
+        Self{x, y, z: Some(z), m: None}
+    }
+}
+
+impl Shape
+{
+    /// The type code of the shape in EWKB.
+    fn code(&self) -> u32
+    {
+        match self {
+            Self::Point(..) => POINT,
+            Self::LineString(..) => LINE_STRING,
+            Self::Polygon(..) => POLYGON,
+            Self::MultiPoint(..) => MULTI_POINT,
+            Self::MultiLineString(..) => MULTI_LINE_STRING,
+            Self::MultiPolygon(..) => MULTI_POLYGON,
+            Self::GeometryCollection(..) => GEOMETRY_COLLECTION,
+            Self::CircularString(..) => CIRCULAR_STRING,
+            Self::CompoundCurve(..) => COMPOUND_CURVE,
+            Self::CurvePolygon(..) => CURVE_POLYGON,
+            Self::MultiCurve(..) => MULTI_CURVE,
+            Self::MultiSurface(..) => MULTI_SURFACE,
+            Self::PolyhedralSurface(..) => POLYHEDRAL_SURFACE,
+            Self::Triangle(..) => TRIANGLE,
+            Self::Tin(..) => TIN,
+        }
+    }
+}
+
+/// Type codes and flags in EWKB.
+const POINT: u32 = 1;
+const LINE_STRING: u32 = 2;
+const POLYGON: u32 = 3;
+const MULTI_POINT: u32 = 4;
+const MULTI_LINE_STRING: u32 = 5;
+const MULTI_POLYGON: u32 = 6;
+const GEOMETRY_COLLECTION: u32 = 7;
+const CIRCULAR_STRING: u32 = 8;
+const COMPOUND_CURVE: u32 = 9;
+const CURVE_POLYGON: u32 = 10;
+const MULTI_CURVE: u32 = 11;
+const MULTI_SURFACE: u32 = 12;
+const POLYHEDRAL_SURFACE: u32 = 13;
+const TRIANGLE: u32 = 14;
+const TIN: u32 = 15;
+const FLAG_Z: u32 = 0x8000_0000;
+const FLAG_M: u32 = 0x4000_0000;
+const FLAG_SRID: u32 = 0x2000_0000;
+
+/// The deepest nesting of collections that is decoded,
+/// which bounds the recursion of the decoder.
+const MAX_DEPTH: usize = 64;
+
+/// Whether a type is `geometry` or `geography`.
+fn is_postgis_type(ty: &Type) -> bool
+{
+    matches!(ty.name(), "geometry" | "geography")
+}
+
+impl ToSql for Geometry
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_postgis_type(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        match cx.format_code() {
+            FormatCode::Binary => write_ewkb(cx, self, buf)?,
+            FormatCode::Text => {
+                let mut ewkb = Vec::new();
+                write_ewkb(cx, self, &mut ewkb)?;
+                for byte in ewkb {
+                    write!(buf, "{byte:02X}")?;
+                }
+            },
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl<'a> FromSql<'a> for Geometry
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_postgis_type(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        match cx.format_code() {
+            FormatCode::Binary => read_ewkb(cx, raw),
+            FormatCode::Text => {
+                let hex = text_value(cx, raw)?;
+                let ewkb = parse_hex(hex)
+                    .ok_or_else(|| invalid_value(cx, "malformed text"))?;
+                read_ewkb(cx, &ewkb)
+            },
+        }
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Vec<u8>>
+{
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    hex.as_bytes().chunks_exact(2)
+        .map(|pair| {
+            let digit = |byte: u8| char::from(byte).to_digit(16);
+            Some((digit(pair[0])? << 4 | digit(pair[1])?) as u8)
+        })
+        .collect()
+}
+
+fn write_ewkb(cx: &Context, geometry: &Geometry, buf: &mut Vec<u8>)
+    -> Result<()>
+{
+    let mut writer = Writer{cx, buf, has_z: geometry.has_z,
+                            has_m: geometry.has_m};
+    writer.shape(&geometry.shape, geometry.srid)
+}
+
+struct Writer<'c, 'b>
+{
+    cx: &'c Context<'c>,
+    buf: &'b mut Vec<u8>,
+    has_z: bool,
+    has_m: bool,
+}
+
+impl Writer<'_, '_>
+{
+    fn u32(&mut self, value: u32)
+    {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn count(&mut self, count: usize) -> Result<()>
+    {
+        let count = u32::try_from(count)
+            .map_err(|_| invalid_value(self.cx, "too many elements"))?;
+        self.u32(count);
+        Ok(())
+    }
+
+    fn header(&mut self, code: u32, srid: Option<i32>)
+    {
+        self.buf.push(1);
+        let mut code = code;
+        code |= if self.has_z { FLAG_Z } else { 0 };
+        code |= if self.has_m { FLAG_M } else { 0 };
+        code |= if srid.is_some() { FLAG_SRID } else { 0 };
+        self.u32(code);
+        if let Some(srid) = srid {
+            self.buf.extend_from_slice(&srid.to_le_bytes());
+        }
+    }
+
+    fn coordinate(&mut self, coordinate: &Coordinate) -> Result<()>
+    {
+        let Coordinate{x, y, z, m} = *coordinate;
+        if z.is_some() != self.has_z || m.is_some() != self.has_m {
+            return Err(invalid_value(self.cx, "wrong coordinate dimensions"));
+        }
+        for value in [Some(x), Some(y), z, m].into_iter().flatten() {
+            self.buf.extend_from_slice(&value.to_le_bytes());
+        }
+        Ok(())
+    }
+
+    fn coordinates(&mut self, coordinates: &[Coordinate]) -> Result<()>
+    {
+        self.count(coordinates.len())?;
+        coordinates.iter().try_for_each(|c| self.coordinate(c))
+    }
+
+    fn rings(&mut self, rings: &[Vec<Coordinate>]) -> Result<()>
+    {
+        self.count(rings.len())?;
+        rings.iter().try_for_each(|ring| self.coordinates(ring))
+    }
+
+    fn point(&mut self, point: &Option<Coordinate>) -> Result<()>
+    {
+        match point {
+            Some(coordinate) => self.coordinate(coordinate),
+            None => {
+                // Empty points have NaN for each coordinate value.
+                let dimensions = 2 + usize::from(self.has_z)
+                                   + usize::from(self.has_m);
+                for _ in 0 .. dimensions {
+                    self.buf.extend_from_slice(&f64::NAN.to_le_bytes());
+                }
+                Ok(())
+            },
+        }
+    }
+
+    /// Write each member with its own header.
+    fn members<T>(
+        &mut self,
+        code: u32,
+        members: &[T],
+        mut write: impl FnMut(&mut Self, &T) -> Result<()>,
+    ) -> Result<()>
+    {
+        self.count(members.len())?;
+        for member in members {
+            self.header(code, None);
+            write(self, member)?;
+        }
+        Ok(())
+    }
+
+    fn shape(&mut self, shape: &Shape, srid: Option<i32>) -> Result<()>
+    {
+        self.header(shape.code(), srid);
+        match shape {
+            Shape::Point(point) => self.point(point),
+            Shape::LineString(coordinates)
+                | Shape::CircularString(coordinates) =>
+                self.coordinates(coordinates),
+            Shape::Polygon(rings) | Shape::Triangle(rings) =>
+                self.rings(rings),
+            Shape::MultiPoint(points) =>
+                self.members(POINT, points, Self::point),
+            Shape::MultiLineString(lines) =>
+                self.members(LINE_STRING, lines, |w, l| w.coordinates(l)),
+            Shape::MultiPolygon(polygons)
+                | Shape::PolyhedralSurface(polygons) =>
+                self.members(POLYGON, polygons, |w, p| w.rings(p)),
+            Shape::Tin(triangles) =>
+                self.members(TRIANGLE, triangles, |w, t| w.rings(t)),
+            Shape::GeometryCollection(shapes)
+                | Shape::CompoundCurve(shapes)
+                | Shape::CurvePolygon(shapes)
+                | Shape::MultiCurve(shapes)
+                | Shape::MultiSurface(shapes) => {
+                self.count(shapes.len())?;
+                shapes.iter().try_for_each(|shape| self.shape(shape, None))
+            },
+        }
+    }
+}
+
+/// Type code, dimensions, and identifier of a geometry.
+struct Header
+{
+    code: u32,
+    has_z: bool,
+    has_m: bool,
+    srid: Option<i32>,
+}
+
+struct Reader<'c, 'a>
+{
+    cx: &'c Context<'c>,
+    raw: &'a [u8],
+    little_endian: bool,
+    has_z: bool,
+    has_m: bool,
+}
+
+fn read_ewkb(cx: &Context, raw: &[u8]) -> Result<Geometry>
+{
+    let mut reader = Reader{cx, raw, little_endian: false,
+                            has_z: false, has_m: false};
+    let Header{code, has_z, has_m, srid} = reader.header()?;
+    reader.has_z = has_z;
+    reader.has_m = has_m;
+    let shape = reader.shape(code, 0)?;
+    if !reader.raw.is_empty() {
+        return Err(invalid_value(cx, "trailing data"));
+    }
+    Ok(Geometry{srid, has_z, has_m, shape})
+}
+
+impl Reader<'_, '_>
+{
+    fn malformed(&self) -> crate::Error
+    {
+        invalid_value(self.cx, "malformed binary value")
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N]>
+    {
+        let (bytes, rest) = self.raw.split_first_chunk::<N>()
+            .ok_or_else(|| self.malformed())?;
+        self.raw = rest;
+        Ok(*bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32>
+    {
+        let bytes = self.bytes()?;
+        Ok(if self.little_endian { u32::from_le_bytes(bytes) }
+           else { u32::from_be_bytes(bytes) })
+    }
+
+    fn f64(&mut self) -> Result<f64>
+    {
+        let bytes = self.bytes()?;
+        Ok(if self.little_endian { f64::from_le_bytes(bytes) }
+           else { f64::from_be_bytes(bytes) })
+    }
+
+    /// Read a count, which bounds the capacity to allocate for it,
+    /// given the least number of bytes each counted item takes.
+    fn count(&mut self, min_item_length: usize) -> Result<(usize, usize)>
+    {
+        let count = usize::try_from(self.u32()?)
+            .map_err(|_| self.malformed())?;
+        Ok((count, count.min(self.raw.len() / min_item_length)))
+    }
+
+    fn dimensions(&self) -> usize
+    {
+        2 + usize::from(self.has_z) + usize::from(self.has_m)
+    }
+
+    fn header(&mut self) -> Result<Header>
+    {
+        self.little_endian = match self.bytes()? {
+            [0] => false,
+            [1] => true,
+            _ => return Err(invalid_value(self.cx, "invalid byte order")),
+        };
+        let code = self.u32()?;
+
+        // ISO type codes add 1000 for Z, 2000 for M, and 3000 for both,
+        // which must not be combined with the flags of EWKB.
+        let iso = code & 0x0FFF_FFFF;
+        let iso_dimensions = iso / 1000;
+        if iso_dimensions > 3
+            || iso_dimensions != 0 && code & (FLAG_Z | FLAG_M) != 0 {
+            return Err(invalid_value(self.cx, "unknown geometry type"));
+        }
+        let has_z = code & FLAG_Z != 0 || matches!(iso_dimensions, 1 | 3);
+        let has_m = code & FLAG_M != 0 || matches!(iso_dimensions, 2 | 3);
+        let srid = if code & FLAG_SRID != 0 {
+            Some(self.u32()?.cast_signed())
+        } else {
+            None
+        };
+        Ok(Header{code: iso % 1000, has_z, has_m, srid})
+    }
+
+    fn coordinate(&mut self) -> Result<Coordinate>
+    {
+        let x = self.f64()?;
+        let y = self.f64()?;
+        let z = if self.has_z { Some(self.f64()?) } else { None };
+        let m = if self.has_m { Some(self.f64()?) } else { None };
+        Ok(Coordinate{x, y, z, m})
+    }
+
+    fn coordinates(&mut self) -> Result<Vec<Coordinate>>
+    {
+        let (count, capacity) = self.count(8 * self.dimensions())?;
+        let mut coordinates = Vec::with_capacity(capacity);
+        for _ in 0 .. count {
+            coordinates.push(self.coordinate()?);
+        }
+        Ok(coordinates)
+    }
+
+    fn rings(&mut self) -> Result<Vec<Vec<Coordinate>>>
+    {
+        let (count, capacity) = self.count(4)?;
+        let mut rings = Vec::with_capacity(capacity);
+        for _ in 0 .. count {
+            rings.push(self.coordinates()?);
+        }
+        Ok(rings)
+    }
+
+    fn point(&mut self) -> Result<Option<Coordinate>>
+    {
+        let coordinate = self.coordinate()?;
+        let Coordinate{x, y, z, m} = coordinate;
+        let empty = [Some(x), Some(y), z, m].into_iter().flatten()
+            .all(f64::is_nan);
+        Ok((!empty).then_some(coordinate))
+    }
+
+    /// Read the members of a collection, each with its own header,
+    /// which must have the same dimensions as the collection.
+    fn members(&mut self, depth: usize) -> Result<Vec<Shape>>
+    {
+        if depth >= MAX_DEPTH {
+            return Err(invalid_value(self.cx, "nested too deeply"));
+        }
+        // Each member takes at least five bytes.
+        let (count, capacity) = self.count(5)?;
+        let mut members = Vec::with_capacity(capacity);
+        for _ in 0 .. count {
+            let header = self.header()?;
+            if (header.has_z, header.has_m) != (self.has_z, self.has_m) {
+                return Err(invalid_value(self.cx, "mixed dimensions"));
+            }
+            members.push(self.shape(header.code, depth + 1)?);
+        }
+        Ok(members)
+    }
+
+    /// Read the members of a collection, which must all be
+    /// of the shape that the function extracts.
+    fn typed_members<T>(
+        &mut self,
+        depth: usize,
+        extract: impl Fn(Shape) -> Option<T>,
+    ) -> Result<Vec<T>>
+    {
+        self.members(depth)?.into_iter()
+            .map(|shape| {
+                extract(shape)
+                    .ok_or_else(|| invalid_value(self.cx, "invalid member"))
+            })
+            .collect()
+    }
+
+    fn shape(&mut self, code: u32, depth: usize) -> Result<Shape>
+    {
+        Ok(match code {
+            POINT => Shape::Point(self.point()?),
+            LINE_STRING => Shape::LineString(self.coordinates()?),
+            POLYGON => Shape::Polygon(self.rings()?),
+            MULTI_POINT => Shape::MultiPoint(
+                self.typed_members(depth, |shape| match shape {
+                    Shape::Point(point) => Some(point),
+                    _ => None,
+                })?,
+            ),
+            MULTI_LINE_STRING => Shape::MultiLineString(
+                self.typed_members(depth, |shape| match shape {
+                    Shape::LineString(coordinates) => Some(coordinates),
+                    _ => None,
+                })?,
+            ),
+            MULTI_POLYGON => Shape::MultiPolygon(
+                self.typed_members(depth, |shape| match shape {
+                    Shape::Polygon(rings) => Some(rings),
+                    _ => None,
+                })?,
+            ),
+            GEOMETRY_COLLECTION =>
+                Shape::GeometryCollection(self.members(depth)?),
+            CIRCULAR_STRING => Shape::CircularString(self.coordinates()?),
+            COMPOUND_CURVE => Shape::CompoundCurve(self.members(depth)?),
+            CURVE_POLYGON => Shape::CurvePolygon(self.members(depth)?),
+            MULTI_CURVE => Shape::MultiCurve(self.members(depth)?),
+            MULTI_SURFACE => Shape::MultiSurface(self.members(depth)?),
+            POLYHEDRAL_SURFACE => Shape::PolyhedralSurface(
+                self.typed_members(depth, |shape| match shape {
+                    Shape::Polygon(rings) => Some(rings),
+                    _ => None,
+                })?,
+            ),
+            TRIANGLE => Shape::Triangle(self.rings()?),
+            TIN => Shape::Tin(
+                self.typed_members(depth, |shape| match shape {
+                    Shape::Triangle(rings) => Some(rings),
+                    _ => None,
+                })?,
+            ),
+            _ => return Err(invalid_value(self.cx, "unknown geometry type")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::{Kind, tests::{fuzz_from_sql, round_trip}},
+        super::*,
+    };
+
+    fn geometry() -> Type
+    {
+        Type::new(0, "geometry".to_owned(), Kind::Simple)
+    }
+
+    fn decode(hex: &str) -> Geometry
+    {
+        let ty = geometry();
+        let cx = Context::new(&ty, FormatCode::Text);
+        Geometry::from_sql(&cx, hex.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<Geometry>(&geometry());
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        let xy = |x, y| Coordinate::xy(x, y);
+        let xyzm = |x, y, z, m| Coordinate{x, y, z: Some(z), m: Some(m)};
+        let ring = vec![xy(0.0, 0.0), xy(1.0, 0.0), xy(0.0, 1.0),
+                        xy(0.0, 0.0)];
+        let geometries = [
+            Geometry{srid: None, has_z: false, has_m: false,
+                     shape: Shape::Point(Some(xy(1.0, 2.0)))},
+            Geometry{srid: Some(4326), has_z: false, has_m: false,
+                     shape: Shape::Point(None)},
+            Geometry{srid: Some(3857), has_z: true, has_m: true,
+                     shape: Shape::LineString(vec![
+                         xyzm(1.0, 2.0, 3.0, 4.0),
+                         xyzm(-1.0, f64::INFINITY, 0.5, 0.0),
+                     ])},
+            Geometry{srid: None, has_z: false, has_m: false,
+                     shape: Shape::MultiPolygon(vec![
+                         vec![ring.clone()],
+                         vec![ring.clone(), ring.clone()],
+                         vec![],
+                     ])},
+            Geometry{srid: Some(0), has_z: false, has_m: false,
+                     shape: Shape::GeometryCollection(vec![
+                         Shape::MultiPoint(vec![Some(xy(1.0, 1.0)), None]),
+                         Shape::GeometryCollection(vec![]),
+                         Shape::CurvePolygon(vec![
+                             Shape::CircularString(ring.clone()),
+                         ]),
+                         Shape::Tin(vec![vec![ring.clone()]]),
+                     ])},
+        ];
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for geometry_ in &geometries {
+                round_trip(&geometry(), format_code, geometry_);
+            }
+        }
+    }
+
+    #[test]
+    fn ewkb()
+    {
+        // SRID=4326;POINT(1 2), in little-endian EWKB.
+        assert_eq!(decode("0101000020E6100000\
+                           000000000000F03F0000000000000040"),
+                   Geometry{srid: Some(4326), has_z: false, has_m: false,
+                            shape: Shape::Point(Some(Coordinate::xy(1.0,
+                                                                    2.0)))});
+
+        // LINESTRING Z (1 2 3, 4 5 6), in big-endian ISO WKB.
+        assert_eq!(decode("00000003EA00000002\
+                           3FF0000000000000400000000000000040080000000000\
+                           00401000000000000040140000000000004018000000000000"),
+                   Geometry{srid: None, has_z: true, has_m: false,
+                            shape: Shape::LineString(vec![
+                                Coordinate::xyz(1.0, 2.0, 3.0),
+                                Coordinate::xyz(4.0, 5.0, 6.0),
+                            ])});
+
+        // Coordinates must agree with the dimensions of the geometry.
+        let ty = geometry();
+        let cx = Context::new(&ty, FormatCode::Binary);
+        let geometry_ = Geometry{
+            srid: None, has_z: true, has_m: false,
+            shape: Shape::Point(Some(Coordinate::xy(1.0, 2.0))),
+        };
+        assert!(geometry_.to_sql(&cx, &mut Vec::new()).is_err());
+
+        // Members must agree with the dimensions of the collection,
+        // and with its type.
+        let ty = geometry();
+        let cx = Context::new(&ty, FormatCode::Text);
+        for hex in [
+            // MULTIPOINT with a POINT Z member.
+            "010400000001000000\
+             0101000080000000000000F03F00000000000000400000000000000840",
+            // MULTIPOINT with a LINESTRING member.
+            "010400000001000000010200000000000000",
+            // Unknown type.
+            "011000000000000000",
+            // ISO type code with unknown dimensions, POINT 4001.
+            "01A10F0000000000000000F03F0000000000000040",
+            // ISO type code with EWKB flags, POINT Z 1001 with FLAG_Z.
+            "01E9030080\
+             000000000000F03F00000000000000400000000000000840",
+            // Trailing data.
+            "0107000000000000000000",
+            "01070000000",
+        ] {
+            assert!(Geometry::from_sql(&cx, hex.as_bytes()).is_err(), "{hex}");
+        }
+
+        // Collections may be nested, but not indefinitely.
+        let nested = |depth| {
+            "010700000001000000".repeat(depth) + "010700000000000000"
+        };
+        assert!(Geometry::from_sql(&cx, nested(63).as_bytes()).is_ok());
+        assert!(Geometry::from_sql(&cx, nested(64).as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,572 @@
+//! Codecs for range and multirange types.
+//!
+//! Ranges are encoded generically in terms of the codec
+//! of the element type, which is given by [`Kind::Range`],
+//! and multiranges in terms of the codec of their range type,
+//! which is given by [`Kind::Multirange`].
+//! In text format, the bounds are in the text format
+//! of the element type, and the literals are parsed and written
+//! by [`parse_range_literal`] and [`write_range_literal`]
+//! and their multirange counterparts.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{
+        Context,
+        Decode,
+        FromSql,
+        IsNull,
+        Kind,
+        ToSql,
+        Type,
+        from_sql_checked_with,
+        invalid_value,
+        is_accepted,
+        parse_multirange_literal,
+        parse_range_literal,
+        text_value,
+        to_sql_checked,
+        write_multirange_literal,
+        write_range_literal,
+    },
+    std::{borrow::Cow, ops::{self, Bound}},
+};
+
+// Flags of a range in binary format, as in `rangetypes.h`.
+const RANGE_EMPTY: u8 = 0x01;
+const RANGE_LB_INC: u8 = 0x02;
+const RANGE_UB_INC: u8 = 0x04;
+const RANGE_LB_INF: u8 = 0x08;
+const RANGE_UB_INF: u8 = 0x10;
+
+/// Value of a range type, such as `int4range` or `tstzrange`.
+///
+/// PostgreSQL normalizes ranges, so ranges that contain no values,
+/// such as `[1,1)`, are returned as [`Range::Empty`],
+/// and ranges of discrete types, such as `int4range` and `daterange`,
+/// are returned with an inclusive lower bound
+/// and an exclusive upper bound.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Range<T>
+{
+    /// The range that contains no values.
+    Empty,
+
+    /// A range with the given bounds.
+    NonEmpty
+    {
+        #[allow(missing_docs)]
+        lower: Bound<T>,
+        #[allow(missing_docs)]
+        upper: Bound<T>,
+    },
+}
+
+/// Value of a multirange type, such as `int4multirange`.
+///
+/// PostgreSQL normalizes multiranges, so the ranges that are returned
+/// are sorted, non-empty, and neither overlap nor touch.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Multirange<T>(pub Vec<Range<T>>);
+
+impl<T> Range<T>
+{
+    /// Whether the range is [`Range::Empty`].
+    pub fn is_empty(&self) -> bool
+    {
+        matches!(self, Self::Empty)
+    }
+}
+
+impl<T> From<ops::Range<T>> for Range<T>
+{
+    /// Create the range `[start,end)`.
+    fn from(range: ops::Range<T>) -> Self
+    {
+        Self::NonEmpty{
+            lower: Bound::Included(range.start),
+            upper: Bound::Excluded(range.end),
+        }
+    }
+}
+
+impl<T> From<ops::RangeInclusive<T>> for Range<T>
+{
+    /// Create the range `[start,end]`.
+    fn from(range: ops::RangeInclusive<T>) -> Self
+    {
+        let (start, end) = range.into_inner();
+        Self::NonEmpty{lower: Bound::Included(start),
+                       upper: Bound::Included(end)}
+    }
+}
+
+impl<T> From<ops::RangeFrom<T>> for Range<T>
+{
+    /// Create the range `[start,)`.
+    fn from(range: ops::RangeFrom<T>) -> Self
+    {
+        Self::NonEmpty{lower: Bound::Included(range.start),
+                       upper: Bound::Unbounded}
+    }
+}
+
+impl<T> From<ops::RangeTo<T>> for Range<T>
+{
+    /// Create the range `(,end)`.
+    fn from(range: ops::RangeTo<T>) -> Self
+    {
+        Self::NonEmpty{lower: Bound::Unbounded,
+                       upper: Bound::Excluded(range.end)}
+    }
+}
+
+/// The element type of a range type.
+fn element_type(ty: &Type) -> Option<&Type>
+{
+    match ty.kind() {
+        Kind::Range(element_type) => Some(element_type),
+        _ => None,
+    }
+}
+
+/// The range type of a multirange type.
+fn range_type(ty: &Type) -> Option<&Type>
+{
+    match ty.kind() {
+        Kind::Multirange(range_type) => Some(range_type),
+        _ => None,
+    }
+}
+
+/// Context for the bounds of a range,
+/// which are in the same format as the range.
+///
+/// If the element type is a domain, the bounds are converted
+/// by the codec of the base type, as with [`from_sql_checked_with`].
+fn element_context<'a>(cx: &Context<'a>) -> Result<Context<'a>>
+{
+    let element_type = element_type(cx.ty())
+        .ok_or_else(|| invalid_value(cx, "not a range type"))?;
+    Ok(Context::new(element_type, cx.format_code())
+        .with_session_parameters(cx.session_parameters()))
+}
+
+/// Context for the ranges of a multirange,
+/// which are in the same format as the multirange.
+fn range_context<'a>(cx: &Context<'a>) -> Result<Context<'a>>
+{
+    let range_type = range_type(cx.ty())
+        .ok_or_else(|| invalid_value(cx, "not a multirange type"))?;
+    Ok(Context::new(range_type, cx.format_code())
+        .with_session_parameters(cx.session_parameters()))
+}
+
+/// Encode a range.
+fn write_range<T>(cx: &Context, range: &Range<T>, buf: &mut Vec<u8>)
+    -> Result<IsNull>
+    where T: ToSql
+{
+    let ecx = element_context(cx)?;
+    if cx.format_code() == FormatCode::Text {
+        let mut literal = String::new();
+        write_range_literal(&range_texts(&ecx, range)?, &mut literal);
+        buf.extend_from_slice(literal.as_bytes());
+        return Ok(IsNull::No);
+    }
+
+    let Range::NonEmpty{lower, upper} = range else {
+        buf.push(RANGE_EMPTY);
+        return Ok(IsNull::No);
+    };
+
+    let flags = |bound: &Bound<T>, inclusive, infinite| match bound {
+        Bound::Included(..) => inclusive,
+        Bound::Excluded(..) => 0,
+        Bound::Unbounded => infinite,
+    };
+    buf.push(flags(lower, RANGE_LB_INC, RANGE_LB_INF)
+             | flags(upper, RANGE_UB_INC, RANGE_UB_INF));
+
+    for bound in [lower, upper] {
+        if let Bound::Included(value) | Bound::Excluded(value) = bound {
+            let start = buf.len();
+            buf.extend_from_slice(&[0; 4]);
+            if to_sql_checked(value, &ecx, buf)? == IsNull::Yes {
+                return Err(invalid_value(cx, "range bound is NULL"));
+            }
+            let length = i32::try_from(buf.len() - start - 4)
+                .map_err(|_| invalid_value(cx, "range bound too large"))?;
+            buf[start .. start + 4].copy_from_slice(&length.to_be_bytes());
+        }
+    }
+
+    Ok(IsNull::No)
+}
+
+/// Encode the bounds of a range in text format,
+/// given the context of the bounds.
+fn range_texts<T>(ecx: &Context, range: &Range<T>) -> Result<Range<String>>
+    where T: ToSql
+{
+    let text = |value: &T| -> Result<String> {
+        let mut buf = Vec::new();
+        if to_sql_checked(value, ecx, &mut buf)? == IsNull::Yes {
+            return Err(invalid_value(ecx, "range bound is NULL"));
+        }
+        Ok(text_value(ecx, &buf)?.to_owned())
+    };
+    let bound = |bound: &Bound<T>| -> Result<Bound<String>> {
+        Ok(match bound {
+            Bound::Included(value) => Bound::Included(text(value)?),
+            Bound::Excluded(value) => Bound::Excluded(text(value)?),
+            Bound::Unbounded => Bound::Unbounded,
+        })
+    };
+    Ok(match range {
+        Range::Empty => Range::Empty,
+        Range::NonEmpty{lower, upper} =>
+            Range::NonEmpty{lower: bound(lower)?, upper: bound(upper)?},
+    })
+}
+
+/// Decode a range, decoding the bounds with the given function.
+///
+/// Bounds in text format that contain escapes are decoded
+/// using [`FromSql::from_sql_temporary`].
+fn read_range<'a, 'b, T>(cx: &Context, raw: &'b [u8], decode: Decode<'b, T>)
+    -> Result<Range<T>>
+    where T: FromSql<'a>
+{
+    let ecx = element_context(cx)?;
+    if cx.format_code() == FormatCode::Text {
+        let literal = parse_range_literal(text_value(cx, raw)?)
+            .map_err(|_| invalid_value(cx, "malformed range literal"))?;
+        return read_range_text(&ecx, literal, decode);
+    }
+
+    let malformed = || invalid_value(cx, "malformed binary value");
+
+    let (&flags, mut raw) = raw.split_first().ok_or_else(malformed)?;
+    if flags & RANGE_EMPTY != 0 {
+        return if raw.is_empty() { Ok(Range::Empty) } else { Err(malformed()) };
+    }
+    if flags & !(RANGE_LB_INC | RANGE_UB_INC | RANGE_LB_INF | RANGE_UB_INF)
+        != 0 {
+        return Err(invalid_value(cx, "invalid flags"));
+    }
+
+    let mut bound = |inclusive, infinite| -> Result<Bound<T>> {
+        if flags & infinite != 0 {
+            return Ok(Bound::Unbounded);
+        }
+        let (length, rest) = raw.split_first_chunk::<4>()
+            .ok_or_else(malformed)?;
+        let value = usize::try_from(i32::from_be_bytes(*length)).ok()
+            .and_then(|length| rest.split_at_checked(length))
+            .map(|(value, rest)| { raw = rest; value })
+            .ok_or_else(|| invalid_value(cx, "malformed range bound"))?;
+        let value = from_sql_checked_with(&ecx, Some(value), decode)?;
+        Ok(if flags & inclusive != 0 {
+            Bound::Included(value)
+        } else {
+            Bound::Excluded(value)
+        })
+    };
+    let lower = bound(RANGE_LB_INC, RANGE_LB_INF)?;
+    let upper = bound(RANGE_UB_INC, RANGE_UB_INF)?;
+
+    if !raw.is_empty() {
+        return Err(invalid_value(cx, "trailing data"));
+    }
+
+    Ok(Range::NonEmpty{lower, upper})
+}
+
+/// Decode the bounds of a range literal,
+/// given the context of the bounds.
+fn read_range_text<'a, 'b, T>(
+    ecx: &Context,
+    literal: Range<Cow<'b, str>>,
+    decode: Decode<'b, T>,
+) -> Result<Range<T>>
+    where T: FromSql<'a>
+{
+    let value = |value: Cow<'b, str>| match value {
+        Cow::Borrowed(value) =>
+            from_sql_checked_with(ecx, Some(value.as_bytes()), decode),
+        Cow::Owned(value) =>
+            from_sql_checked_with(ecx, Some(value.as_bytes()),
+                                  T::from_sql_temporary),
+    };
+    let bound = |bound| -> Result<Bound<T>> {
+        Ok(match bound {
+            Bound::Included(bound) => Bound::Included(value(bound)?),
+            Bound::Excluded(bound) => Bound::Excluded(value(bound)?),
+            Bound::Unbounded => Bound::Unbounded,
+        })
+    };
+    Ok(match literal {
+        Range::Empty => Range::Empty,
+        Range::NonEmpty{lower, upper} =>
+            Range::NonEmpty{lower: bound(lower)?, upper: bound(upper)?},
+    })
+}
+
+/// Encode a multirange.
+fn write_multirange<T>(cx: &Context, ranges: &[Range<T>], buf: &mut Vec<u8>)
+    -> Result<IsNull>
+    where T: ToSql
+{
+    let rcx = range_context(cx)?;
+    if cx.format_code() == FormatCode::Text {
+        let ecx = element_context(&rcx)?;
+        let ranges = ranges.iter()
+            .map(|range| range_texts(&ecx, range))
+            .collect::<Result<Vec<_>>>()?;
+        let mut literal = String::new();
+        write_multirange_literal(&ranges, &mut literal);
+        buf.extend_from_slice(literal.as_bytes());
+        return Ok(IsNull::No);
+    }
+
+    let too_large = || invalid_value(cx, "multirange too large");
+
+    let count = i32::try_from(ranges.len()).map_err(|_| too_large())?;
+    buf.extend_from_slice(&count.to_be_bytes());
+    for range in ranges {
+        let start = buf.len();
+        buf.extend_from_slice(&[0; 4]);
+        write_range(&rcx, range, buf)?;
+        let length = i32::try_from(buf.len() - start - 4)
+            .map_err(|_| too_large())?;
+        buf[start .. start + 4].copy_from_slice(&length.to_be_bytes());
+    }
+
+    Ok(IsNull::No)
+}
+
+/// Decode a multirange, decoding the bounds with the given function.
+fn read_multirange<'a, 'b, T>(
+    cx: &Context,
+    mut raw: &'b [u8],
+    decode: Decode<'b, T>,
+) -> Result<Multirange<T>>
+    where T: FromSql<'a>
+{
+    let rcx = range_context(cx)?;
+    if cx.format_code() == FormatCode::Text {
+        let ecx = element_context(&rcx)?;
+        let literal = parse_multirange_literal(text_value(cx, raw)?)
+            .map_err(|_| invalid_value(cx, "malformed multirange literal"))?;
+        let ranges = literal.into_iter()
+            .map(|range| read_range_text(&ecx, range, decode))
+            .collect::<Result<_>>()?;
+        return Ok(Multirange(ranges));
+    }
+
+    let read_i32 = |raw: &mut &[u8]| -> Result<i32> {
+        let (bytes, rest) = raw.split_first_chunk::<4>()
+            .ok_or_else(|| invalid_value(cx, "malformed binary value"))?;
+        *raw = rest;
+        Ok(i32::from_be_bytes(*bytes))
+    };
+
+    let count = usize::try_from(read_i32(&mut raw)?)
+        .map_err(|_| invalid_value(cx, "invalid number of ranges"))?;
+
+    // Each range takes at least five bytes, so a corrupt
+    // count cannot cause an arbitrarily large allocation.
+    let mut ranges = Vec::with_capacity(count.min(raw.len() / 5));
+    for _ in 0 .. count {
+        let length = read_i32(&mut raw)?;
+        let range = usize::try_from(length).ok()
+            .and_then(|length| raw.split_at_checked(length))
+            .map(|(range, rest)| { raw = rest; range })
+            .ok_or_else(|| invalid_value(cx, "malformed range"))?;
+        ranges.push(read_range(&rcx, range, decode)?);
+    }
+
+    if !raw.is_empty() {
+        return Err(invalid_value(cx, "trailing data"));
+    }
+
+    Ok(Multirange(ranges))
+}
+
+impl<T> ToSql for Range<T>
+    where T: ToSql
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        element_type(ty).is_some_and(|ty| is_accepted(ty, T::accepts))
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        write_range(cx, self, buf)
+    }
+}
+
+impl<'a, T> FromSql<'a> for Range<T>
+    where T: FromSql<'a>
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        element_type(ty).is_some_and(|ty| is_accepted(ty, T::accepts))
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        read_range(cx, raw, T::from_sql)
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        read_range(cx, raw, T::from_sql_temporary)
+    }
+}
+
+impl<T> ToSql for Multirange<T>
+    where T: ToSql
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        range_type(ty).is_some_and(<Range<T> as ToSql>::accepts)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        write_multirange(cx, &self.0, buf)
+    }
+}
+
+impl<'a, T> FromSql<'a> for Multirange<T>
+    where T: FromSql<'a>
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        range_type(ty).is_some_and(<Range<T> as FromSql>::accepts)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        read_multirange(cx, raw, T::from_sql)
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        read_multirange(cx, raw, T::from_sql_temporary)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::{
+            Error,
+            types::{
+                Date,
+                Numeric,
+                TimestampTz,
+                from_sql_checked,
+                tests::{fuzz_from_sql, round_trip},
+            },
+        },
+        super::*,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<Range<i32>>(&Type::INT4RANGE);
+        fuzz_from_sql::<Range<Numeric>>(&Type::NUMRANGE);
+        fuzz_from_sql::<Multirange<i64>>(&Type::INT8MULTIRANGE);
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        let ranges = [
+            Range::Empty,
+            Range::from(1 .. 5),
+            Range::from(-3 ..= 7),
+            Range::from(10 ..),
+            Range::from(.. 0),
+            Range::NonEmpty{lower: Bound::Unbounded, upper: Bound::Unbounded},
+            Range::NonEmpty{lower: Bound::Excluded(i64::MIN),
+                            upper: Bound::Included(i64::MAX)},
+        ];
+        let numerics = Range::from("1.5".parse::<Numeric>().unwrap() ..);
+        let strings = Range::NonEmpty{
+            lower: Bound::Included(String::new()),
+            upper: Bound::Excluded("a \"b\" [c,d]\\".to_owned()),
+        };
+
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for range in ranges {
+                round_trip(&Type::INT8RANGE, format_code, &range);
+            }
+            round_trip(&Type::INT8MULTIRANGE, format_code,
+                       &Multirange(ranges.to_vec()));
+            round_trip(&Type::INT8MULTIRANGE, format_code,
+                       &Multirange::<i64>::default());
+            round_trip(&Type::NUMRANGE, format_code, &numerics);
+            round_trip(&Type::DATERANGE, format_code,
+                       &Range::<Date>::from(.. Date::from_days(0)));
+            round_trip(&Type::TSTZMULTIRANGE, format_code,
+                       &Multirange(vec![Range::<TimestampTz>::Empty]));
+
+            // There is no built-in range of text, which has escapes.
+            round_trip(&text_range(), format_code, &strings);
+        }
+    }
+
+    fn text_range() -> Type
+    {
+        Type::new(0, "textrange".to_owned(), Kind::Range(Type::TEXT))
+    }
+
+    #[test]
+    fn text()
+    {
+        let cx = Context::new(&Type::INT4RANGE, FormatCode::Text);
+        let range: Range<i32> = from_sql_checked(&cx, Some(b"[1,5)"))
+            .unwrap();
+        assert_eq!(range, Range::from(1 .. 5));
+        let range: Range<i32> = from_sql_checked(&cx, Some(b" EMPTY "))
+            .unwrap();
+        assert_eq!(range, Range::Empty);
+        assert!(from_sql_checked::<Range<i32>>(&cx, Some(b"[1,x)")).is_err());
+
+        // Bounds with escapes cannot be borrowed.
+        let ty = text_range();
+        let cx = Context::new(&ty, FormatCode::Text);
+        let strs = from_sql_checked::<Range<&str>>(&cx, Some(br#"["a""b",)"#));
+        assert!(matches!(strs, Err(Error::Conversion(..))));
+        let strs: Range<&str> = from_sql_checked(&cx, Some(br#"("a b",c]"#))
+            .unwrap();
+        assert_eq!(strs, Range::NonEmpty{lower: Bound::Excluded("a b"),
+                                         upper: Bound::Included("c")});
+    }
+
+    #[test]
+    fn rejects()
+    {
+        let cx = Context::new(&Type::INT4RANGE, FormatCode::Binary);
+        let decode =
+            |raw: &[u8]| from_sql_checked::<Range<i32>>(&cx, Some(raw));
+
+        let mut raw = vec![RANGE_LB_INC | RANGE_UB_INF, 0, 0, 0, 4, 0, 0, 0, 7];
+        assert_eq!(decode(&raw).unwrap(), Range::from(7 ..));
+        raw.push(0);
+        assert!(decode(&raw).is_err());
+
+        assert!(decode(&[]).is_err());
+        assert!(decode(&[RANGE_EMPTY, 0]).is_err());
+        assert!(decode(&[RANGE_LB_INF | RANGE_UB_INF | 0x20]).is_err());
+        assert!(decode(&[RANGE_UB_INF, 0, 0, 0, 4, 0, 0, 0]).is_err());
+        assert!(decode(&[RANGE_UB_INF, 0xFF, 0xFF, 0xFF, 0xFF]).is_err());
+    }
+}
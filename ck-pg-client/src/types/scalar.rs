@@ -0,0 +1,275 @@
+//! Codecs for `bool`, `"char"`, integer, floating-point,
+//! object identifier, and transaction identifier types.
+//!
+//! Besides `oid`, `u32` accepts the OID alias types, such as `regclass`,
+//! and `xid` and `cid`; `u64` accepts `xid8`.
+//! In text format, values of the OID alias types are the names
+//! of the objects they refer to, which cannot be decoded as numbers,
+//! but can be decoded as strings.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{
+        Context,
+        FromSql,
+        IsNull,
+        ToSql,
+        Type,
+        fixed_length,
+        invalid_value,
+        text_value,
+    },
+    std::io::Write,
+};
+
+impl ToSql for bool
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::BOOL
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        match cx.format_code() {
+            FormatCode::Binary => buf.push(u8::from(*self)),
+            FormatCode::Text => buf.push(if *self { b't' } else { b'f' }),
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl<'a> FromSql<'a> for bool
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::BOOL
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        match (cx.format_code(), raw) {
+            (FormatCode::Binary, [0]) | (FormatCode::Text, b"f") => Ok(false),
+            (FormatCode::Binary, [1]) | (FormatCode::Text, b"t") => Ok(true),
+            _ => Err(invalid_value(cx, "expected true or false")),
+        }
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+/// The `"char"` type, which is a single byte.
+///
+/// In text format, bytes outside the ASCII range
+/// are represented as octal escapes, such as `\377`.
+impl ToSql for i8
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::CHAR
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        let byte = self.to_be_bytes()[0];
+        match cx.format_code() {
+            FormatCode::Binary => buf.push(byte),
+            FormatCode::Text if byte == 0 => (),
+            FormatCode::Text if byte.is_ascii() => buf.push(byte),
+            FormatCode::Text => write!(buf, "\\{byte:03o}")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl<'a> FromSql<'a> for i8
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::CHAR
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        let byte = match (cx.format_code(), raw) {
+            (FormatCode::Binary, &[byte]) => byte,
+            (FormatCode::Text, []) => 0,
+            (FormatCode::Text, &[byte]) if byte.is_ascii() => byte,
+            (FormatCode::Text, [b'\\', digits @ ..]) if digits.len() == 3 =>
+                text_value(cx, digits).ok()
+                    .and_then(|digits| u8::from_str_radix(digits, 8).ok())
+                    .ok_or_else(|| invalid_value(cx, "invalid octal escape"))?,
+            _ => return Err(invalid_value(cx, "expected a single byte")),
+        };
+        Ok(i8::from_be_bytes([byte]))
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+/// Implement [`ToSql`] and [`FromSql`] for a number type.
+///
+/// The binary format is big-endian,
+/// and the text format is what `Display` and `FromStr` use.
+macro_rules! number_codec
+{
+    ($rust:ty, $($pg:ident)|+, $write_text:expr) => {
+        impl ToSql for $rust
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                [$(Type::$pg),+].contains(ty)
+            }
+
+            fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>)
+                -> Result<IsNull>
+            {
+                match cx.format_code() {
+                    FormatCode::Binary =>
+                        buf.extend_from_slice(&self.to_be_bytes()),
+                    FormatCode::Text =>
+                        $write_text(*self, buf)?,
+                }
+                Ok(IsNull::No)
+            }
+        }
+
+        impl<'a> FromSql<'a> for $rust
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                [$(Type::$pg),+].contains(ty)
+            }
+
+            fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+            {
+                match cx.format_code() {
+                    FormatCode::Binary =>
+                        Ok(Self::from_be_bytes(fixed_length(cx, raw)?)),
+                    FormatCode::Text =>
+                        text_value(cx, raw)?.parse()
+                            .map_err(|_| invalid_value(cx, "not a number")),
+                }
+            }
+
+            fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+            {
+                <Self as FromSql>::from_sql(cx, raw)
+            }
+        }
+    };
+}
+
+number_codec!(i16, INT2, write_integer_text);
+number_codec!(i32, INT4, write_integer_text);
+number_codec!(i64, INT8, write_integer_text);
+number_codec!(
+    u32,
+    OID | REGPROC | REGPROCEDURE | REGOPER | REGOPERATOR | REGCLASS
+        | REGTYPE | REGCONFIG | REGDICTIONARY | REGNAMESPACE | REGROLE
+        | REGCOLLATION | XID | CID,
+    write_integer_text
+);
+number_codec!(u64, XID8, write_integer_text);
+number_codec!(f32, FLOAT4, write_float_text);
+number_codec!(f64, FLOAT8, write_float_text);
+
+fn write_integer_text<T>(value: T, buf: &mut Vec<u8>) -> Result<()>
+    where T: std::fmt::Display
+{
+    write!(buf, "{value}")?;
+    Ok(())
+}
+
+/// Write a float in text format.
+///
+/// Infinities are spelled the way PostgreSQL spells them,
+/// which is not the way Rust spells them.
+fn write_float_text<T>(value: T, buf: &mut Vec<u8>) -> Result<()>
+    where T: Copy + Into<f64> + std::fmt::Display
+{
+    match value.into() {
+        f64::INFINITY => buf.extend_from_slice(b"Infinity"),
+        f64::NEG_INFINITY => buf.extend_from_slice(b"-Infinity"),
+        _ => write!(buf, "{value}")?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::tests::{fuzz_from_sql, round_trip},
+        super::*,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<bool>(&Type::BOOL);
+        fuzz_from_sql::<i8>(&Type::CHAR);
+        fuzz_from_sql::<i16>(&Type::INT2);
+        fuzz_from_sql::<i32>(&Type::INT4);
+        fuzz_from_sql::<i64>(&Type::INT8);
+        fuzz_from_sql::<u32>(&Type::OID);
+        fuzz_from_sql::<u64>(&Type::XID8);
+        fuzz_from_sql::<f32>(&Type::FLOAT4);
+        fuzz_from_sql::<f64>(&Type::FLOAT8);
+    }
+
+    #[test]
+    fn malformed_length()
+    {
+        let cx = Context::new(&Type::INT4, FormatCode::Binary);
+        assert!(i32::from_sql(&cx, &[0, 0, 1]).is_err());
+        assert!(i32::from_sql(&cx, &[0, 0, 0, 1, 0]).is_err());
+        assert_eq!(i32::from_sql(&cx, &[0, 0, 1, 0]).unwrap(), 256);
+
+        let cx = Context::new(&Type::BOOL, FormatCode::Binary);
+        assert!(bool::from_sql(&cx, &[2]).is_err());
+        assert!(bool::from_sql(&cx, &[]).is_err());
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            round_trip(&Type::BOOL, format_code, &true);
+            round_trip(&Type::BOOL, format_code, &false);
+            for value in [0, 1, b'a' as i8, -1, i8::MIN, i8::MAX] {
+                round_trip(&Type::CHAR, format_code, &value);
+            }
+            for value in [0, -1, i16::MIN, i16::MAX] {
+                round_trip(&Type::INT2, format_code, &value);
+            }
+            for value in [0, -1, i32::MIN, i32::MAX] {
+                round_trip(&Type::INT4, format_code, &value);
+            }
+            for value in [0, -1, i64::MIN, i64::MAX] {
+                round_trip(&Type::INT8, format_code, &value);
+            }
+            for value in [0, u32::MAX] {
+                round_trip(&Type::OID, format_code, &value);
+                round_trip(&Type::XID, format_code, &value);
+            }
+            for value in [0, u64::MAX] {
+                round_trip(&Type::XID8, format_code, &value);
+            }
+            for value in [0.1, -0.0, f32::MIN_POSITIVE, f32::INFINITY,
+                          f32::NEG_INFINITY, f32::MAX] {
+                round_trip(&Type::FLOAT4, format_code, &value);
+            }
+            for value in [0.1, -0.0, f64::MIN_POSITIVE, f64::INFINITY,
+                          f64::NEG_INFINITY, f64::MAX] {
+                round_trip(&Type::FLOAT8, format_code, &value);
+            }
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Codecs for `tid`, `pg_lsn`, `pg_snapshot`, and `txid_snapshot`.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{
+        Context,
+        FromSql,
+        IsNull,
+        ToSql,
+        Type,
+        fixed_length,
+        invalid_value,
+        text_value,
+    },
+    std::{fmt, io::Write, str::FromStr},
+    thiserror::Error,
+};
+
+/// Value of type `tid`, which is the physical location of a row.
+///
+/// Tuple identifiers are what the `ctid` system column contains.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Tid
+{
+    /// Number of the block that contains the row.
+    pub block: u32,
+
+    /// Position of the row within the block, starting at 1.
+    pub offset: u16,
+}
+
+/// Value of type `pg_lsn`, which is a position in the write-ahead log.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PgLsn(pub u64);
+
+/// Value of type `pg_snapshot` or `txid_snapshot`,
+/// which is the set of transactions visible to a snapshot.
+///
+/// Transactions before `xmin` are visible unless they were aborted,
+/// transactions from `xmax` onwards are not visible,
+/// and neither are the transactions in `xip`.
+/// The server checks that `xip` is sorted and lies in this range.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Snapshot
+{
+    /// Earliest transaction that was still running.
+    pub xmin: u64,
+
+    /// First transaction that had not yet been assigned.
+    pub xmax: u64,
+
+    /// Transactions that were running, in ascending order.
+    pub xip: Vec<u64>,
+}
+
+/// Error returned when parsing a [`Tid`], [`PgLsn`],
+/// or [`Snapshot`] fails.
+#[derive(Clone, Debug, Error)]
+#[error("invalid tid, pg_lsn, or snapshot literal")]
+pub struct ParseSystemValueError(());
+
+/// Write a tuple identifier as `(block,offset)`, as in `(0,1)`.
+impl fmt::Display for Tid
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "({},{})", self.block, self.offset)
+    }
+}
+
+/// Write a position as two hexadecimal numbers,
+/// the high and the low 32 bits, as in `16/B374D848`.
+impl fmt::Display for PgLsn
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 & 0xFFFF_FFFF)
+    }
+}
+
+/// Write a snapshot as `xmin:xmax:xip`, as in `10:20:10,14,15`.
+impl fmt::Display for Snapshot
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}:{}:", self.xmin, self.xmax)?;
+        for (i, xid) in self.xip.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            write!(f, "{separator}{xid}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Tid
+{
+    type Err = ParseSystemValueError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        let (block, offset) = s.strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .and_then(|s| s.split_once(','))
+            .ok_or(ParseSystemValueError(()))?;
+        Ok(Self{block: parse_decimal(block)?, offset: parse_decimal(offset)?})
+    }
+}
+
+impl FromStr for PgLsn
+{
+    type Err = ParseSystemValueError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        let parse_half = |s: &str| {
+            if !(1 ..= 8).contains(&s.len())
+                || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(ParseSystemValueError(()));
+            }
+            u64::from_str_radix(s, 16).map_err(|_| ParseSystemValueError(()))
+        };
+        let (high, low) = s.split_once('/').ok_or(ParseSystemValueError(()))?;
+        Ok(Self((parse_half(high)? << 32) | parse_half(low)?))
+    }
+}
+
+impl FromStr for Snapshot
+{
+    type Err = ParseSystemValueError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        let mut parts = s.splitn(3, ':');
+        let mut next = || parts.next().ok_or(ParseSystemValueError(()));
+        let xmin = parse_decimal(next()?)?;
+        let xmax = parse_decimal(next()?)?;
+        let xip = match next()? {
+            "" => Vec::new(),
+            xip => xip.split(',').map(parse_decimal)
+                .collect::<std::result::Result<_, _>>()?,
+        };
+        Ok(Self{xmin, xmax, xip})
+    }
+}
+
+/// Parse an unsigned decimal number without a sign.
+fn parse_decimal<T>(s: &str) -> std::result::Result<T, ParseSystemValueError>
+    where T: FromStr
+{
+    if !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseSystemValueError(()));
+    }
+    s.parse().map_err(|_| ParseSystemValueError(()))
+}
+
+/// Read a snapshot in binary format, which consists of
+/// the number of running transactions, `xmin`, `xmax`,
+/// and the running transactions.
+fn read_snapshot(cx: &Context, raw: &[u8]) -> Result<Snapshot>
+{
+    let malformed = || invalid_value(cx, "malformed binary value");
+    let (header, xip) = raw.split_first_chunk::<20>().ok_or_else(malformed)?;
+    let (nxip, header) = header.split_first_chunk::<4>().ok_or_else(malformed)?;
+    let (xmin, xmax) = header.split_at(8);
+    let nxip = usize::try_from(i32::from_be_bytes(*nxip))
+        .map_err(|_| malformed())?;
+    let (xip, []) = xip.as_chunks::<8>()
+        else { return Err(malformed()) };
+    if xip.len() != nxip {
+        return Err(malformed());
+    }
+    Ok(Snapshot{
+        xmin: u64::from_be_bytes(fixed_length(cx, xmin)?),
+        xmax: u64::from_be_bytes(fixed_length(cx, xmax)?),
+        xip: xip.iter().map(|&xid| u64::from_be_bytes(xid)).collect(),
+    })
+}
+
+/// Write a snapshot in binary format.
+fn write_snapshot(cx: &Context, value: &Snapshot, buf: &mut Vec<u8>)
+    -> Result<()>
+{
+    let nxip = i32::try_from(value.xip.len())
+        .map_err(|_| invalid_value(cx, "too many transactions"))?;
+    buf.extend_from_slice(&nxip.to_be_bytes());
+    buf.extend_from_slice(&value.xmin.to_be_bytes());
+    buf.extend_from_slice(&value.xmax.to_be_bytes());
+    for xid in &value.xip {
+        buf.extend_from_slice(&xid.to_be_bytes());
+    }
+    Ok(())
+}
+
+/// Implement [`ToSql`] and [`FromSql`] for a system type.
+///
+/// The text format is what `Display` produces and `FromStr` parses.
+macro_rules! system_codec
+{
+    ($rust:ty, $($pg:ident)|+, $encode:expr, $decode:expr) => {
+        impl ToSql for $rust
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                [$(Type::$pg),+].contains(ty)
+            }
+
+            fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>)
+                -> Result<IsNull>
+            {
+                match cx.format_code() {
+                    FormatCode::Binary => $encode(cx, self, buf)?,
+                    FormatCode::Text => write!(buf, "{self}")?,
+                }
+                Ok(IsNull::No)
+            }
+        }
+
+        impl<'a> FromSql<'a> for $rust
+        {
+            fn accepts(ty: &Type) -> bool
+            {
+                [$(Type::$pg),+].contains(ty)
+            }
+
+            fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+            {
+                match cx.format_code() {
+                    FormatCode::Binary => $decode(cx, raw),
+                    FormatCode::Text => text_value(cx, raw)?.parse()
+                        .map_err(|_| invalid_value(cx, "malformed text")),
+                }
+            }
+
+            fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+            {
+                <Self as FromSql>::from_sql(cx, raw)
+            }
+        }
+    };
+}
+
+system_codec!(
+    Tid, TID,
+    |_, value: &Tid, buf: &mut Vec<u8>| -> Result<()> {
+        buf.extend_from_slice(&value.block.to_be_bytes());
+        buf.extend_from_slice(&value.offset.to_be_bytes());
+        Ok(())
+    },
+    |cx, raw| {
+        let raw: [u8; 6] = fixed_length(cx, raw)?;
+        let (block, offset) = raw.split_at(4);
+        Ok(Tid{block: u32::from_be_bytes(fixed_length(cx, block)?),
+               offset: u16::from_be_bytes(fixed_length(cx, offset)?)})
+    }
+);
+
+system_codec!(
+    PgLsn, PG_LSN,
+    |_, value: &PgLsn, buf: &mut Vec<u8>| -> Result<()> {
+        buf.extend_from_slice(&value.0.to_be_bytes());
+        Ok(())
+    },
+    |cx, raw| fixed_length(cx, raw).map(|raw| PgLsn(u64::from_be_bytes(raw)))
+);
+
+system_codec!(
+    Snapshot, PG_SNAPSHOT | TXID_SNAPSHOT,
+    write_snapshot,
+    read_snapshot
+);
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::tests::{fuzz_from_sql, round_trip},
+        super::*,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<Tid>(&Type::TID);
+        fuzz_from_sql::<PgLsn>(&Type::PG_LSN);
+        fuzz_from_sql::<Snapshot>(&Type::PG_SNAPSHOT);
+    }
+
+    #[test]
+    fn parse()
+    {
+        assert_eq!("(0,1)".parse::<Tid>().unwrap(), Tid{block: 0, offset: 1});
+        assert_eq!("(4294967295,65535)".parse::<Tid>().unwrap(),
+                   Tid{block: u32::MAX, offset: u16::MAX});
+        for s in ["0,1", "(0,1", "(0)", "(-1,1)", "(+0,1)", "(0,65536)"] {
+            assert!(s.parse::<Tid>().is_err(), "{s}");
+        }
+
+        assert_eq!("16/B374D848".parse::<PgLsn>().unwrap(),
+                   PgLsn(0x16_B374_D848));
+        assert_eq!("ffffffff/0".parse::<PgLsn>().unwrap(),
+                   PgLsn(0xFFFF_FFFF_0000_0000));
+        for s in ["16", "/0", "0/", "123456789/0", "+1/0", "0/x"] {
+            assert!(s.parse::<PgLsn>().is_err(), "{s}");
+        }
+
+        assert_eq!("10:20:10,14,15".parse::<Snapshot>().unwrap(),
+                   Snapshot{xmin: 10, xmax: 20, xip: vec![10, 14, 15]});
+        assert_eq!("3:3:".parse::<Snapshot>().unwrap(),
+                   Snapshot{xmin: 3, xmax: 3, xip: vec![]});
+        for s in ["3:3", "3:3:,", "3:3:4,", "a:3:", "3:3:4:5"] {
+            assert!(s.parse::<Snapshot>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn display()
+    {
+        assert_eq!(Tid{block: 7, offset: 2}.to_string(), "(7,2)");
+        assert_eq!(PgLsn(0x16_B374_D848).to_string(), "16/B374D848");
+        assert_eq!(PgLsn(0).to_string(), "0/0");
+        assert_eq!(Snapshot{xmin: 10, xmax: 20, xip: vec![10, 14]}
+                       .to_string(),
+                   "10:20:10,14");
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for value in [Tid::default(), Tid{block: u32::MAX, offset: 1}] {
+                round_trip(&Type::TID, format_code, &value);
+            }
+            for value in [PgLsn(0), PgLsn(0x16_B374_D848), PgLsn(u64::MAX)] {
+                round_trip(&Type::PG_LSN, format_code, &value);
+            }
+            for value in [
+                Snapshot{xmin: 1, xmax: 1, xip: vec![]},
+                Snapshot{xmin: 10, xmax: 20, xip: vec![10, 14, 15]},
+            ] {
+                round_trip(&Type::PG_SNAPSHOT, format_code, &value);
+                round_trip(&Type::TXID_SNAPSHOT, format_code, &value);
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Utilities for testing codecs.
+
+use {
+    crate::{
+        protocol::FormatCode,
+        types::{Context, FromSql, ToSql, Type, from_sql_checked},
+    },
+    std::fmt,
+};
+
+/// Malformed and well-formed inputs for [`fuzz_from_sql`].
+///
+/// These are all inputs of up to two bytes, as well as
+/// pseudo-random inputs and repeated bytes of up to 64 bytes.
+pub fn fuzz_inputs() -> Vec<Vec<u8>>
+{
+    let mut inputs = vec![vec![]];
+    for a in 0 ..= 255 {
+        inputs.push(vec![a]);
+        for b in 0 ..= 255 {
+            inputs.push(vec![a, b]);
+        }
+    }
+
+    // xorshift32, for reproducible pseudo-random inputs.
+    let mut state = 0x2545_F491_u32;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state.to_be_bytes()[0]
+    };
+
+    for length in 3 ..= 64 {
+        for _ in 0 .. 64 {
+            inputs.push((0 .. length).map(|_| next()).collect());
+        }
+        for byte in [0x00, 0x01, 0x7F, 0x80, 0xFF] {
+            inputs.push(vec![byte; length]);
+        }
+    }
+
+    inputs
+}
+
+/// Decode every input of [`fuzz_inputs`] in both formats.
+///
+/// Decoding must never panic, whatever the input;
+/// invalid inputs must result in an error.
+pub fn fuzz_from_sql<T>(ty: &Type)
+    where T: for<'a> FromSql<'a>
+{
+    assert!(T::accepts(ty), "{ty} not accepted");
+    for input in fuzz_inputs() {
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            let cx = Context::new(ty, format_code);
+            let _ = from_sql_checked::<T>(&cx, Some(&input));
+        }
+    }
+}
+
+/// Encode a value in the given format and decode it again.
+pub fn round_trip<T>(ty: &Type, format_code: FormatCode, value: &T)
+    where T: ToSql + for<'a> FromSql<'a> + fmt::Debug + PartialEq
+{
+    let cx = Context::new(ty, format_code);
+    let mut buf = Vec::new();
+    value.to_sql(&cx, &mut buf).unwrap();
+    let decoded: T = from_sql_checked(&cx, Some(&buf)).unwrap();
+    assert_eq!(&decoded, value, "{ty} {format_code:?} {buf:?}");
+}
@@ -0,0 +1,293 @@
+//! Codecs for character string types and `bytea`.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{
+        Context,
+        FromSql,
+        IsNull,
+        Kind,
+        ToSql,
+        Type,
+        invalid_value,
+        json::{is_json_type, strip_json_header, write_json_header},
+        text_value,
+    },
+    std::io::Write,
+};
+
+/// Whether a type is a character string type.
+///
+/// Besides the built-in types, these are `xml`, which the server checks
+/// for well-formedness, enum types, whose values are their labels,
+/// and `citext` from the extension of that name,
+/// which is accepted by its name, in any schema.
+/// The binary format of these types is the same as their text format.
+fn is_string_type(ty: &Type) -> bool
+{
+    [Type::TEXT, Type::VARCHAR, Type::BPCHAR, Type::NAME, Type::UNKNOWN,
+     Type::XML]
+        .contains(ty)
+        || matches!(ty.kind(), Kind::Enum(..))
+        || ty.name() == "citext"
+}
+
+/// Whether a type is an OID alias type, such as `regclass`,
+/// whose values are names of objects in text format
+/// and OIDs in binary format.
+fn is_oid_alias_type(ty: &Type) -> bool
+{
+    [Type::REGPROC, Type::REGPROCEDURE, Type::REGOPER, Type::REGOPERATOR,
+     Type::REGCLASS, Type::REGTYPE, Type::REGCONFIG, Type::REGDICTIONARY,
+     Type::REGNAMESPACE, Type::REGROLE, Type::REGCOLLATION]
+        .contains(ty)
+}
+
+/// Values of type `json` and `jsonb` are their JSON text,
+/// which is not checked for validity.
+impl ToSql for &str
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_string_type(ty) || is_json_type(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        write_json_header(cx, buf);
+        buf.extend_from_slice(self.as_bytes());
+        Ok(IsNull::No)
+    }
+}
+
+/// Values of type `bpchar` (`character(n)`)
+/// are padded with spaces to their declared length.
+/// Values of type `json` and `jsonb` are their JSON text.
+/// Values of OID alias types, such as `regclass`, are the names
+/// of the objects, in text format only; decode them into `u32`
+/// to obtain the OIDs.
+impl<'a> FromSql<'a> for &'a str
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_string_type(ty) || is_json_type(ty) || is_oid_alias_type(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        let binary = cx.format_code() == FormatCode::Binary;
+        if binary && is_oid_alias_type(cx.ty()) {
+            return Err(invalid_value(cx, "binary format not supported"));
+        }
+        text_value(cx, strip_json_header(cx, raw)?)
+    }
+}
+
+impl ToSql for String
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <&str as ToSql>::accepts(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        self.as_str().to_sql(cx, buf)
+    }
+}
+
+impl<'a> FromSql<'a> for String
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <&str as FromSql>::accepts(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        <&str>::from_sql(cx, raw).map(str::to_owned)
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+/// In text format, `bytea` values are encoded in hex format,
+/// and decoded from either hex format or escape format,
+/// depending on the `bytea_output` setting of the server.
+impl ToSql for &[u8]
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::BYTEA
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        match cx.format_code() {
+            FormatCode::Binary =>
+                buf.extend_from_slice(self),
+            FormatCode::Text => {
+                buf.extend_from_slice(b"\\x");
+                for byte in *self {
+                    write!(buf, "{byte:02x}")?;
+                }
+            },
+        }
+        Ok(IsNull::No)
+    }
+}
+
+/// Unlike for the other impls, decoding `bytea` values in text format
+/// is not supported, as it would require an allocation.
+impl<'a> FromSql<'a> for &'a [u8]
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::BYTEA
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        match cx.format_code() {
+            FormatCode::Binary => Ok(raw),
+            FormatCode::Text =>
+                Err(invalid_value(cx, "text format not supported for &[u8]")),
+        }
+    }
+}
+
+impl ToSql for Vec<u8>
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <&[u8] as ToSql>::accepts(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        self.as_slice().to_sql(cx, buf)
+    }
+}
+
+impl<'a> FromSql<'a> for Vec<u8>
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <&[u8] as FromSql>::accepts(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        match cx.format_code() {
+            FormatCode::Binary => Ok(raw.to_vec()),
+            FormatCode::Text => decode_bytea_text(raw)
+                .ok_or_else(|| invalid_value(cx, "invalid bytea literal")),
+        }
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+/// Decode a `bytea` value in hex format or escape format.
+fn decode_bytea_text(raw: &[u8]) -> Option<Vec<u8>>
+{
+    if let Some(hex) = raw.strip_prefix(b"\\x") {
+        if hex.len() % 2 != 0 {
+            return None;
+        }
+        return hex.chunks_exact(2)
+            .map(|pair| {
+                // from_str_radix would also accept a sign.
+                if !pair.iter().all(u8::is_ascii_hexdigit) {
+                    return None;
+                }
+                let pair = std::str::from_utf8(pair).ok()?;
+                u8::from_str_radix(pair, 16).ok()
+            })
+            .collect();
+    }
+
+    let mut bytes = Vec::with_capacity(raw.len());
+    let mut rest = raw;
+    while let [first, tail @ ..] = rest {
+        match (first, tail) {
+            (b'\\', [b'\\', tail @ ..]) => {
+                bytes.push(b'\\');
+                rest = tail;
+            },
+            (b'\\', [a @ b'0' ..= b'3', b @ b'0' ..= b'7',
+                     c @ b'0' ..= b'7', tail @ ..]) => {
+                bytes.push((a - b'0') << 6 | (b - b'0') << 3 | (c - b'0'));
+                rest = tail;
+            },
+            (b'\\', _) =>
+                return None,
+            (&byte, tail) => {
+                bytes.push(byte);
+                rest = tail;
+            },
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::{
+            from_sql_checked,
+            tests::{fuzz_from_sql, fuzz_inputs, round_trip},
+        },
+        super::*,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<String>(&Type::TEXT);
+        fuzz_from_sql::<Vec<u8>>(&Type::BYTEA);
+
+        for input in fuzz_inputs() {
+            for format_code in [FormatCode::Binary, FormatCode::Text] {
+                let cx = Context::new(&Type::BYTEA, format_code);
+                let _ = from_sql_checked::<&[u8]>(&cx, Some(&input));
+                let cx = Context::new(&Type::VARCHAR, format_code);
+                let _ = from_sql_checked::<&str>(&cx, Some(&input));
+            }
+        }
+    }
+
+    #[test]
+    fn bytea_text()
+    {
+        let cx = Context::new(&Type::BYTEA, FormatCode::Text);
+        let decode = |raw: &[u8]| Vec::<u8>::from_sql(&cx, raw).ok();
+        assert_eq!(decode(b"\\x00ff10"), Some(vec![0x00, 0xFF, 0x10]));
+        assert_eq!(decode(b"\\x0"), None);
+        assert_eq!(decode(b"\\xzz"), None);
+        assert_eq!(decode(b"\\x+f"), None);
+        assert_eq!(decode(b"a\\\\b\\000\\377"), Some(b"a\\b\0\xFF".to_vec()));
+        assert_eq!(decode(b"\\400"), None);
+        assert_eq!(decode(b"\\"), None);
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for value in ["", "hello", "\u{1F418}"] {
+                round_trip(&Type::TEXT, format_code, &value.to_owned());
+            }
+            let all_bytes: Vec<u8> = (0 ..= 255).collect();
+            round_trip(&Type::BYTEA, format_code, &all_bytes);
+            round_trip(&Type::BYTEA, format_code, &Vec::<u8>::new());
+        }
+    }
+}
@@ -0,0 +1,784 @@
+//! Codecs for `tsvector` and `tsquery`.
+//!
+//! In binary format, a `tsvector` value is the number of lexemes,
+//! followed by each lexeme as a null-terminated string,
+//! the number of its positions, and each position with its weight.
+//! A `tsquery` value is the number of items, followed by the operators
+//! and operands in prefix order, where the right operand of a binary
+//! operator precedes its left operand.
+//! Text format is not supported.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{Context, FromSql, IsNull, ToSql, Type, invalid_value, text_value},
+};
+
+/// The greatest length of a lexeme in bytes that PostgreSQL supports.
+const MAX_WORD_LENGTH: usize = 2047;
+
+/// The greatest total length of the lexemes of a value
+/// that PostgreSQL supports.
+const MAX_TOTAL_LENGTH: usize = (1 << 20) - 1;
+
+/// Weight of a position of a lexeme.
+///
+/// The weights are ordered from [`D`], the default, to [`A`].
+///
+/// [`A`]: `Self::A`
+/// [`D`]: `Self::D`
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum TsWeight
+{
+    #[default]
+    D,
+    C,
+    B,
+    A,
+}
+
+/// Set of weights that a lexeme in a query is restricted to.
+///
+/// The empty set, which is the default, does not restrict the weight.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TsWeights(u8);
+
+/// Position of a lexeme in a document.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TsPosition
+{
+    /// The position, which must be in the range
+    /// `1 ..= `[`TsPosition::MAX`].
+    pub position: u16,
+
+    #[allow(missing_docs)]
+    pub weight: TsWeight,
+}
+
+/// Lexeme in a [`TsVector`], with its positions.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TsLexeme
+{
+    word: String,
+    positions: Vec<TsPosition>,
+}
+
+/// Value of type `tsvector`, which is a sorted list of distinct lexemes.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TsVector
+{
+    lexemes: Vec<TsLexeme>,
+}
+
+/// Node in the tree of operators and operands of a [`TsQuery`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum TsQueryNode
+{
+    /// Match a lexeme.
+    Lexeme
+    {
+        #[allow(missing_docs)]
+        word: String,
+
+        /// The weights that the lexeme is restricted to.
+        weights: TsWeights,
+
+        /// Whether the lexeme also matches lexemes it is a prefix of.
+        prefix: bool,
+    },
+
+    /// Match if the operand does not match, written `!`.
+    Not(Box<TsQueryNode>),
+
+    /// Match if both operands match, written `&`.
+    And(Box<TsQueryNode>, Box<TsQueryNode>),
+
+    /// Match if either operand matches, written `|`.
+    Or(Box<TsQueryNode>, Box<TsQueryNode>),
+
+    /// Match if the right operand matches at the given distance
+    /// after the left operand, written `<N>`, or `<->` for distance 1.
+    Phrase
+    {
+        #[allow(missing_docs)]
+        left: Box<TsQueryNode>,
+        #[allow(missing_docs)]
+        right: Box<TsQueryNode>,
+
+        /// The distance, which must not exceed [`TsQueryNode::MAX_DISTANCE`].
+        distance: u16,
+    },
+}
+
+/// Value of type `tsquery`.
+///
+/// The server sends queries without lexemes,
+/// but does not accept them in binary format.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TsQuery
+{
+    root: Option<TsQueryNode>,
+}
+
+impl TsWeight
+{
+    fn from_bits(bits: u16) -> Self
+    {
+        match bits & 3 {
+            0 => Self::D,
+            1 => Self::C,
+            2 => Self::B,
+            _ => Self::A,
+        }
+    }
+}
+
+impl TsWeights
+{
+    /// Whether the set contains the weight.
+    pub fn contains(self, weight: TsWeight) -> bool
+    {
+        self.0 & 1 << weight as u8 != 0
+    }
+
+    /// Add the weight to the set.
+    pub fn insert(&mut self, weight: TsWeight)
+    {
+        self.0 |= 1 << weight as u8;
+    }
+
+    /// Whether the set is empty, in which case
+    /// the weight is not restricted.
+    pub fn is_empty(self) -> bool
+    {
+        self.0 == 0
+    }
+}
+
+impl FromIterator<TsWeight> for TsWeights
+{
+    fn from_iter<I>(iter: I) -> Self
+        where I: IntoIterator<Item = TsWeight>
+    {
+        let mut weights = Self::default();
+        iter.into_iter().for_each(|weight| weights.insert(weight));
+        weights
+    }
+}
+
+impl TsPosition
+{
+    /// The greatest position that PostgreSQL supports.
+    pub const MAX: u16 = (1 << 14) - 1;
+
+    /// Create a position with the default weight.
+    pub fn new(position: u16) -> Self
+    {
+        Self{position, weight: TsWeight::D}
+    }
+
+    fn from_bits(bits: u16) -> Self
+    {
+        let weight = TsWeight::from_bits(bits >> 14);
+        Self{position: bits & Self::MAX, weight}
+    }
+
+    fn to_bits(self) -> u16
+    {
+        (self.weight as u16) << 14 | self.position
+    }
+}
+
+impl TsLexeme
+{
+    /// The greatest number of positions of a lexeme
+    /// that PostgreSQL supports.
+    pub const MAX_POSITIONS: usize = 256;
+
+    /// Create a lexeme from its word and positions.
+    ///
+    /// The word must not exceed 2047 bytes or contain null characters,
+    /// and each position must be in the range `1 ..= `[`TsPosition::MAX`].
+    /// Positions are sorted, and of duplicate positions only the one
+    /// with the greatest weight is kept, as PostgreSQL does.
+    /// There must be at most [`MAX_POSITIONS`] positions left.
+    ///
+    /// [`MAX_POSITIONS`]: `Self::MAX_POSITIONS`
+    pub fn new(word: String, mut positions: Vec<TsPosition>) -> Option<Self>
+    {
+        if word.len() > MAX_WORD_LENGTH || word.contains('\0') {
+            return None;
+        }
+        if positions.iter()
+            .any(|p| !(1 ..= TsPosition::MAX).contains(&p.position))
+        {
+            return None;
+        }
+        positions.sort_by(|a, b| {
+            a.position.cmp(&b.position).then(b.weight.cmp(&a.weight))
+        });
+        positions.dedup_by_key(|p| p.position);
+        if positions.len() > Self::MAX_POSITIONS {
+            return None;
+        }
+        Some(Self{word, positions})
+    }
+
+    /// The word of the lexeme.
+    pub fn word(&self) -> &str
+    {
+        &self.word
+    }
+
+    /// The positions of the lexeme, in ascending order.
+    pub fn positions(&self) -> &[TsPosition]
+    {
+        &self.positions
+    }
+
+    /// The space the lexeme takes up in a `tsvector` value,
+    /// which is limited to [`MAX_TOTAL_LENGTH`] in total.
+    fn stored_length(&self) -> usize
+    {
+        match self.positions.len() {
+            0 => self.word.len(),
+            n => self.word.len().next_multiple_of(2) + 2 * (n + 1),
+        }
+    }
+}
+
+impl TsVector
+{
+    /// Create a value from its lexemes.
+    ///
+    /// Lexemes are sorted, and the positions of duplicate lexemes
+    /// are merged, as PostgreSQL does. This fails if a merged lexeme
+    /// has too many positions, or if the lexemes and their positions
+    /// take up more than a mebibyte.
+    pub fn new(mut lexemes: Vec<TsLexeme>) -> Option<Self>
+    {
+        lexemes.sort_by(|a, b| a.word.cmp(&b.word));
+
+        let mut merged: Vec<TsLexeme> = Vec::with_capacity(lexemes.len());
+        for lexeme in lexemes {
+            match merged.last_mut() {
+                Some(last) if last.word == lexeme.word => {
+                    let mut positions = std::mem::take(&mut last.positions);
+                    positions.extend(lexeme.positions);
+                    *last = TsLexeme::new(lexeme.word, positions)?;
+                },
+                _ => merged.push(lexeme),
+            }
+        }
+
+        let total_length: usize =
+            merged.iter().map(TsLexeme::stored_length).sum();
+        if total_length > MAX_TOTAL_LENGTH {
+            return None;
+        }
+
+        Some(Self{lexemes: merged})
+    }
+
+    /// The lexemes, sorted by their words.
+    pub fn lexemes(&self) -> &[TsLexeme]
+    {
+        &self.lexemes
+    }
+}
+
+impl TsQueryNode
+{
+    /// The greatest distance of a phrase that PostgreSQL supports.
+    pub const MAX_DISTANCE: u16 = 1 << 14;
+
+    /// Create a node that matches the lexeme,
+    /// with any weight and not as a prefix.
+    pub fn lexeme(word: impl Into<String>) -> Self
+    {
+        Self::Lexeme{word: word.into(), weights: TsWeights::default(),
+                     prefix: false}
+    }
+
+    /// Iterate over the node and its descendants in the order in which
+    /// they are sent: each operator before its operands,
+    /// and the right operand before the left operand.
+    fn items(&self) -> impl Iterator<Item = &Self>
+    {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            match node {
+                Self::Lexeme{..} => (),
+                Self::Not(operand) => stack.push(operand),
+                Self::And(left, right) | Self::Or(left, right)
+                    | Self::Phrase{left, right, ..} =>
+                    stack.extend([&**left, &**right]),
+            }
+            Some(node)
+        })
+    }
+}
+
+impl TsQuery
+{
+    /// Create a value from the root of its tree,
+    /// or from `None` for a query without lexemes.
+    ///
+    /// The word of each lexeme must not exceed 2047 bytes or contain
+    /// null characters, the words must not take up more than a mebibyte,
+    /// and no distance may exceed [`TsQueryNode::MAX_DISTANCE`].
+    pub fn new(root: Option<TsQueryNode>) -> Option<Self>
+    {
+        let mut total_length = 0usize;
+        for node in root.iter().flat_map(TsQueryNode::items) {
+            match node {
+                TsQueryNode::Lexeme{word, ..} => {
+                    if word.len() > MAX_WORD_LENGTH || word.contains('\0') {
+                        return None;
+                    }
+                    total_length += word.len() + 1;
+                },
+                TsQueryNode::Phrase{distance, ..}
+                    if *distance > TsQueryNode::MAX_DISTANCE =>
+                    return None,
+                _ => (),
+            }
+        }
+        if total_length > MAX_TOTAL_LENGTH {
+            return None;
+        }
+        Some(Self{root})
+    }
+
+    /// The root of the tree, or `None` if the query has no lexemes.
+    pub fn root(&self) -> Option<&TsQueryNode>
+    {
+        self.root.as_ref()
+    }
+}
+
+/// Item types and operators in binary format.
+const ITEM_OPERAND: u8 = 1;
+const ITEM_OPERATOR: u8 = 2;
+const OPERATOR_NOT: u8 = 1;
+const OPERATOR_AND: u8 = 2;
+const OPERATOR_OR: u8 = 3;
+const OPERATOR_PHRASE: u8 = 4;
+
+/// Cursor over a binary value.
+struct Reader<'c, 'a>
+{
+    cx: &'c Context<'c>,
+    raw: &'a [u8],
+}
+
+impl<'a> Reader<'_, 'a>
+{
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N]>
+    {
+        let (bytes, rest) = self.raw.split_first_chunk::<N>()
+            .ok_or_else(|| invalid_value(self.cx, "malformed binary value"))?;
+        self.raw = rest;
+        Ok(*bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8>
+    {
+        self.bytes::<1>().map(|[byte]| byte)
+    }
+
+    fn u16(&mut self) -> Result<u16>
+    {
+        self.bytes().map(u16::from_be_bytes)
+    }
+
+    /// Read a count, which bounds the capacity to allocate for it,
+    /// given the least number of bytes each counted item takes.
+    fn count(&mut self, min_item_length: usize) -> Result<(usize, usize)>
+    {
+        let count = usize::try_from(i32::from_be_bytes(self.bytes()?))
+            .map_err(|_| invalid_value(self.cx, "negative count"))?;
+        Ok((count, count.min(self.raw.len() / min_item_length)))
+    }
+
+    /// Read a null-terminated string.
+    fn string(&mut self) -> Result<&'a str>
+    {
+        let length = self.raw.iter().position(|&byte| byte == 0)
+            .ok_or_else(|| invalid_value(self.cx, "malformed binary value"))?;
+        let string = text_value(self.cx, &self.raw[.. length])?;
+        self.raw = &self.raw[length + 1 ..];
+        Ok(string)
+    }
+
+    fn finish<T>(self, value: Option<T>) -> Result<T>
+    {
+        if !self.raw.is_empty() {
+            return Err(invalid_value(self.cx, "trailing data"));
+        }
+        value.ok_or_else(|| invalid_value(self.cx, "malformed binary value"))
+    }
+}
+
+fn write_count(cx: &Context, buf: &mut Vec<u8>, count: usize) -> Result<()>
+{
+    let count = i32::try_from(count)
+        .map_err(|_| invalid_value(cx, "too many items"))?;
+    buf.extend_from_slice(&count.to_be_bytes());
+    Ok(())
+}
+
+fn write_string(buf: &mut Vec<u8>, string: &str)
+{
+    buf.extend_from_slice(string.as_bytes());
+    buf.push(0);
+}
+
+/// In binary format only.
+impl ToSql for TsVector
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::TSVECTOR
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        if cx.format_code() == FormatCode::Text {
+            return Err(invalid_value(cx, "text format not supported"));
+        }
+        write_count(cx, buf, self.lexemes.len())?;
+        for lexeme in &self.lexemes {
+            write_string(buf, &lexeme.word);
+            // MAX_POSITIONS fits in a u16.
+            buf.extend_from_slice(&(lexeme.positions.len() as u16)
+                                      .to_be_bytes());
+            for position in &lexeme.positions {
+                buf.extend_from_slice(&position.to_bits().to_be_bytes());
+            }
+        }
+        Ok(IsNull::No)
+    }
+}
+
+/// In binary format only.
+impl<'a> FromSql<'a> for TsVector
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::TSVECTOR
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        if cx.format_code() == FormatCode::Text {
+            return Err(invalid_value(cx, "text format not supported"));
+        }
+
+        let mut reader = Reader{cx, raw};
+        // Each lexeme takes at least three bytes.
+        let (count, capacity) = reader.count(3)?;
+        let mut lexemes = Vec::with_capacity(capacity);
+        for _ in 0 .. count {
+            let word = reader.string()?.to_owned();
+            let positions = (0 .. reader.u16()?)
+                .map(|_| reader.u16().map(TsPosition::from_bits))
+                .collect::<Result<_>>()?;
+            let lexeme = TsLexeme::new(word, positions)
+                .ok_or_else(|| invalid_value(cx, "invalid lexeme"))?;
+            lexemes.push(lexeme);
+        }
+        reader.finish(TsVector::new(lexemes))
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+/// In binary format only.
+impl ToSql for TsQuery
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::TSQUERY
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        if cx.format_code() == FormatCode::Text {
+            return Err(invalid_value(cx, "text format not supported"));
+        }
+
+        let items = || self.root.iter().flat_map(TsQueryNode::items);
+        write_count(cx, buf, items().count())?;
+        for node in items() {
+            let operator = match node {
+                TsQueryNode::Lexeme{word, weights, prefix} => {
+                    buf.extend_from_slice(&[ITEM_OPERAND, weights.0,
+                                            u8::from(*prefix)]);
+                    write_string(buf, word);
+                    continue;
+                },
+                TsQueryNode::Not(..) => OPERATOR_NOT,
+                TsQueryNode::And(..) => OPERATOR_AND,
+                TsQueryNode::Or(..) => OPERATOR_OR,
+                TsQueryNode::Phrase{..} => OPERATOR_PHRASE,
+            };
+            buf.extend_from_slice(&[ITEM_OPERATOR, operator]);
+            if let TsQueryNode::Phrase{distance, ..} = node {
+                buf.extend_from_slice(&distance.to_be_bytes());
+            }
+        }
+        Ok(IsNull::No)
+    }
+}
+
+/// In binary format only.
+impl<'a> FromSql<'a> for TsQuery
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::TSQUERY
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        if cx.format_code() == FormatCode::Text {
+            return Err(invalid_value(cx, "text format not supported"));
+        }
+
+        /// Item as read, before it is attached to its operands.
+        enum Item
+        {
+            Node(TsQueryNode),
+            Operator(u8, u16),
+        }
+
+        let mut reader = Reader{cx, raw};
+        // Each item takes at least two bytes.
+        let (count, capacity) = reader.count(2)?;
+        let mut items = Vec::with_capacity(capacity);
+        for _ in 0 .. count {
+            let item = match reader.u8()? {
+                ITEM_OPERAND => {
+                    let weights = reader.u8()?;
+                    let prefix = reader.u8()? != 0;
+                    let word = reader.string()?.to_owned();
+                    if weights > 0xF {
+                        return Err(invalid_value(cx, "invalid weights"));
+                    }
+                    Item::Node(TsQueryNode::Lexeme{
+                        word,
+                        weights: TsWeights(weights),
+                        prefix,
+                    })
+                },
+                ITEM_OPERATOR => match reader.u8()? {
+                    OPERATOR_PHRASE => Item::Operator(OPERATOR_PHRASE,
+                                                      reader.u16()?),
+                    operator => Item::Operator(operator, 0),
+                },
+                _ => return Err(invalid_value(cx, "invalid item type")),
+            };
+            items.push(item);
+        }
+
+        // In reverse, operands precede their operators, with the
+        // right operand on top, so no recursion is needed.
+        let mut stack = Vec::new();
+        for item in items.into_iter().rev() {
+            let node = match item {
+                Item::Node(node) => node,
+                Item::Operator(operator, distance) => {
+                    let mut pop = || stack.pop().map(Box::new).ok_or_else(|| {
+                        invalid_value(cx, "missing operand")
+                    });
+                    if operator == OPERATOR_NOT {
+                        TsQueryNode::Not(pop()?)
+                    } else {
+                        let right = pop()?;
+                        let left = pop()?;
+                        match operator {
+                            OPERATOR_AND => TsQueryNode::And(left, right),
+                            OPERATOR_OR => TsQueryNode::Or(left, right),
+                            OPERATOR_PHRASE =>
+                                TsQueryNode::Phrase{left, right, distance},
+                            _ => return Err(invalid_value(cx,
+                                                          "invalid operator")),
+                        }
+                    }
+                },
+            };
+            stack.push(node);
+        }
+
+        let root = stack.pop();
+        let query = if stack.is_empty() { TsQuery::new(root) } else { None };
+        reader.finish(query)
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::tests::{fuzz_from_sql, round_trip},
+        super::*,
+    };
+
+    fn lexeme(word: &str, positions: &[(u16, TsWeight)]) -> TsLexeme
+    {
+        let positions = positions.iter()
+            .map(|&(position, weight)| TsPosition{position, weight})
+            .collect();
+        TsLexeme::new(word.to_owned(), positions).unwrap()
+    }
+
+    fn node(word: &str) -> Box<TsQueryNode>
+    {
+        Box::new(TsQueryNode::lexeme(word))
+    }
+
+    /// The query `'a':*AB & !'b' <2> 'c' | 'd'`.
+    fn query() -> TsQuery
+    {
+        let a = TsQueryNode::Lexeme{
+            word: "a".to_owned(),
+            weights: [TsWeight::A, TsWeight::B].into_iter().collect(),
+            prefix: true,
+        };
+        let phrase = TsQueryNode::Phrase{
+            left: Box::new(TsQueryNode::Not(node("b"))),
+            right: node("c"),
+            distance: 2,
+        };
+        let and = TsQueryNode::And(Box::new(a), Box::new(phrase));
+        TsQuery::new(Some(TsQueryNode::Or(Box::new(and), node("d")))).unwrap()
+    }
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<TsVector>(&Type::TSVECTOR);
+        fuzz_from_sql::<TsQuery>(&Type::TSQUERY);
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        use TsWeight::*;
+        let vector = TsVector::new(vec![
+            lexeme("b", &[(3, A), (1, D)]),
+            lexeme("a", &[(2, C), (5, D)]),
+            lexeme("cc", &[]),
+        ]).unwrap();
+        round_trip(&Type::TSVECTOR, FormatCode::Binary, &vector);
+        round_trip(&Type::TSVECTOR, FormatCode::Binary, &TsVector::default());
+        round_trip(&Type::TSQUERY, FormatCode::Binary, &query());
+        round_trip(&Type::TSQUERY, FormatCode::Binary, &TsQuery::default());
+    }
+
+    #[test]
+    fn binary()
+    {
+        // As sent by tsvectorsend and tsquerysend.
+        use TsWeight::*;
+        let vector = TsVector::new(vec![
+            lexeme("cc", &[]),
+            lexeme("b", &[(3, A), (1, D)]),
+            lexeme("a", &[(2, C), (5, D)]),
+        ]).unwrap();
+        let raw = b"\0\0\0\x03a\0\0\x02\x40\x02\0\x05b\0\0\x02\0\x01\xc0\x03\
+                    cc\0\0\0";
+        let cx = Context::new(&Type::TSVECTOR, FormatCode::Binary);
+        assert_eq!(TsVector::from_sql(&cx, raw).unwrap(), vector);
+
+        let raw = b"\0\0\0\x08\x02\x03\x01\0\0d\0\x02\x02\x02\x04\0\x02\
+                    \x01\0\0c\0\x02\x01\x01\0\0b\0\x01\x0c\x01a\0";
+        let cx = Context::new(&Type::TSQUERY, FormatCode::Binary);
+        assert_eq!(TsQuery::from_sql(&cx, raw).unwrap(), query());
+
+        // Operators without enough operands, and leftover operands.
+        for raw in [&b"\0\0\0\x01\x02\x01"[..],
+                    b"\0\0\0\x02\x02\x02\x01\0\0a\0",
+                    b"\0\0\0\x02\x01\0\0a\0\x01\0\0b\0"] {
+            assert!(TsQuery::from_sql(&cx, raw).is_err(), "{raw:?}");
+        }
+    }
+
+    #[test]
+    fn limits()
+    {
+        use TsWeight::*;
+
+        // Positions are sorted, keeping the greatest weight.
+        let merged = lexeme("a", &[(2, B), (1, D), (2, A), (2, C)]);
+        assert_eq!(merged.positions(),
+                   [TsPosition::new(1), TsPosition{position: 2, weight: A}]);
+        assert!(TsLexeme::new("a".to_owned(), vec![TsPosition::new(0)])
+                    .is_none());
+        assert!(TsLexeme::new("a".to_owned(),
+                              vec![TsPosition::new(TsPosition::MAX + 1)])
+                    .is_none());
+        assert!(TsLexeme::new("a\0".to_owned(), vec![]).is_none());
+        assert!(TsLexeme::new("a".repeat(2048), vec![]).is_none());
+        assert!(TsLexeme::new("a".repeat(2047), vec![]).is_some());
+        let positions = (1 ..= 257).map(TsPosition::new).collect();
+        assert!(TsLexeme::new("a".to_owned(), positions).is_none());
+
+        // Duplicate lexemes are merged, even if that makes
+        // for too many positions.
+        let vector = TsVector::new(vec![lexeme("b", &[]),
+                                        lexeme("a", &[(1, D)]),
+                                        lexeme("a", &[(3, B), (1, C)])])
+            .unwrap();
+        assert_eq!(vector.lexemes(),
+                   [lexeme("a", &[(1, C), (3, B)]), lexeme("b", &[])]);
+        let half = |range: std::ops::RangeInclusive<u16>| {
+            let positions = range.map(TsPosition::new).collect();
+            TsLexeme::new("a".to_owned(), positions).unwrap()
+        };
+        assert!(TsVector::new(vec![half(1 ..= 128), half(129 ..= 257)])
+                    .is_none());
+
+        // Values may take up a mebibyte.
+        let words = |count: usize, length: usize| {
+            (0 .. count)
+                .map(|i| {
+                    let word = format!("{i:0length$}");
+                    TsLexeme::new(word, vec![]).unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert!(TsVector::new(words(512, 2047)).is_some());
+        assert!(TsVector::new(words(513, 2047)).is_none());
+
+        let phrase = |distance| {
+            TsQuery::new(Some(TsQueryNode::Phrase{left: node("a"),
+                                                  right: node("b"),
+                                                  distance}))
+        };
+        assert!(phrase(TsQueryNode::MAX_DISTANCE).is_some());
+        assert!(phrase(TsQueryNode::MAX_DISTANCE + 1).is_none());
+        assert!(TsQuery::new(Some(*node("a\0"))).is_none());
+    }
+
+    #[test]
+    fn weights()
+    {
+        let weights: TsWeights = [TsWeight::A, TsWeight::C].into_iter()
+            .collect();
+        assert!(weights.contains(TsWeight::A));
+        assert!(!weights.contains(TsWeight::B));
+        assert!(!weights.is_empty());
+        assert!(TsWeights::default().is_empty());
+    }
+}
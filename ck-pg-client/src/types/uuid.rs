@@ -0,0 +1,152 @@
+//! Codec for `uuid`.
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{
+        Context,
+        FromSql,
+        IsNull,
+        ToSql,
+        Type,
+        fixed_length,
+        invalid_value,
+        text_value,
+    },
+    std::{fmt, io::Write, str::FromStr},
+    thiserror::Error,
+};
+
+/// Value of type `uuid`.
+///
+/// The bytes are in the order in which they are formatted,
+/// which is also the order in which they are sent in binary format.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Uuid(pub [u8; 16]);
+
+/// Error returned when parsing a [`Uuid`] fails.
+#[derive(Clone, Debug, Error)]
+#[error("invalid uuid literal")]
+pub struct ParseUuidError(());
+
+impl fmt::Display for Uuid
+{
+    /// Format the value the way PostgreSQL does,
+    /// such as `a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        for (i, byte) in self.0.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                f.write_str("-")?;
+            }
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Uuid
+{
+    type Err = ParseUuidError;
+
+    /// Parse 32 hexadecimal digits in either case, either with hyphens
+    /// the way [`Display`][`fmt::Display`] formats them, or without.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err>
+    {
+        let hyphens = [8, 13, 18, 23];
+        let digits: Vec<u8> = match s.len() {
+            32 => s.bytes().collect(),
+            36 if hyphens.iter().all(|&i| s.as_bytes()[i] == b'-') =>
+                s.bytes().enumerate()
+                    .filter(|(i, _)| !hyphens.contains(i))
+                    .map(|(_, b)| b)
+                    .collect(),
+            _ => return Err(ParseUuidError(())),
+        };
+        let mut bytes = [0; 16];
+        for (byte, pair) in bytes.iter_mut().zip(digits.chunks(2)) {
+            let hex = |b: u8| char::from(b).to_digit(16);
+            let (Some(high), Some(low)) = (hex(pair[0]), hex(pair[1]))
+                else { return Err(ParseUuidError(())) };
+            *byte = (high * 16 + low) as u8;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl ToSql for Uuid
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::UUID
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        match cx.format_code() {
+            FormatCode::Binary => buf.extend_from_slice(&self.0),
+            FormatCode::Text => write!(buf, "{self}")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl<'a> FromSql<'a> for Uuid
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        *ty == Type::UUID
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        match cx.format_code() {
+            FormatCode::Binary => fixed_length(cx, raw).map(Self),
+            FormatCode::Text => text_value(cx, raw)?.parse()
+                .map_err(|_| invalid_value(cx, "malformed text")),
+        }
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::tests::{fuzz_from_sql, round_trip},
+        super::*,
+    };
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<Uuid>(&Type::UUID);
+    }
+
+    #[test]
+    fn parse()
+    {
+        let text = "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11";
+        let uuid: Uuid = text.parse().unwrap();
+        assert_eq!(uuid.0[.. 4], [0xa0, 0xee, 0xbc, 0x99]);
+        assert_eq!(uuid.to_string(), text);
+        assert_eq!(text.to_uppercase().parse::<Uuid>().ok(), Some(uuid));
+        assert_eq!(text.replace('-', "").parse::<Uuid>().ok(), Some(uuid));
+        assert!(text.replace('-', "+").parse::<Uuid>().is_err());
+        assert!(text[1 ..].parse::<Uuid>().is_err());
+        assert!("é".repeat(16).parse::<Uuid>().is_err());
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for bytes in [[0; 16], [0xff; 16], *b"0123456789abcdef"] {
+                round_trip(&Type::UUID, format_code, &Uuid(bytes));
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Codec for `uuid` using the **uuid** crate.
+
+use {
+    crate::Result,
+    super::{Context, FromSql, IsNull, ToSql, Type, Uuid},
+};
+
+impl ToSql for ::uuid::Uuid
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <Uuid as ToSql>::accepts(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        Uuid(*self.as_bytes()).to_sql(cx, buf)
+    }
+}
+
+impl<'a> FromSql<'a> for ::uuid::Uuid
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        <Uuid as FromSql>::accepts(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        Uuid::from_sql(cx, raw).map(|uuid| Self::from_bytes(uuid.0))
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::{protocol::FormatCode, types::tests::round_trip},
+        super::*,
+    };
+
+    #[test]
+    fn round_trips()
+    {
+        let uuid =
+            ::uuid::Uuid::from_u128(0xa0eebc99_9c0b_4ef8_bb6d_6bb9bd380a11);
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            round_trip(&Type::UUID, format_code, &uuid);
+            round_trip(&Type::UUID, format_code, &::uuid::Uuid::nil());
+        }
+    }
+}
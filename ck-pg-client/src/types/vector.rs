@@ -0,0 +1,631 @@
+//! Codecs for `vector`, `halfvec`, and `sparsevec`
+//! from the **pgvector** extension.
+//!
+//! The OIDs of these types differ between databases,
+//! so the types are accepted by their names.
+//! Values of `vector` and `halfvec` are converted like
+//! one-dimensional arrays of `float4`, so they can be encoded from
+//! `&[f32]` and `Vec<f32>` and decoded into `Vec<f32>`.
+//! In binary format, they can also be borrowed as [`VectorRef`].
+//! They cannot be borrowed as `&[f32]`, as the elements are
+//! in network byte order and need not be aligned.
+//! Elements of `halfvec` are converted to and from half precision,
+//! rounding to nearest.
+//! In binary format, such a value is the number of elements
+//! and an unused field, both `int2`, followed by the elements.
+//! Values of `sparsevec` are converted to and from [`SparseVector`].
+
+use {
+    crate::{Result, protocol::FormatCode},
+    super::{
+        Array,
+        Context,
+        Decode,
+        Dimension,
+        FromSql,
+        IsNull,
+        ToSql,
+        Type,
+        from_sql_checked_with,
+        invalid_value,
+        text_value,
+    },
+    std::io::Write,
+};
+
+/// The element type of `vector` and `halfvec`.
+pub (super) static ELEMENT_TYPE: Type = Type::FLOAT4;
+
+/// Whether a type is `vector` or `halfvec`.
+pub (super) fn is_vector_type(ty: &Type) -> bool
+{
+    matches!(ty.name(), "vector" | "halfvec")
+}
+
+/// Whether the elements of the type are in half precision.
+fn is_half(ty: &Type) -> bool
+{
+    ty.name() == "halfvec"
+}
+
+/// Encode a value of type `vector` or `halfvec`,
+/// given the context of the elements.
+pub (super) fn write_vector<T>(
+    cx: &Context,
+    ecx: &Context,
+    dimensions: &[Dimension],
+    elements: &[T],
+    buf: &mut Vec<u8>,
+) -> Result<IsNull>
+    where T: ToSql
+{
+    if dimensions.len() > 1 {
+        return Err(invalid_value(cx, "array is multi-dimensional"));
+    }
+
+    let encode = |element: &T, buf: &mut Vec<u8>| -> Result<()> {
+        match element.to_sql(ecx, buf)? {
+            IsNull::No => Ok(()),
+            IsNull::Yes => Err(invalid_value(cx, "element is NULL")),
+        }
+    };
+
+    if cx.format_code() == FormatCode::Text {
+        buf.push(b'[');
+        for (i, element) in elements.iter().enumerate() {
+            if i != 0 {
+                buf.push(b',');
+            }
+            encode(element, buf)?;
+        }
+        buf.push(b']');
+        return Ok(IsNull::No);
+    }
+
+    let dimension = i16::try_from(elements.len())
+        .map_err(|_| invalid_value(cx, "too many elements"))?;
+    buf.extend_from_slice(&dimension.to_be_bytes());
+    buf.extend_from_slice(&0i16.to_be_bytes());
+    let mut element_buf = Vec::with_capacity(4);
+    for element in elements {
+        element_buf.clear();
+        encode(element, &mut element_buf)?;
+        let bits = <[u8; 4]>::try_from(element_buf.as_slice())
+            .map_err(|_| invalid_value(cx, "malformed element"))?;
+        if is_half(cx.ty()) {
+            let half = single_to_half(f32::from_be_bytes(bits));
+            buf.extend_from_slice(&half.to_be_bytes());
+        } else {
+            buf.extend_from_slice(&bits);
+        }
+    }
+    Ok(IsNull::No)
+}
+
+/// Decode a value of type `vector` or `halfvec`,
+/// given the context of the elements.
+pub (super) fn read_vector<'a, 'b, T>(
+    cx: &Context,
+    ecx: &Context,
+    raw: &'b [u8],
+    decode: Decode<'b, T>,
+) -> Result<Array<T>>
+    where T: FromSql<'a>
+{
+    let elements = match cx.format_code() {
+        FormatCode::Binary => read_vector_binary(cx, ecx, raw, decode)?,
+        FormatCode::Text => {
+            let literal = text_value(cx, raw)?.trim();
+            let inner = literal.strip_prefix('[')
+                .and_then(|literal| literal.strip_suffix(']'))
+                .ok_or_else(|| invalid_value(cx, "malformed text"))?;
+            if inner.trim().is_empty() {
+                Vec::new()
+            } else {
+                inner.split(',')
+                    .map(|element| {
+                        let element = element.trim().as_bytes();
+                        from_sql_checked_with(ecx, Some(element), decode)
+                    })
+                    .collect::<Result<_>>()?
+            }
+        },
+    };
+    Ok(Array::from(elements))
+}
+
+fn read_vector_binary<'a, 'b, T>(
+    cx: &Context,
+    ecx: &Context,
+    raw: &'b [u8],
+    decode: Decode<'b, T>,
+) -> Result<Vec<T>>
+    where T: FromSql<'a>
+{
+    let half = is_half(cx.ty());
+    vector_elements(cx, raw)?
+        .chunks_exact(if half { 2 } else { 4 })
+        .map(|bytes| {
+            if !half {
+                return from_sql_checked_with(ecx, Some(bytes), decode);
+            }
+            let value = read_element(bytes);
+            T::from_sql_temporary(ecx, &value.to_be_bytes())
+        })
+        .collect()
+}
+
+/// The elements of a value of type `vector` or `halfvec`
+/// in binary format, after checking the header.
+fn vector_elements<'b>(cx: &Context, raw: &'b [u8]) -> Result<&'b [u8]>
+{
+    let malformed = || invalid_value(cx, "malformed binary value");
+
+    let (header, raw) = raw.split_first_chunk::<4>().ok_or_else(malformed)?;
+    let dimension = usize::try_from(i16::from_be_bytes([header[0],
+                                                        header[1]]))
+        .map_err(|_| malformed())?;
+    if header[2 ..] != [0, 0] {
+        return Err(invalid_value(cx, "unused field is not zero"));
+    }
+
+    let element_length = if is_half(cx.ty()) { 2 } else { 4 };
+    if raw.len() != dimension * element_length {
+        return Err(malformed());
+    }
+    Ok(raw)
+}
+
+/// Decode an element of `vector` or `halfvec` in binary format,
+/// which is in half precision if it has two bytes.
+fn read_element(bytes: &[u8]) -> f32
+{
+    match *bytes {
+        [a, b] => half_to_single(u16::from_be_bytes([a, b])),
+        [a, b, c, d] => f32::from_be_bytes([a, b, c, d]),
+        _ => unreachable!(),
+    }
+}
+
+/// Convert half precision to single precision, which is exact.
+fn half_to_single(bits: u16) -> f32
+{
+    let sign = u32::from(bits & 0x8000) << 16;
+    let exponent = u32::from(bits >> 10 & 0x1F);
+    let mantissa = u32::from(bits & 0x3FF);
+    let single = match exponent {
+        // Zero or subnormal, whose mantissa is in units of 2^-24.
+        0 => (mantissa as f32 * 2.0f32.powi(-24)).to_bits(),
+        // Infinity or NaN.
+        0x1F => 0x7F80_0000 | mantissa << 13,
+        _ => (exponent + 127 - 15) << 23 | mantissa << 13,
+    };
+    f32::from_bits(sign | single)
+}
+
+/// Convert single precision to half precision,
+/// rounding to nearest with ties to even.
+fn single_to_half(value: f32) -> u16
+{
+    let bits = value.to_bits();
+    let sign = (bits >> 16 & 0x8000) as u16;
+    let exponent = (bits >> 23 & 0xFF) as i32;
+    let mantissa = bits & 0x7F_FFFF;
+
+    // Infinity or NaN, which is kept quiet.
+    if exponent == 0xFF {
+        let nan = if mantissa == 0 { 0 } else { 0x200 | mantissa >> 13 };
+        return sign | 0x7C00 | nan as u16;
+    }
+
+    // Round away the lowest `shift` bits of the mantissa,
+    // including the implicit leading bit. A carry out of
+    // the mantissa correctly increments the exponent.
+    let round = |mantissa: u32, shift: u32| {
+        let truncated = mantissa >> shift;
+        let remainder = mantissa & ((1 << shift) - 1);
+        let halfway = 1 << (shift - 1);
+        let up = remainder > halfway
+            || remainder == halfway && truncated & 1 == 1;
+        truncated + u32::from(up)
+    };
+
+    let exponent = exponent - 127 + 15;
+    let half = if exponent >= 0x1F {
+        0x7C00
+    } else if exponent > 0 {
+        round((exponent as u32) << 23 | mantissa, 13)
+    } else if exponent >= -10 {
+        round(0x80_0000 | mantissa, (14 - exponent) as u32)
+    } else {
+        0
+    };
+    sign | half as u16
+}
+
+/// Value of type `vector` or `halfvec` borrowed from a value
+/// in binary format, whose elements are converted on access.
+#[derive(Clone, Copy, Debug)]
+pub struct VectorRef<'a>
+{
+    elements: &'a [u8],
+    half: bool,
+}
+
+impl<'a> VectorRef<'a>
+{
+    fn element_length(&self) -> usize
+    {
+        if self.half { 2 } else { 4 }
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize
+    {
+        self.elements.len() / self.element_length()
+    }
+
+    /// Whether the vector has no elements.
+    pub fn is_empty(&self) -> bool
+    {
+        self.elements.is_empty()
+    }
+
+    /// The element with the given zero-based index.
+    pub fn get(&self, index: usize) -> Option<f32>
+    {
+        let length = self.element_length();
+        let start = index.checked_mul(length)?;
+        self.elements.get(start .. start + length).map(read_element)
+    }
+
+    /// Iterate over the elements.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = f32> + use<'a>
+    {
+        self.elements.chunks_exact(self.element_length()).map(read_element)
+    }
+
+    /// Copy the elements into a vector.
+    pub fn to_vec(&self) -> Vec<f32>
+    {
+        self.iter().collect()
+    }
+}
+
+/// Decoded from `vector` and `halfvec`. In binary format only.
+impl<'a> FromSql<'a> for VectorRef<'a>
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_vector_type(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        if cx.format_code() == FormatCode::Text {
+            return Err(invalid_value(cx, "text format not supported"));
+        }
+        let elements = vector_elements(cx, raw)?;
+        Ok(Self{elements, half: is_half(cx.ty())})
+    }
+}
+
+/// Value of type `sparsevec` from the **pgvector** extension,
+/// which is a vector that stores only its non-zero elements.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SparseVector
+{
+    dimension: usize,
+    entries: Vec<(usize, f32)>,
+}
+
+impl SparseVector
+{
+    /// The greatest dimension that pgvector supports.
+    pub const MAX_DIMENSION: usize = 1_000_000_000;
+
+    /// The greatest number of non-zero elements that pgvector supports.
+    pub const MAX_ENTRIES: usize = 16000;
+
+    /// Create a sparse vector from its dimension and its elements,
+    /// given as pairs of zero-based indexes and values.
+    ///
+    /// Elements are sorted by index, and elements that are zero
+    /// are removed. Fails if an index occurs twice or is out of range,
+    /// or if the dimension or the number of non-zero elements
+    /// exceeds [`MAX_DIMENSION`] or [`MAX_ENTRIES`].
+    ///
+    /// [`MAX_DIMENSION`]: `Self::MAX_DIMENSION`
+    /// [`MAX_ENTRIES`]: `Self::MAX_ENTRIES`
+    pub fn new(dimension: usize, mut entries: Vec<(usize, f32)>)
+        -> Option<Self>
+    {
+        entries.retain(|&(_, value)| value != 0.0);
+        entries.sort_by_key(|&(index, _)| index);
+        let valid = dimension <= Self::MAX_DIMENSION
+            && entries.len() <= Self::MAX_ENTRIES
+            && entries.iter().all(|&(index, _)| index < dimension)
+            && entries.windows(2).all(|pair| pair[0].0 != pair[1].0);
+        valid.then_some(Self{dimension, entries})
+    }
+
+    /// Create a sparse vector from the non-zero elements of a slice.
+    pub fn from_dense(values: &[f32]) -> Option<Self>
+    {
+        Self::new(values.len(), values.iter().copied().enumerate().collect())
+    }
+
+    /// The number of elements, including those that are zero.
+    pub fn dimension(&self) -> usize
+    {
+        self.dimension
+    }
+
+    /// The non-zero elements as pairs of zero-based indexes and values,
+    /// sorted by index.
+    pub fn entries(&self) -> &[(usize, f32)]
+    {
+        &self.entries
+    }
+
+    /// All elements, including those that are zero.
+    pub fn to_dense(&self) -> Vec<f32>
+    {
+        let mut values = vec![0.0; self.dimension];
+        for &(index, value) in &self.entries {
+            values[index] = value;
+        }
+        values
+    }
+}
+
+/// Whether a type is `sparsevec`.
+fn is_sparse_vector_type(ty: &Type) -> bool
+{
+    ty.name() == "sparsevec"
+}
+
+impl ToSql for SparseVector
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_sparse_vector_type(ty)
+    }
+
+    fn to_sql(&self, cx: &Context, buf: &mut Vec<u8>) -> Result<IsNull>
+    {
+        if cx.format_code() == FormatCode::Text {
+            // Indexes are one-based in text format.
+            buf.push(b'{');
+            for (i, (index, value)) in self.entries.iter().enumerate() {
+                if i != 0 {
+                    buf.push(b',');
+                }
+                write!(buf, "{}:{value}", index + 1)?;
+            }
+            write!(buf, "}}/{}", self.dimension)?;
+            return Ok(IsNull::No);
+        }
+
+        // MAX_DIMENSION and MAX_ENTRIES fit in an i32.
+        let write_i32 = |buf: &mut Vec<u8>, value: usize| {
+            buf.extend_from_slice(&(value as i32).to_be_bytes());
+        };
+        write_i32(buf, self.dimension);
+        write_i32(buf, self.entries.len());
+        write_i32(buf, 0);
+        for &(index, _) in &self.entries {
+            write_i32(buf, index);
+        }
+        for &(_, value) in &self.entries {
+            buf.extend_from_slice(&value.to_be_bytes());
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl<'a> FromSql<'a> for SparseVector
+{
+    fn accepts(ty: &Type) -> bool
+    {
+        is_sparse_vector_type(ty)
+    }
+
+    fn from_sql(cx: &Context, raw: &'a [u8]) -> Result<Self>
+    {
+        let (dimension, entries) = match cx.format_code() {
+            FormatCode::Binary => read_sparse_vector_binary(cx, raw)?,
+            FormatCode::Text => parse_sparse_vector(text_value(cx, raw)?)
+                .ok_or_else(|| invalid_value(cx, "malformed text"))?,
+        };
+        Self::new(dimension, entries)
+            .ok_or_else(|| invalid_value(cx, "invalid sparse vector"))
+    }
+
+    fn from_sql_temporary(cx: &Context, raw: &[u8]) -> Result<Self>
+    {
+        <Self as FromSql>::from_sql(cx, raw)
+    }
+}
+
+type Entries = Vec<(usize, f32)>;
+
+fn read_sparse_vector_binary(cx: &Context, mut raw: &[u8])
+    -> Result<(usize, Entries)>
+{
+    let malformed = || invalid_value(cx, "malformed binary value");
+    let mut read = || -> Result<[u8; 4]> {
+        let (bytes, rest) = raw.split_first_chunk::<4>()
+            .ok_or_else(malformed)?;
+        raw = rest;
+        Ok(*bytes)
+    };
+    let mut read_usize = || -> Result<usize> {
+        usize::try_from(i32::from_be_bytes(read()?)).map_err(|_| malformed())
+    };
+
+    let dimension = read_usize()?;
+    let count = read_usize()?;
+    if read_usize()? != 0 {
+        return Err(invalid_value(cx, "unused field is not zero"));
+    }
+    if raw.len() != count.checked_mul(8).ok_or_else(malformed)? {
+        return Err(malformed());
+    }
+
+    let (indexes, values) = raw.split_at(count * 4);
+    let entries = indexes.chunks_exact(4).zip(values.chunks_exact(4))
+        .map(|(index, value)| {
+            let index = i32::from_be_bytes(index.try_into().ok()?);
+            let value = f32::from_be_bytes(value.try_into().ok()?);
+            Some((usize::try_from(index).ok()?, value))
+        })
+        .collect::<Option<_>>()
+        .ok_or_else(malformed)?;
+    Ok((dimension, entries))
+}
+
+/// Parse a `sparsevec` literal, such as `{1:0.5,3:2}/5`,
+/// whose indexes are one-based.
+fn parse_sparse_vector(s: &str) -> Option<(usize, Entries)>
+{
+    let (entries, dimension) = s.trim().strip_prefix('{')?.split_once('}')?;
+    let dimension = dimension.trim_start().strip_prefix('/')?.trim().parse()
+        .ok()?;
+    if entries.trim().is_empty() {
+        return Some((dimension, Vec::new()));
+    }
+    let entries = entries.split(',')
+        .map(|entry| {
+            let (index, value) = entry.split_once(':')?;
+            let index = index.trim().parse::<usize>().ok()?.checked_sub(1)?;
+            Some((index, value.trim().parse().ok()?))
+        })
+        .collect::<Option<_>>()?;
+    Some((dimension, entries))
+}
+
+#[cfg(test)]
+mod tests
+{
+    use {
+        crate::types::{Kind, tests::{fuzz_from_sql, round_trip}},
+        super::*,
+    };
+
+    fn extension_type(name: &str) -> Type
+    {
+        Type::new(0, name.to_owned(), Kind::Simple)
+    }
+
+    #[test]
+    fn fuzz()
+    {
+        fuzz_from_sql::<Vec<f32>>(&extension_type("vector"));
+        fuzz_from_sql::<Vec<f32>>(&extension_type("halfvec"));
+        fuzz_from_sql::<SparseVector>(&extension_type("sparsevec"));
+    }
+
+    #[test]
+    fn round_trips()
+    {
+        let sparse = SparseVector::new(10, vec![(9, -1.5), (0, 0.25)])
+            .unwrap();
+        for format_code in [FormatCode::Binary, FormatCode::Text] {
+            for name in ["vector", "halfvec"] {
+                let ty = extension_type(name);
+                round_trip(&ty, format_code, &vec![1.5f32, -2.0, 0.0, 65504.0]);
+                round_trip(&ty, format_code, &vec![0.1f32; 0]);
+            }
+            round_trip(&extension_type("vector"), format_code,
+                       &vec![0.1f32, 1e-30, 3.4e38]);
+            round_trip(&extension_type("sparsevec"), format_code, &sparse);
+            round_trip(&extension_type("sparsevec"), format_code,
+                       &SparseVector::new(3, vec![]).unwrap());
+        }
+    }
+
+    #[test]
+    fn binary()
+    {
+        let decode = |name: &str, raw: &[u8]| {
+            let ty = extension_type(name);
+            let cx = Context::new(&ty, FormatCode::Binary);
+            Vec::<f32>::from_sql(&cx, raw)
+        };
+        assert_eq!(decode("vector", b"\0\x02\0\0\x3f\xc0\0\0\xc0\0\0\0")
+                       .unwrap(),
+                   [1.5, -2.0]);
+        assert_eq!(decode("halfvec", b"\0\x02\0\0\x3e\0\xc0\0").unwrap(),
+                   [1.5, -2.0]);
+        assert!(decode("vector", b"\0\x01\0\x01\x3f\xc0\0\0").is_err());
+        assert!(decode("vector", b"\0\x02\0\0\x3f\xc0\0\0").is_err());
+        assert!(decode("halfvec", b"\xff\xff\0\0").is_err());
+
+        let ty = extension_type("halfvec");
+        let cx = Context::new(&ty, FormatCode::Binary);
+        let vector = VectorRef::from_sql(&cx, b"\0\x02\0\0\x3e\0\xc0\0")
+            .unwrap();
+        assert_eq!(vector.len(), 2);
+        assert_eq!(vector.get(1), Some(-2.0));
+        assert_eq!(vector.get(2), None);
+        assert_eq!(vector.iter().collect::<Vec<_>>(), [1.5, -2.0]);
+        assert!(VectorRef::from_sql(&cx, b"\0\x02\0\0\x3e\0").is_err());
+
+        // Elements are rounded to the nearest half-precision value.
+        let ty = extension_type("halfvec");
+        let cx = Context::new(&ty, FormatCode::Binary);
+        let mut buf = Vec::new();
+        [0.1f32, 1e10].as_slice().to_sql(&cx, &mut buf).unwrap();
+        assert_eq!(buf, b"\0\x02\0\0\x2e\x66\x7c\0");
+
+        let ty = extension_type("sparsevec");
+        let cx = Context::new(&ty, FormatCode::Binary);
+        let raw = b"\0\0\0\x05\0\0\0\x02\0\0\0\0\0\0\0\x01\0\0\0\x03\
+                    \x3f\xc0\0\0\xc0\0\0\0";
+        assert_eq!(SparseVector::from_sql(&cx, raw).unwrap().to_dense(),
+                   [0.0, 1.5, 0.0, -2.0, 0.0]);
+        let raw = b"\0\0\0\x05\0\0\0\x01\0\0\0\0\0\0\0\x05\x3f\xc0\0\0";
+        assert!(SparseVector::from_sql(&cx, raw).is_err());
+    }
+
+    #[test]
+    fn text()
+    {
+        let ty = extension_type("vector");
+        let cx = Context::new(&ty, FormatCode::Text);
+        assert_eq!(Vec::<f32>::from_sql(&cx, b"[1, 2.5,-3]").unwrap(),
+                   [1.0, 2.5, -3.0]);
+        for raw in [&b"1,2"[..], b"[1,,2]", b"[1,2", b"[x]"] {
+            assert!(Vec::<f32>::from_sql(&cx, raw).is_err(), "{raw:?}");
+        }
+
+        let ty = extension_type("sparsevec");
+        let cx = Context::new(&ty, FormatCode::Text);
+        let sparse = SparseVector::from_sql(&cx, b"{1:0.5, 3:2}/4").unwrap();
+        assert_eq!(sparse.entries(), [(0, 0.5), (2, 2.0)]);
+        assert_eq!(sparse.dimension(), 4);
+        assert_eq!(SparseVector::from_sql(&cx, b"{}/2").unwrap(),
+                   SparseVector::new(2, vec![]).unwrap());
+        for raw in [&b"{0:1}/2"[..], b"{3:1}/2", b"{1:1,1:2}/2", b"{1:1}",
+                    b"{1:x}/2", b"[1:1]/2"] {
+            assert!(SparseVector::from_sql(&cx, raw).is_err(), "{raw:?}");
+        }
+    }
+
+    #[test]
+    fn sparse_vector()
+    {
+        let sparse = SparseVector::from_dense(&[0.0, 3.0, 0.0, 1.0]).unwrap();
+        assert_eq!(sparse.dimension(), 4);
+        assert_eq!(sparse.entries(), [(1, 3.0), (3, 1.0)]);
+        assert_eq!(sparse.to_dense(), [0.0, 3.0, 0.0, 1.0]);
+
+        assert!(SparseVector::new(2, vec![(2, 1.0)]).is_none());
+        assert!(SparseVector::new(2, vec![(1, 1.0), (1, 2.0)]).is_none());
+        assert!(SparseVector::new(SparseVector::MAX_DIMENSION + 1, vec![])
+                    .is_none());
+        let entries = (0 .. 16001).map(|i| (i, 1.0)).collect();
+        assert!(SparseVector::new(20000, entries).is_none());
+    }
+}